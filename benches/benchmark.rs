@@ -167,7 +167,8 @@ ops_factory!(
         BinOp {
             apply: |a: f64, b| a.powf(b),
             prio: 4,
-            is_commutative: false
+            is_commutative: false,
+            is_right_assoc: false
         }
     ),
     Operator::make_bin(
@@ -175,7 +176,8 @@ ops_factory!(
         BinOp {
             apply: |a, b| a * b,
             prio: 2,
-            is_commutative: true
+            is_commutative: true,
+            is_right_assoc: false
         }
     ),
     Operator::make_bin(
@@ -183,7 +185,8 @@ ops_factory!(
         BinOp {
             apply: |a, b| a / b,
             prio: 3,
-            is_commutative: false
+            is_commutative: false,
+            is_right_assoc: false
         }
     ),
     Operator::make_bin_unary(
@@ -191,7 +194,8 @@ ops_factory!(
         BinOp {
             apply: |a, b| a + b,
             prio: 0,
-            is_commutative: true
+            is_commutative: true,
+            is_right_assoc: false
         },
         |a| a
     ),
@@ -200,7 +204,8 @@ ops_factory!(
         BinOp {
             apply: |a, b| a - b,
             prio: 1,
-            is_commutative: false
+            is_commutative: false,
+            is_right_assoc: false
         },
         |a| -a
     ),
@@ -0,0 +1,18 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+
+use exmex::{MatchLiteral, NumberMatcher};
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(s) = std::str::from_utf8(data) {
+        if let Some(literal) = NumberMatcher::is_literal(s) {
+            let x = literal.parse::<f64>().unwrap();
+            // too large exponents such as in 1e999 result in infinity which is not a literal
+            if x.is_finite() {
+                let displayed = x.to_string();
+                assert_eq!(NumberMatcher::is_literal(&displayed), Some(displayed.as_str()));
+                assert_eq!(displayed.parse::<f64>().unwrap().to_bits(), x.to_bits());
+            }
+        }
+    }
+});
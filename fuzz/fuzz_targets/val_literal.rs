@@ -0,0 +1,46 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+
+use exmex::{MatchLiteral, Val, ValMatcher};
+
+/// Writes a value in literal notation or returns `None` for values without a literal such as
+/// infinite floats.
+fn to_literal(val: &Val) -> Option<String> {
+    match val {
+        Val::Int(i) => Some(i.to_string()),
+        // the debug representation of floats always contains a decimal point or an exponent
+        Val::Float(x) if x.is_finite() => Some(format!("{:?}", x)),
+        Val::Bool(b) => Some(b.to_string()),
+        Val::Str(s) => Some(format!("\"{}\"", s)),
+        Val::Array(a) => a
+            .iter()
+            .map(to_literal)
+            .collect::<Option<Vec<_>>>()
+            .map(|elts| format!("[{}]", elts.join(", "))),
+        _ => None,
+    }
+}
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(s) = std::str::from_utf8(data) {
+        if let Some(literal) = ValMatcher::is_literal(s) {
+            match literal.parse::<Val>() {
+                Ok(val) => {
+                    if let Some(displayed) = to_literal(&val) {
+                        assert_eq!(ValMatcher::is_literal(&displayed), Some(displayed.as_str()));
+                        let reparsed = displayed.parse::<Val>().unwrap();
+                        // debug strings distinguish ints from floats and -0.0 from 0.0
+                        assert_eq!(format!("{:?}", reparsed), format!("{:?}", val));
+                    }
+                }
+                // matched literals can only fail to parse if integers do not fit into an i32
+                Err(e) => assert!(
+                    e.msg().contains("Overflow") || e.msg().contains("does not fit"),
+                    "{}: {}",
+                    literal,
+                    e.msg()
+                ),
+            }
+        }
+    }
+});
@@ -0,0 +1,225 @@
+use std::{
+    cell::RefCell,
+    fmt::{self, Display, Formatter},
+    str::FromStr,
+};
+
+use astro_float::{Consts, Radix, RoundingMode};
+
+use crate::{
+    expression::MatchLiteral, format_exerr, literal_matcher_from_pattern, BinOp, ExError, FlatEx,
+    MakeOperators, Operator,
+};
+
+const RM: RoundingMode = RoundingMode::ToEven;
+
+thread_local! {
+    // cache of constants such as π that astro-float needs for many functions
+    static CONSTS: RefCell<Consts> = RefCell::new(
+        Consts::new().expect("could not initialize constants cache of astro-float"),
+    );
+}
+
+fn with_consts<R>(f: impl FnOnce(&mut Consts) -> R) -> R {
+    CONSTS.with(|cc| f(&mut cc.borrow_mut()))
+}
+
+/// *`feature = "bigfloat"`* - Binary floating point number with a precision of `P` bits based on
+/// the crate [astro-float](https://docs.rs/astro-float). In contrast to
+/// [`astro_float::BigFloat`](https://docs.rs/astro-float/latest/astro_float/struct.BigFloat.html),
+/// literals are parsed with the full precision `P`. Invalid operations such as the square root of
+/// a negative number result in NaN.
+#[derive(Clone, Debug, PartialEq, PartialOrd)]
+pub struct BigFloat<const P: usize = 256>(pub astro_float::BigFloat);
+
+impl<const P: usize> BigFloat<P> {
+    /// Returns whether the number is NaN.
+    pub fn is_nan(&self) -> bool {
+        self.0.is_nan()
+    }
+}
+
+impl<const P: usize> From<f64> for BigFloat<P> {
+    fn from(x: f64) -> Self {
+        BigFloat(astro_float::BigFloat::from_f64(x, P))
+    }
+}
+
+impl<const P: usize> FromStr for BigFloat<P> {
+    type Err = ExError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let x = with_consts(|cc| astro_float::BigFloat::parse(s, Radix::Dec, P, RM, cc));
+        if x.is_nan() {
+            Err(format_exerr!("cannot parse '{}' as big float", s))
+        } else {
+            Ok(BigFloat(x))
+        }
+    }
+}
+
+impl<const P: usize> Display for BigFloat<P> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// *`feature = "bigfloat"`* - Factory of default operators for [`BigFloat`](BigFloat)s with a precision of `P` bits.
+/// Operators that are not in the list such as `signum` are not available for big floats and their
+/// usage leads to an error during parsing.
+///
+/// |representation|description|
+/// |--------------|-----------|
+/// |`^`| power |
+/// |`*`| product |
+/// |`/`| division |
+/// |`+`| addition as binary or identity as unary operator|
+/// |`-`| subtraction as binary or inverting the sign as unary operator |
+/// |`abs`| absolute value |
+/// |`floor`| largest integer less than or equal to a number |
+/// |`ceil`| smallest integer greater than or equal to a number |
+/// |`trunc`| integer part of a number |
+/// |`fract`| fractional part of a number |
+/// |`sqrt`| square root |
+/// |`cbrt`| cube root |
+/// |`exp`| exponential function |
+/// |`ln`| natural logarithm |
+/// |`log2`| logarithm with basis 2 |
+/// |`log10`| logarithm with basis 10 |
+/// |`sin`| sine |
+/// |`cos`| cosine |
+/// |`tan`| tangent |
+/// |`asin`| inverse sine |
+/// |`acos`| inverse cosine |
+/// |`atan`| inverse tangent |
+/// |`sinh`| hyperbolic sine |
+/// |`cosh`| hyperbolic cosine |
+/// |`tanh`| hyperbolic tangent |
+/// |`PI`| constant π |
+/// |`π`| second representation of constant π |
+/// |`E`| Euler's number |
+/// |`e`| second representation of Euler's number |
+///
+/// ```rust
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// #
+/// use exmex::prelude::*;
+/// use exmex::{BigFloat, FlatExBigFloat};
+/// let expr = FlatExBigFloat::<256>::from_str("sqrt(2)^2 - x")?;
+/// let res = expr.eval(&[BigFloat::from_str("2")?])?;
+/// assert!(res.0.abs() < BigFloat::<256>::from_str("1e-70")?.0);
+/// assert!(FlatExBigFloat::<256>::from_str("signum(x)").is_err());
+/// #
+/// #     Ok(())
+/// # }
+/// ```
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Debug)]
+pub struct BigFloatOpsFactory<const P: usize = 256>;
+
+macro_rules! unary_with_consts {
+    ($repr:literal, $name:ident) => {
+        Operator::make_unary($repr, |a: BigFloat<P>| {
+            BigFloat(with_consts(|cc| a.0.$name(P, RM, cc)))
+        })
+    };
+}
+
+impl<const P: usize> MakeOperators<BigFloat<P>> for BigFloatOpsFactory<P> {
+    /// Returns the default operators.
+    fn make<'a>() -> Vec<Operator<'a, BigFloat<P>>> {
+        let (pi, e) = with_consts(|cc| (cc.pi(P, RM), cc.e(P, RM)));
+        vec![
+            Operator::make_bin(
+                "^",
+                BinOp {
+                    apply: |a, b| BigFloat(with_consts(|cc| a.0.pow(&b.0, P, RM, cc))),
+                    prio: 4,
+                    is_commutative: false,
+                    is_right_assoc: true,
+                },
+            ),
+            Operator::make_bin(
+                "*",
+                BinOp {
+                    apply: |a, b| BigFloat(a.0.mul(&b.0, P, RM)),
+                    prio: 2,
+                    is_commutative: true,
+                    is_right_assoc: false,
+                },
+            ),
+            Operator::make_bin(
+                "/",
+                BinOp {
+                    apply: |a, b| BigFloat(a.0.div(&b.0, P, RM)),
+                    prio: 3,
+                    is_commutative: false,
+                    is_right_assoc: false,
+                },
+            ),
+            Operator::make_bin_unary(
+                "+",
+                BinOp {
+                    apply: |a, b| BigFloat(a.0.add(&b.0, P, RM)),
+                    prio: 0,
+                    is_commutative: true,
+                    is_right_assoc: false,
+                },
+                |a| a,
+            ),
+            Operator::make_bin_unary(
+                "-",
+                BinOp {
+                    apply: |a, b| BigFloat(a.0.sub(&b.0, P, RM)),
+                    prio: 1,
+                    is_commutative: false,
+                    is_right_assoc: false,
+                },
+                |a| BigFloat(a.0.neg()),
+            ),
+            Operator::make_unary("abs", |a| BigFloat(a.0.abs())),
+            Operator::make_unary("floor", |a| BigFloat(a.0.floor())),
+            Operator::make_unary("ceil", |a| BigFloat(a.0.ceil())),
+            Operator::make_unary("trunc", |a| BigFloat(a.0.int())),
+            Operator::make_unary("fract", |a| BigFloat(a.0.fract())),
+            Operator::make_unary("sqrt", |a| BigFloat(a.0.sqrt(P, RM))),
+            Operator::make_unary("cbrt", |a| BigFloat(a.0.cbrt(P, RM))),
+            unary_with_consts!("exp", exp),
+            unary_with_consts!("ln", ln),
+            unary_with_consts!("log2", log2),
+            unary_with_consts!("log10", log10),
+            unary_with_consts!("sin", sin),
+            unary_with_consts!("cos", cos),
+            unary_with_consts!("tan", tan),
+            unary_with_consts!("asin", asin),
+            unary_with_consts!("acos", acos),
+            unary_with_consts!("atan", atan),
+            unary_with_consts!("sinh", sinh),
+            unary_with_consts!("cosh", cosh),
+            unary_with_consts!("tanh", tanh),
+            Operator::make_constant("PI", BigFloat(pi.clone())),
+            Operator::make_constant("π", BigFloat(pi)),
+            Operator::make_constant("E", BigFloat(e.clone())),
+            Operator::make_constant("e", BigFloat(e)),
+        ]
+    }
+}
+
+// Floats possibly in scientific notation such as 1.5e-30.
+const PATTERN: &str = r"^([0-9]+(\.[0-9]+)?|\.[0-9]+)([eE][+-]?[0-9]+)?";
+literal_matcher_from_pattern!(BigFloatMatcher, PATTERN);
+
+/// *`feature = "bigfloat"`* - Alias for [`FlatEx`](FlatEx) with [`BigFloat`](BigFloat) as data type, [`BigFloatOpsFactory`](BigFloatOpsFactory)
+/// as operator factory, and [`BigFloatMatcher`](BigFloatMatcher) that also matches literals in
+/// scientific notation.
+pub type FlatExBigFloat<const P: usize = 256> =
+    FlatEx<BigFloat<P>, BigFloatOpsFactory<P>, BigFloatMatcher>;
+
+#[test]
+fn test_big_float() {
+    let x = BigFloat::<512>::from_str("0.1").unwrap();
+    assert_eq!(x.0.precision(), Some(512));
+    assert!(BigFloat::<128>::from_str("x").is_err());
+    assert_eq!(BigFloatMatcher::is_literal("1.5e-30*x"), Some("1.5e-30"));
+    assert_eq!(BigFloatMatcher::is_literal("2e+x"), Some("2"));
+    assert_eq!(BigFloatMatcher::is_literal("e"), None);
+}
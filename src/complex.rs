@@ -0,0 +1,174 @@
+use std::marker::PhantomData;
+
+use num::{Complex, Float};
+
+use crate::{
+    expression::MatchLiteral, literal_matcher_from_pattern, BinOp, FlatEx, MakeOperators, Operator,
+};
+
+/// Factory of default operators for complex numbers with real and imaginary parts of type `T`.
+/// Operators that map to real numbers such as `abs` return a complex number with imaginary
+/// part zero.
+///
+/// |representation|description|
+/// |--------------|-----------|
+/// |`^`| power |
+/// |`*`| product |
+/// |`/`| division |
+/// |`+`| addition as binary or identity as unary operator|
+/// |`-`| subtraction as binary or inverting the sign as unary operator |
+/// |`abs`| absolute value |
+/// |`arg`| argument, i.e., the angle in polar form |
+/// |`conj`| complex conjugate |
+/// |`re`| real part |
+/// |`im`| imaginary part |
+/// |`sin`| sine |
+/// |`cos`| cosine |
+/// |`tan`| tangent |
+/// |`asin`| inverse sine |
+/// |`acos`| inverse cosine |
+/// |`atan`| inverse tangent |
+/// |`sinh`| hyperbolic sine |
+/// |`cosh`| hyperbolic cosine |
+/// |`tanh`| hyperbolic tangent |
+/// |`exp`| exponential function |
+/// |`sqrt`| principal square root |
+/// |`ln`| principal natural logarithm |
+/// |`log2`| logarithm with basis 2 |
+/// |`log10`| logarithm with basis 10 |
+/// |`PI`| constant π |
+/// |`π`| second representation of constant π |
+/// |`TAU`| constant τ=2π |
+/// |`τ`| second representation of constant τ |
+/// |`E`| Euler's number |
+/// |`e`| second representation of Euler's number |
+///
+/// Imaginary literals such as `2i`, `0.5j`, or `i` are matched by [`ComplexMatcher`](ComplexMatcher).
+/// With the feature `partial`, expressions that only contain holomorphic operators can be
+/// differentiated. In contrast, `abs`, `arg`, `conj`, `re`, and `im` are not differentiable.
+/// ```rust
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// #
+/// use exmex::prelude::*;
+/// use exmex::FlatExComplex;
+/// use num::Complex;
+/// let expr = FlatExComplex::<f64>::from_str("conj(z) * (2 + 3i) + abs(4i - 3)")?;
+/// let res = expr.eval(&[Complex::new(1.0, 1.0)])?;
+/// assert!((res - Complex::new(10.0, 1.0)).norm() < 1e-12);
+/// #
+/// #     Ok(())
+/// # }
+/// ```
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Debug)]
+pub struct ComplexOpsFactory<T: Float> {
+    dummy: PhantomData<T>,
+}
+
+fn real<T: Float>(x: T) -> Complex<T> {
+    Complex::new(x, T::zero())
+}
+
+impl<T: Float> MakeOperators<Complex<T>> for ComplexOpsFactory<T> {
+    /// Returns the default operators.
+    fn make<'a>() -> Vec<Operator<'a, Complex<T>>> {
+        let constant = |x: f64| real(T::from(x).unwrap());
+        vec![
+            Operator::make_bin(
+                "^",
+                BinOp {
+                    apply: |a, b| a.powc(b),
+                    prio: 4,
+                    is_commutative: false,
+                    is_right_assoc: true,
+                },
+            ),
+            Operator::make_bin(
+                "*",
+                BinOp {
+                    apply: |a, b| a * b,
+                    prio: 2,
+                    is_commutative: true,
+                    is_right_assoc: false,
+                },
+            ),
+            Operator::make_bin(
+                "/",
+                BinOp {
+                    apply: |a, b| a / b,
+                    prio: 3,
+                    is_commutative: false,
+                    is_right_assoc: false,
+                },
+            ),
+            Operator::make_bin_unary(
+                "+",
+                BinOp {
+                    apply: |a, b| a + b,
+                    prio: 0,
+                    is_commutative: true,
+                    is_right_assoc: false,
+                },
+                |a| a,
+            ),
+            Operator::make_bin_unary(
+                "-",
+                BinOp {
+                    apply: |a, b| a - b,
+                    prio: 1,
+                    is_commutative: false,
+                    is_right_assoc: false,
+                },
+                // in contrast to -a, this does not create a negative zero imaginary part
+                // such that, e.g., sqrt(-4) is on the principal branch
+                |a| real(T::zero()) - a,
+            ),
+            Operator::make_unary("abs", |a| real(a.norm())),
+            Operator::make_unary("arg", |a| real(a.arg())),
+            Operator::make_unary("conj", |a| a.conj()),
+            Operator::make_unary("re", |a| real(a.re)),
+            Operator::make_unary("im", |a| real(a.im)),
+            Operator::make_unary("sin", |a| a.sin()),
+            Operator::make_unary("cos", |a| a.cos()),
+            Operator::make_unary("tan", |a| a.tan()),
+            Operator::make_unary("asin", |a| a.asin()),
+            Operator::make_unary("acos", |a| a.acos()),
+            Operator::make_unary("atan", |a| a.atan()),
+            Operator::make_unary("sinh", |a| a.sinh()),
+            Operator::make_unary("cosh", |a| a.cosh()),
+            Operator::make_unary("tanh", |a| a.tanh()),
+            Operator::make_unary("exp", |a| a.exp()),
+            Operator::make_unary("sqrt", |a| a.sqrt()),
+            Operator::make_unary("ln", |a| a.ln()),
+            Operator::make_unary("log2", |a| a.log(T::from(2.0).unwrap())),
+            Operator::make_unary("log10", |a| a.log(T::from(10.0).unwrap())),
+            Operator::make_constant("PI", constant(std::f64::consts::PI)),
+            Operator::make_constant("π", constant(std::f64::consts::PI)),
+            Operator::make_constant("E", constant(std::f64::consts::E)),
+            Operator::make_constant("e", constant(std::f64::consts::E)),
+            Operator::make_constant("TAU", constant(std::f64::consts::TAU)),
+            Operator::make_constant("τ", constant(std::f64::consts::TAU)),
+        ]
+    }
+}
+
+// Real numbers and imaginary numbers with suffix i or j. The imaginary unit can stand alone
+// but must not be the beginning of a name such as im.
+const PATTERN: &str = r"^(([0-9]+(\.[0-9]+)?|\.[0-9]+)([ij]\b)?|[ij]\b)";
+literal_matcher_from_pattern!(ComplexMatcher, PATTERN);
+
+/// Alias for [`FlatEx`](FlatEx) with complex numbers as data type, [`ComplexOpsFactory`](ComplexOpsFactory)
+/// as operator factory, and [`ComplexMatcher`](ComplexMatcher) as literal matcher.
+pub type FlatExComplex<T> = FlatEx<Complex<T>, ComplexOpsFactory<T>, ComplexMatcher>;
+
+#[test]
+fn test_complex_matcher() {
+    assert_eq!(ComplexMatcher::is_literal("2i*x"), Some("2i"));
+    assert_eq!(ComplexMatcher::is_literal("2.5j"), Some("2.5j"));
+    assert_eq!(ComplexMatcher::is_literal(".5+1"), Some(".5"));
+    assert_eq!(ComplexMatcher::is_literal("i+1"), Some("i"));
+    assert_eq!(ComplexMatcher::is_literal("3*i"), Some("3"));
+    assert_eq!(ComplexMatcher::is_literal("2im"), Some("2"));
+    assert_eq!(ComplexMatcher::is_literal("im(z)"), None);
+    assert_eq!(ComplexMatcher::is_literal("x"), None);
+}
@@ -0,0 +1,240 @@
+use std::{
+    fmt::{self, Display, Formatter},
+    str::FromStr,
+};
+
+use num::Signed;
+use rust_decimal::{Decimal, MathematicalOps};
+
+use crate::{
+    format_exerr, BinOp, ExError, ExResult, FlatEx, MakeOperators, NumberMatcher, Operator,
+};
+
+/// *`feature = "decimal"`* - Decimal of type
+/// [`Decimal`](https://docs.rs/rust_decimal/latest/rust_decimal/struct.Decimal.html) whose operations
+/// are checked by the operators of [`DecimalOpsFactory`](DecimalOpsFactory). Decimals can neither
+/// represent infinity nor NaN. Since operators cannot return errors, invalid operations such as
+/// divisions by zero, overflows, or the square root of a negative number result in the variant
+/// `Error` instead of a panic. Similar to [`Val`](crate::Val), the error can be transformed into a
+/// `Result` via [`to_result`](CheckedDecimal::to_result).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CheckedDecimal {
+    Value(Decimal),
+    Error(ExError),
+}
+
+impl CheckedDecimal {
+    /// Returns the decimal or the contained error.
+    pub fn to_result(self) -> ExResult<Decimal> {
+        match self {
+            CheckedDecimal::Value(x) => Ok(x),
+            CheckedDecimal::Error(e) => Err(e),
+        }
+    }
+}
+
+impl From<Decimal> for CheckedDecimal {
+    fn from(x: Decimal) -> Self {
+        CheckedDecimal::Value(x)
+    }
+}
+
+impl Display for CheckedDecimal {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            CheckedDecimal::Value(x) => write!(f, "{}", x),
+            CheckedDecimal::Error(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl FromStr for CheckedDecimal {
+    type Err = ExError;
+    fn from_str(s: &str) -> ExResult<Self> {
+        Decimal::from_str(s)
+            .map(CheckedDecimal::Value)
+            .map_err(|_| format_exerr!("cannot parse '{}' as decimal", s))
+    }
+}
+
+fn checked_bin(
+    a: CheckedDecimal,
+    b: CheckedDecimal,
+    op: &str,
+    f: fn(Decimal, Decimal) -> Option<Decimal>,
+) -> CheckedDecimal {
+    match (a, b) {
+        (CheckedDecimal::Value(a), CheckedDecimal::Value(b)) => match f(a, b) {
+            Some(x) => CheckedDecimal::Value(x),
+            None if b.is_zero() && (op == "/" || op == "%") => {
+                CheckedDecimal::Error(format_exerr!("division by zero in {} {} {}", a, op, b))
+            }
+            None => CheckedDecimal::Error(format_exerr!("overflow in {} {} {}", a, op, b)),
+        },
+        (CheckedDecimal::Error(e), _) | (_, CheckedDecimal::Error(e)) => CheckedDecimal::Error(e),
+    }
+}
+
+fn checked_unary(
+    a: CheckedDecimal,
+    op: &str,
+    f: fn(Decimal) -> Option<Decimal>,
+) -> CheckedDecimal {
+    match a {
+        CheckedDecimal::Value(a) => match f(a) {
+            Some(x) => CheckedDecimal::Value(x),
+            None => CheckedDecimal::Error(format_exerr!("cannot compute {}({})", op, a)),
+        },
+        CheckedDecimal::Error(e) => CheckedDecimal::Error(e),
+    }
+}
+
+fn map(a: CheckedDecimal, f: fn(Decimal) -> Decimal) -> CheckedDecimal {
+    match a {
+        CheckedDecimal::Value(a) => CheckedDecimal::Value(f(a)),
+        CheckedDecimal::Error(e) => CheckedDecimal::Error(e),
+    }
+}
+
+/// *`feature = "decimal"`* - Factory of default operators for the decimal type
+/// [`Decimal`](https://docs.rs/rust_decimal/latest/rust_decimal/struct.Decimal.html) of the crate
+/// [rust_decimal](https://docs.rs/rust_decimal). Decimal literals such as `0.1` are represented exactly.
+/// Operators that are not in the list such as `asin` are not available for decimals and their
+/// usage leads to an error during parsing.
+///
+/// |representation|description|
+/// |--------------|-----------|
+/// |`^`| power |
+/// |`*`| product |
+/// |`/`| division |
+/// |`%`| remainder |
+/// |`+`| addition as binary or identity as unary operator|
+/// |`-`| subtraction as binary or inverting the sign as unary operator |
+/// |`abs`| absolute value |
+/// |`signum`| signum |
+/// |`floor`| largest integer less than or equal to a number |
+/// |`ceil`| smallest integer greater than or equal to a number |
+/// |`round`| nearest integer, rounding half-way cases to even |
+/// |`trunc`| integer part of a number |
+/// |`fract`| fractional part of a number |
+/// |`sqrt`| square root |
+/// |`exp`| exponential function |
+/// |`ln`| natural logarithm |
+/// |`sin`| sine |
+/// |`cos`| cosine |
+/// |`tan`| tangent |
+/// |`PI`| constant π |
+/// |`π`| second representation of constant π |
+/// |`TAU`| constant τ=2π |
+/// |`τ`| second representation of constant τ |
+/// |`E`| Euler's number |
+/// |`e`| second representation of Euler's number |
+///
+/// All operations are checked. Invalid operations such as a division by zero, overflows, or the
+/// square root of a negative number result in an error of the [`CheckedDecimal`](CheckedDecimal).
+/// ```rust
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// #
+/// use exmex::prelude::*;
+/// use exmex::{CheckedDecimal, FlatExDecimal};
+/// let expr = FlatExDecimal::from_str("0.1 + x")?;
+/// let res = expr.eval(&[CheckedDecimal::from_str("0.2")?])?;
+/// assert_eq!(res, CheckedDecimal::from_str("0.3")?);
+/// let err = FlatExDecimal::from_str("1 / 0")?.eval(&[])?.to_result().unwrap_err();
+/// assert!(err.msg().contains("division by zero"));
+/// assert!(FlatExDecimal::from_str("asin(0.1)").is_err());
+/// #
+/// #     Ok(())
+/// # }
+/// ```
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Debug)]
+pub struct DecimalOpsFactory;
+
+impl MakeOperators<CheckedDecimal> for DecimalOpsFactory {
+    /// Returns the default operators.
+    fn make<'a>() -> Vec<Operator<'a, CheckedDecimal>> {
+        vec![
+            Operator::make_bin(
+                "^",
+                BinOp {
+                    apply: |a, b| checked_bin(a, b, "^", |a, b| a.checked_powd(b)),
+                    prio: 4,
+                    is_commutative: false,
+                    is_right_assoc: true,
+                },
+            ),
+            Operator::make_bin(
+                "*",
+                BinOp {
+                    apply: |a, b| checked_bin(a, b, "*", |a, b| a.checked_mul(b)),
+                    prio: 2,
+                    is_commutative: true,
+                    is_right_assoc: false,
+                },
+            ),
+            Operator::make_bin(
+                "/",
+                BinOp {
+                    apply: |a, b| checked_bin(a, b, "/", |a, b| a.checked_div(b)),
+                    prio: 3,
+                    is_commutative: false,
+                    is_right_assoc: false,
+                },
+            ),
+            Operator::make_bin(
+                "%",
+                BinOp {
+                    apply: |a, b| checked_bin(a, b, "%", |a, b| a.checked_rem(b)),
+                    prio: 3,
+                    is_commutative: false,
+                    is_right_assoc: false,
+                },
+            ),
+            Operator::make_bin_unary(
+                "+",
+                BinOp {
+                    apply: |a, b| checked_bin(a, b, "+", |a, b| a.checked_add(b)),
+                    prio: 0,
+                    is_commutative: true,
+                    is_right_assoc: false,
+                },
+                |a| a,
+            ),
+            Operator::make_bin_unary(
+                "-",
+                BinOp {
+                    apply: |a, b| checked_bin(a, b, "-", |a, b| a.checked_sub(b)),
+                    prio: 1,
+                    is_commutative: false,
+                    is_right_assoc: false,
+                },
+                |a| map(a, |a| -a),
+            ),
+            Operator::make_unary("abs", |a| map(a, |a| a.abs())),
+            Operator::make_unary("signum", |a| map(a, |a| a.signum())),
+            Operator::make_unary("floor", |a| map(a, |a| a.floor())),
+            Operator::make_unary("ceil", |a| map(a, |a| a.ceil())),
+            Operator::make_unary("round", |a| map(a, |a| a.round())),
+            Operator::make_unary("trunc", |a| map(a, |a| a.trunc())),
+            Operator::make_unary("fract", |a| map(a, |a| a.fract())),
+            Operator::make_unary("sqrt", |a| checked_unary(a, "sqrt", |a| a.sqrt())),
+            Operator::make_unary("exp", |a| checked_unary(a, "exp", |a| a.checked_exp())),
+            Operator::make_unary("ln", |a| checked_unary(a, "ln", |a| a.checked_ln())),
+            Operator::make_unary("sin", |a| checked_unary(a, "sin", |a| a.checked_sin())),
+            Operator::make_unary("cos", |a| checked_unary(a, "cos", |a| a.checked_cos())),
+            Operator::make_unary("tan", |a| checked_unary(a, "tan", |a| a.checked_tan())),
+            Operator::make_constant("PI", CheckedDecimal::Value(Decimal::PI)),
+            Operator::make_constant("π", CheckedDecimal::Value(Decimal::PI)),
+            Operator::make_constant("E", CheckedDecimal::Value(Decimal::E)),
+            Operator::make_constant("e", CheckedDecimal::Value(Decimal::E)),
+            Operator::make_constant("TAU", CheckedDecimal::Value(Decimal::TWO_PI)),
+            Operator::make_constant("τ", CheckedDecimal::Value(Decimal::TWO_PI)),
+        ]
+    }
+}
+
+/// *`feature = "decimal"`* - Alias for [`FlatEx`](FlatEx) with [`CheckedDecimal`](CheckedDecimal)
+/// as data type and [`DecimalOpsFactory`](DecimalOpsFactory) as operator factory. Decimal literals
+/// are matched by the default [`NumberMatcher`](NumberMatcher).
+pub type FlatExDecimal = FlatEx<CheckedDecimal, DecimalOpsFactory, NumberMatcher>;
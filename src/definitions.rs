@@ -1,5 +1,6 @@
 pub const N_NODES_ON_STACK: usize = 32usize;
 pub const N_VARS_ON_STACK: usize = 16usize;
 pub const N_UNARYOPS_OF_DEEPEX_ON_STACK: usize = 16usize;
+pub const N_EXPANDED_TOKENS_MAX: usize = 1usize << 16;
 #[cfg(feature = "partial")]
 pub const N_BINOPS_OF_DEEPEX_ON_STACK: usize = 16usize;
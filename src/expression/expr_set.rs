@@ -0,0 +1,255 @@
+use std::{fmt::Debug, str::FromStr};
+
+use smallvec::SmallVec;
+
+use crate::{
+    data_type::DataType,
+    definitions::N_VARS_ON_STACK,
+    expression::{
+        tape::{Tape, TapeBuilder},
+        Express,
+    },
+    format_exerr, ExError, ExResult, FlatEx, FloatOpsFactory, MakeOperators, MatchLiteral,
+    MatchVariable, NumberMatcher, VarNameMatcher,
+};
+
+/// A set of expressions that share their variables and are evaluated in one go. Sub-expressions
+/// that occur in more than one expression are evaluated only once per call of
+/// [`eval`](ExprSet::eval). Expressions with short-circuit operators such as `&&` or `?` are
+/// evaluated separately such that operands that do not influence the result are not evaluated.
+///
+/// The variables of the set are the union of the variables of all expressions in alphabetical
+/// order.
+///
+/// ```rust
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// #
+/// use exmex::ExprSet;
+/// let set = ExprSet::<f64>::from_strs(&["sin(x)*y", "sin(x)+z", "2*y"])?;
+/// assert_eq!(set.var_names(), ["x", "y", "z"]);
+/// let res = set.eval(&[1.0, 2.0, 3.0])?;
+/// assert!((res[0] - 1.0f64.sin() * 2.0).abs() < 1e-12);
+/// assert!((res[1] - (1.0f64.sin() + 3.0)).abs() < 1e-12);
+/// assert!((res[2] - 4.0).abs() < 1e-12);
+/// #
+/// #     Ok(())
+/// # }
+/// ```
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Debug)]
+pub struct ExprSet<T, OF = FloatOpsFactory<T>, LM = NumberMatcher, VM = VarNameMatcher>
+where
+    T: DataType,
+    OF: MakeOperators<T>,
+    LM: MatchLiteral,
+    VM: MatchVariable,
+{
+    exprs: Vec<FlatEx<T, OF, LM, VM>>,
+    var_names: SmallVec<[String; N_VARS_ON_STACK]>,
+    var_maps: Vec<SmallVec<[usize; N_VARS_ON_STACK]>>,
+    tape: Tape<T>,
+}
+
+impl<T, OF, LM, VM> ExprSet<T, OF, LM, VM>
+where
+    T: DataType,
+    OF: MakeOperators<T>,
+    LM: MatchLiteral,
+    VM: MatchVariable,
+{
+    /// Creates a set from already parsed expressions.
+    pub fn from_flatexes(exprs: Vec<FlatEx<T, OF, LM, VM>>) -> Self {
+        let mut var_names = exprs
+            .iter()
+            .flat_map(|expr| expr.var_names().iter().cloned())
+            .collect::<SmallVec<[String; N_VARS_ON_STACK]>>();
+        var_names.sort_unstable();
+        var_names.dedup();
+        let var_maps = exprs
+            .iter()
+            .map(|expr| {
+                expr.var_names()
+                    .iter()
+                    .map(|name| var_names.iter().position(|n| n == name).unwrap())
+                    .collect::<SmallVec<[usize; N_VARS_ON_STACK]>>()
+            })
+            .collect::<Vec<_>>();
+        let mut builder = TapeBuilder::new();
+        // the tape evaluates all operands, hence short-circuit expressions are evaluated lazily
+        for (expr, var_map) in exprs.iter().zip(var_maps.iter()) {
+            if !expr.has_short_circuit() {
+                builder.push_flat(expr.nodes(), expr.ops(), expr.prio_indices(), |idx| {
+                    var_map[idx]
+                });
+            }
+        }
+        ExprSet {
+            exprs,
+            var_names,
+            var_maps,
+            tape: builder.build(),
+        }
+    }
+
+    /// Parses all strings and creates a set of the resulting expressions.
+    ///
+    /// # Errors
+    ///
+    /// An [`ExError`](crate::ExError) is returned if any of the strings cannot be parsed.
+    pub fn from_strs(texts: &[&str]) -> ExResult<Self>
+    where
+        <T as FromStr>::Err: Debug,
+    {
+        let exprs = texts
+            .iter()
+            .map(|text| FlatEx::<T, OF, LM, VM>::from_str(text))
+            .collect::<ExResult<Vec<_>>>()?;
+        Ok(Self::from_flatexes(exprs))
+    }
+
+    /// Evaluates all expressions and returns their results in the order of the expressions.
+    ///
+    /// # Arguments
+    ///
+    /// * `vars` - Values of the variables of the set; the n-th value corresponds to
+    ///   the n-th variable of [`var_names`](ExprSet::var_names).
+    ///
+    /// # Errors
+    ///
+    /// If the number of variables of the set is different from the length of
+    /// the variable slice, we return an [`ExError`](crate::ExError).
+    pub fn eval(&self, vars: &[T]) -> ExResult<Vec<T>> {
+        if self.var_names.len() != vars.len() {
+            return Err(format_exerr!(
+                "expression set contains {} vars which is different to the length {} of the passed slice",
+                self.var_names.len(),
+                vars.len()
+            ));
+        }
+        let slots = self.tape.eval_slots(vars);
+        let mut outputs = self.tape.outputs().iter();
+        self.exprs
+            .iter()
+            .zip(self.var_maps.iter())
+            .map(|(expr, var_map)| {
+                if expr.has_short_circuit() {
+                    let expr_vars = var_map
+                        .iter()
+                        .map(|idx| vars[*idx].clone())
+                        .collect::<SmallVec<[T; N_VARS_ON_STACK]>>();
+                    expr.eval(&expr_vars)
+                } else {
+                    let idx = outputs.next().ok_or_else(|| {
+                        ExError::new("tape of expression set has too few outputs")
+                    })?;
+                    Ok(slots[*idx].clone())
+                }
+            })
+            .collect()
+    }
+
+    /// Returns the union of the variables of all expressions in alphabetical order.
+    pub fn var_names(&self) -> &[String] {
+        &self.var_names
+    }
+
+    /// Returns the expressions of the set.
+    pub fn exprs(&self) -> &[FlatEx<T, OF, LM, VM>] {
+        &self.exprs
+    }
+
+    pub fn len(&self) -> usize {
+        self.exprs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.exprs.is_empty()
+    }
+}
+
+#[cfg(test)]
+use crate::util::assert_float_eq_f64;
+
+#[test]
+fn test_expr_set() -> ExResult<()> {
+    let texts = [
+        "x^2+sin(y)",
+        "sin(y)*z",
+        "{α}",
+        "2.5",
+        "(x^2+sin(y))/(z+x)",
+        "x+z",
+    ];
+    let set = ExprSet::<f64>::from_strs(&texts)?;
+    assert_eq!(set.len(), 6);
+    assert_eq!(set.var_names(), ["x", "y", "z", "α"]);
+    let vars = [1.5, -0.3, 2.0, 4.0];
+    let res = set.eval(&vars)?;
+    assert_eq!(res.len(), 6);
+    for (text, res) in texts.iter().zip(res.iter()) {
+        let flatex = FlatEx::<f64>::from_str(text)?;
+        let expr_vars = flatex
+            .var_names()
+            .iter()
+            .map(|name| vars[set.var_names().iter().position(|n| n == name).unwrap()])
+            .collect::<Vec<_>>();
+        assert_float_eq_f64(*res, flatex.eval(&expr_vars)?);
+    }
+    // x^2, sin(y), x^2+sin(y), sin(y)*z, z+x, (x^2+sin(y))/(z+x), and x+z since operands
+    // are not swapped
+    assert_eq!(set.tape.n_operations(), 7);
+    assert!(set.eval(&vars[..3]).is_err());
+    assert!(ExprSet::<f64>::from_strs(&["x+", "y"]).is_err());
+    let set = ExprSet::<f64>::from_strs(&[])?;
+    assert!(set.is_empty());
+    assert!(set.eval(&[])?.is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_expr_set_short_circuit() -> ExResult<()> {
+    use crate::{BinOp, Operand, Operator, ShortCircuit};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    static N_CALLS: AtomicUsize = AtomicUsize::new(0);
+    #[derive(Clone)]
+    struct GuardOpsFactory;
+    impl MakeOperators<f64> for GuardOpsFactory {
+        fn make<'a>() -> Vec<Operator<'a, f64>> {
+            vec![
+                Operator::make_bin_short_circuit(
+                    "&&",
+                    BinOp {
+                        apply: |a, b| if a != 0.0 { b } else { 0.0 },
+                        prio: 0,
+                        is_commutative: false,
+                        is_right_assoc: false,
+                    },
+                    ShortCircuit {
+                        first: Operand::Left,
+                        apply: |a| if *a == 0.0 { Some(0.0) } else { None },
+                        selection: None,
+                    },
+                ),
+                Operator::make_bin(
+                    "+",
+                    BinOp {
+                        apply: |a, b| a + b,
+                        prio: 1,
+                        is_commutative: true,
+                        is_right_assoc: false,
+                    },
+                ),
+                Operator::make_unary("count", |a| {
+                    N_CALLS.fetch_add(1, Ordering::SeqCst);
+                    a
+                }),
+            ]
+        }
+    }
+    let set = ExprSet::<f64, GuardOpsFactory>::from_strs(&["x && count(y)", "x + y", "y + x"])?;
+    assert_eq!(set.eval(&[0.0, 2.0])?, [0.0, 2.0, 2.0]);
+    assert_eq!(N_CALLS.load(Ordering::SeqCst), 0);
+    assert_eq!(set.eval(&[1.0, 2.0])?, [2.0, 3.0, 3.0]);
+    assert_eq!(N_CALLS.load(Ordering::SeqCst), 1);
+    Ok(())
+}
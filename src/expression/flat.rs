@@ -2,8 +2,12 @@ use crate::data_type::DataType;
 use crate::definitions::{N_NODES_ON_STACK, N_VARS_ON_STACK};
 
 use self::detail::{FlatNode, FlatNodeKind, FlatNodeVec, FlatOpVec};
-use crate::expression::Express;
-use crate::{ExError, ExResult, FloatOpsFactory, MakeOperators, MatchLiteral, NumberMatcher, format_exerr};
+use crate::expression::{tape::{self, Tape}, Express};
+use crate::operators::{Operand, Selection};
+use crate::{
+    format_exerr, DecimalSeparator, ExError, ExResult, FloatOpsFactory, FuncEnv, MakeOperators,
+    MatchLiteral, MatchVariable, NumberMatcher, ParseOptions, VarNameMatcher,
+};
 
 use smallvec::SmallVec;
 use std::fmt::{self, Debug, Display, Formatter};
@@ -15,7 +19,7 @@ use crate::{operators::UnaryOp, BinOp, Operator};
 
 const DEPTH_PRIO_STEP: i64 = 1000;
 pub type ExprIdxVec = SmallVec<[usize; N_NODES_ON_STACK]>;
-mod detail {
+pub(crate) mod detail {
     use std::{fmt::Debug, marker::PhantomData, str::FromStr};
 
     use smallvec::{smallvec, SmallVec};
@@ -23,9 +27,11 @@ mod detail {
     use crate::{
         data_type::DataType,
         definitions::{N_NODES_ON_STACK, N_UNARYOPS_OF_DEEPEX_ON_STACK},
-        operators::UnaryOp,
+        operators::{Operand, Selection, ShortCircuit, UnaryOp},
+        format_exerr,
         parser::{self, Paren, ParsedToken},
-        BinOp, ExError, ExResult, FlatEx, MakeOperators, MatchLiteral, Operator,
+        BinOp, ExError, ExResult, FlatEx, MakeOperators, MatchLiteral, MatchVariable, Operator,
+        ParseOptions,
     };
 
     use super::{ExprIdxVec, DEPTH_PRIO_STEP};
@@ -34,12 +40,20 @@ mod detail {
     pub type FlatOpVec<T> = SmallVec<[FlatOp<T>; N_NODES_ON_STACK]>;
     type UnaryOpIdxDepthStack = SmallVec<[(usize, i64); N_UNARYOPS_OF_DEEPEX_ON_STACK]>;
 
+    /// Representations of the functions of a [`UnaryOp`](UnaryOp) in the same order. Most
+    /// nodes and operators have at most two unary functions.
+    pub type UnaryReprVec = SmallVec<[&'static str; 2]>;
+
     /// A `FlatOp` contains besides a binary operation an optional unary operation that
-    /// will be executed after the binary operation in case of its existence.
+    /// will be executed after the binary operation in case of its existence. The
+    /// representations of the operators identify them, e.g., for lowering to machine code.
     #[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Debug)]
     pub struct FlatOp<T: Clone> {
         pub unary_op: UnaryOp<T>,
+        pub unary_reprs: UnaryReprVec,
         pub bin_op: BinOp<T>,
+        pub bin_repr: &'static str,
+        pub short_circuit: Option<ShortCircuit<T>>,
     }
 
     #[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Debug)]
@@ -52,6 +66,7 @@ mod detail {
     pub struct FlatNode<T> {
         pub kind: FlatNodeKind<T>,
         pub unary_op: UnaryOp<T>,
+        pub unary_reprs: UnaryReprVec,
     }
 
     impl<T> FlatNode<T>
@@ -62,10 +77,23 @@ mod detail {
             FlatNode {
                 kind,
                 unary_op: UnaryOp::new(),
+                unary_reprs: UnaryReprVec::new(),
             }
         }
     }
 
+    /// Returns the representation of the operator of the factory with the given
+    /// representation, which outlives the text that has been parsed.
+    pub fn find_static_repr<T: Clone>(
+        ops: &[Operator<'static, T>],
+        repr: &str,
+    ) -> ExResult<&'static str> {
+        ops.iter()
+            .map(|op| op.repr())
+            .find(|op_repr| *op_repr == repr)
+            .ok_or_else(|| format_exerr!("operator '{}' is not provided by the factory", repr))
+    }
+
     pub fn eval_flatex<T: Clone + Debug>(
         vars: &[T],
         nodes: &FlatNodeVec<T>,
@@ -103,6 +131,147 @@ mod detail {
         Ok(numbers[0].clone())
     }
 
+    /// Operand of an operator in the tree of a flat expression, i.e., either a node or the
+    /// result of another operator.
+    #[derive(Clone, Copy, Debug)]
+    enum Child {
+        Node(usize),
+        Op(usize),
+    }
+
+    /// Returns the operands of each operator and the root of the tree of operators, i.e., the
+    /// operator that [`eval_flatex`](eval_flatex) applies last. Segments of nodes that have
+    /// already been combined are only tracked at their first and their last node such that the
+    /// tree is built in linear time.
+    fn make_tree(
+        n_nodes: usize,
+        n_ops: usize,
+        prio_indices: &ExprIdxVec,
+    ) -> (SmallVec<[(Child, Child); N_NODES_ON_STACK]>, Child) {
+        // the first node of a segment knows the root and the last node of the segment, the
+        // last node knows the first one
+        let mut roots = (0..n_nodes)
+            .map(Child::Node)
+            .collect::<SmallVec<[Child; N_NODES_ON_STACK]>>();
+        let mut lasts = (0..n_nodes).collect::<ExprIdxVec>();
+        let mut firsts = (0..n_nodes).collect::<ExprIdxVec>();
+        let mut children = smallvec![(Child::Node(0), Child::Node(0)); n_ops];
+        for &op_idx in prio_indices {
+            let first = firsts[op_idx];
+            let last = lasts[op_idx + 1];
+            children[op_idx] = (roots[first], roots[op_idx + 1]);
+            roots[first] = Child::Op(op_idx);
+            lasts[first] = last;
+            firsts[last] = first;
+        }
+        (children, roots[0])
+    }
+
+    enum Task {
+        Eval(Child),
+        /// Applies the operator after its first operand has been evaluated.
+        ApplyFirst(usize),
+        /// Applies the operator after both operands have been evaluated.
+        ApplyBoth(usize),
+    }
+
+    /// Evaluates like [`eval_flatex`](eval_flatex) but skips operands of operators with
+    /// short-circuit evaluation that do not influence the result. The tree of operators is
+    /// traversed with an explicit stack, skipped operands are not visited at all. Besides its
+    /// value, each intermediate result knows whether it has not been selected by a
+    /// [`Selection::Condition`](Selection::Condition), which is consumed by a
+    /// [`Selection::Alternative`](Selection::Alternative).
+    pub fn eval_flatex_lazy<T: Clone + Debug>(
+        vars: &[T],
+        nodes: &FlatNodeVec<T>,
+        ops: &FlatOpVec<T>,
+        prio_indices: &ExprIdxVec,
+    ) -> ExResult<T> {
+        let (children, root) = make_tree(nodes.len(), ops.len(), prio_indices);
+        let first_operand = |op: &FlatOp<T>| {
+            op.short_circuit
+                .as_ref()
+                .map(|short_circuit| short_circuit.first)
+                .unwrap_or(Operand::Left)
+        };
+        let selection = |op: &FlatOp<T>| {
+            op.short_circuit
+                .as_ref()
+                .and_then(|short_circuit| short_circuit.selection)
+        };
+        // unary operators turn results that have not been selected into regular values
+        let push = |values: &mut SmallVec<[(T, bool); N_NODES_ON_STACK]>,
+                    op: &FlatOp<T>,
+                    res: T,
+                    is_unselected: bool| {
+            values.push((op.unary_op.apply(res), is_unselected && op.unary_op.len() == 0));
+        };
+        let mut tasks = vec![Task::Eval(root)];
+        let mut values = SmallVec::<[(T, bool); N_NODES_ON_STACK]>::new();
+        while let Some(task) = tasks.pop() {
+            match task {
+                Task::Eval(Child::Node(node_idx)) => {
+                    let node = &nodes[node_idx];
+                    let val = node.unary_op.apply(match &node.kind {
+                        FlatNodeKind::Num(n) => n.clone(),
+                        FlatNodeKind::Var(idx) => vars[*idx].clone(),
+                    });
+                    values.push((val, false));
+                }
+                Task::Eval(Child::Op(op_idx)) => {
+                    let (left, right) = children[op_idx];
+                    tasks.push(Task::ApplyFirst(op_idx));
+                    tasks.push(Task::Eval(match first_operand(&ops[op_idx]) {
+                        Operand::Left => left,
+                        Operand::Right => right,
+                    }));
+                }
+                Task::ApplyFirst(op_idx) => {
+                    let op = &ops[op_idx];
+                    let (first, is_first_unselected) =
+                        values.last().expect("first operand has been evaluated");
+                    let short_circuit = match (&op.short_circuit, selection(op)) {
+                        (Some(_), Some(Selection::Alternative)) if *is_first_unselected => None,
+                        (Some(short_circuit), _) => (short_circuit.apply)(first),
+                        (None, _) => None,
+                    };
+                    match short_circuit {
+                        Some(res) => {
+                            values.pop();
+                            let is_unselected = selection(op) == Some(Selection::Condition);
+                            push(&mut values, op, res, is_unselected);
+                        }
+                        None => {
+                            let (left, right) = children[op_idx];
+                            tasks.push(Task::ApplyBoth(op_idx));
+                            tasks.push(Task::Eval(match first_operand(op) {
+                                Operand::Left => right,
+                                Operand::Right => left,
+                            }));
+                        }
+                    }
+                }
+                Task::ApplyBoth(op_idx) => {
+                    let op = &ops[op_idx];
+                    let (second, is_second_unselected) =
+                        values.pop().expect("second operand has been evaluated");
+                    let (first, _) = values.pop().expect("first operand has been evaluated");
+                    let res = match first_operand(op) {
+                        Operand::Left => (op.bin_op.apply)(first, second),
+                        Operand::Right => (op.bin_op.apply)(second, first),
+                    };
+                    // a selected operand that has not been selected itself such as `x if a` in
+                    // `(x if a) if b` passes this on, as does the fallback of an alternative
+                    push(&mut values, op, res, is_second_unselected && selection(op).is_some());
+                }
+            }
+        }
+        values
+            .pop()
+            .map(|(val, _)| val)
+            .ok_or_else(|| ExError::new("cannot evaluate an empty expression"))
+    }
+
     /// This is called in case a closing paren occurs. If available, the index of the unary operator of the
     /// relevant depth operators will be returned and the open operator will be removed.
     ///   
@@ -129,15 +298,33 @@ mod detail {
     }
 
     type ExResultOption<T> = ExResult<Option<T>>;
+    type UnaryWithRepr<T> = (fn(T) -> T, &'static str);
+
+    /// Appends unary functions with their representations such that they are applied after
+    /// the existing ones, see [`UnaryOp::append_after_iter`](UnaryOp::append_after_iter).
+    pub fn append_unaries<T, I>(unary_op: &mut UnaryOp<T>, unary_reprs: &mut UnaryReprVec, iter: I)
+    where
+        T: Clone,
+        I: Iterator<Item = UnaryWithRepr<T>>,
+    {
+        let (funcs, reprs): (SmallVec<[_; N_UNARYOPS_OF_DEEPEX_ON_STACK]>, UnaryReprVec) =
+            iter.unzip();
+        unary_op.append_after_iter(funcs.into_iter());
+        *unary_reprs = reprs.into_iter().chain(unary_reprs.iter().copied()).collect();
+    }
 
-    fn unpack_unary<T>(idx: usize, parsed_tokens: &[ParsedToken<T>]) -> ExResultOption<fn(T) -> T>
+    fn unpack_unary<T>(
+        idx: usize,
+        parsed_tokens: &[ParsedToken<T>],
+        ops: &[Operator<'static, T>],
+    ) -> ExResultOption<UnaryWithRepr<T>>
     where
         T: DataType,
     {
         match &parsed_tokens[idx] {
             ParsedToken::Op(op) => {
                 if !is_binary(op, idx, parsed_tokens)? {
-                    Ok(Some(op.unary()?))
+                    Ok(Some((op.unary()?, find_static_repr(ops, op.repr())?)))
                 } else {
                     Ok(None)
                 }
@@ -146,15 +333,17 @@ mod detail {
         }
     }
 
-    pub fn make_expression<T, OF, LMF>(
+    pub fn make_expression<T, OF, LMF, VM>(
         text: &str,
         parsed_tokens: &[ParsedToken<T>],
         parsed_vars: &[&str],
-    ) -> ExResult<FlatEx<T, OF, LMF>>
+        ops: &[Operator<'static, T>],
+    ) -> ExResult<FlatEx<T, OF, LMF, VM>>
     where
         T: Clone + FromStr + Debug,
         OF: MakeOperators<T>,
         LMF: MatchLiteral,
+        VM: MatchVariable,
     {
         let mut flat_nodes = FlatNodeVec::<T>::new();
         let mut flat_ops = FlatOpVec::<T>::new();
@@ -164,7 +353,7 @@ mod detail {
         let mut unary_stack: UnaryOpIdxDepthStack = SmallVec::new();
 
         let iter_subsequent_unaries = |end_idx: usize| {
-            let unpack = |idx| unpack_unary(idx, parsed_tokens);
+            let unpack = |idx| unpack_unary(idx, parsed_tokens, ops);
             let dist_from_end = (0..end_idx + 1)
                 .rev()
                 .map(unpack)
@@ -188,9 +377,11 @@ mod detail {
                 let idx_op = idx_node - 1;
                 if let ParsedToken::Op(op) = &parsed_tokens[idx_op] {
                     if !is_binary(op, idx_op, parsed_tokens)? {
+                        let (funcs, unary_reprs) = iter_subsequent_unaries(idx_op)?.unzip();
                         return Ok(FlatNode {
                             kind,
-                            unary_op: UnaryOp::from_iter(iter_subsequent_unaries(idx_op)?),
+                            unary_op: UnaryOp::from_vec(funcs),
+                            unary_reprs,
                         });
                     }
                 }
@@ -205,7 +396,10 @@ mod detail {
                         bin_op.prio += depth * DEPTH_PRIO_STEP;
                         flat_ops.push(FlatOp::<T> {
                             unary_op: UnaryOp::new(),
+                            unary_reprs: UnaryReprVec::new(),
                             bin_op,
+                            bin_repr: find_static_repr(ops, op.repr())?,
+                            short_circuit: op.short_circuit(),
                         });
                     } else if let ParsedToken::Paren(p) = &parsed_tokens[idx_tkn + 1] {
                         match p {
@@ -243,7 +437,11 @@ mod detail {
                                 .iter_mut()
                                 .rev()
                                 .take_while(|op| op.bin_op.prio >= depth * DEPTH_PRIO_STEP)
-                                .min_by(|fo1, fo2| fo1.bin_op.prio.cmp(&fo2.bin_op.prio));
+                                .enumerate()
+                                .min_by_key(|(i, op)| {
+                                    (op.bin_op.prio, assoc_order(*i, op.bin_op.is_right_assoc))
+                                })
+                                .map(|(_, op)| op);
                             match lowest_prio_flat_op {
                                 None => {
                                     // no binary operators of current depth, attach to last node
@@ -254,18 +452,22 @@ mod detail {
                                     let mut closed = pop_unary_stack(&mut unary_stack, depth - 1);
                                     match &mut closed {
                                         None => (),
-                                        Some(uop_idx) => last_node
-                                            .unary_op
-                                            .append_after_iter(iter_subsequent_unaries(*uop_idx)?),
+                                        Some(uop_idx) => append_unaries(
+                                            &mut last_node.unary_op,
+                                            &mut last_node.unary_reprs,
+                                            iter_subsequent_unaries(*uop_idx)?,
+                                        ),
                                     }
                                 }
                                 Some(lowpfo) => {
                                     let mut closed = pop_unary_stack(&mut unary_stack, depth - 1);
                                     match &mut closed {
                                         None => (),
-                                        Some(uop_idx) => lowpfo
-                                            .unary_op
-                                            .append_after_iter(iter_subsequent_unaries(*uop_idx)?),
+                                        Some(uop_idx) => append_unaries(
+                                            &mut lowpfo.unary_op,
+                                            &mut lowpfo.unary_reprs,
+                                            iter_subsequent_unaries(*uop_idx)?,
+                                        ),
                                     }
                                 }
                             }
@@ -274,6 +476,11 @@ mod detail {
                         }
                     }
                 }
+                ParsedToken::Comma => {
+                    return Err(ExError::new(
+                        "a comma can only separate arguments of function calls",
+                    ));
+                }
             }
         }
         let indices = prioritized_indices_flat(&flat_ops, &flat_nodes);
@@ -283,37 +490,61 @@ mod detail {
             prio_indices: indices,
             var_names: parsed_vars.iter().map(|s| s.to_string()).collect(),
             text: text.to_string(),
+            tape: None,
             dummy_ops_factory: PhantomData,
             dummy_literal_matcher_factory: PhantomData,
+            dummy_variable_matcher_factory: PhantomData,
         })
     }
 
-    pub fn parse<T, OF, LMF>(text: &str, ops: &[Operator<T>]) -> ExResult<FlatEx<T, OF, LMF>>
+    pub fn parse<T, OF, LMF, VM>(
+        text: &str,
+        ops: &[Operator<'static, T>],
+        options: &ParseOptions,
+    ) -> ExResult<FlatEx<T, OF, LMF, VM>>
     where
         T: DataType,
         <T as FromStr>::Err: Debug,
         OF: MakeOperators<T>,
         LMF: MatchLiteral,
+        VM: MatchVariable,
     {
-        let mut expr = parse_wo_compile(text, ops)?;
-        expr.compile();
+        let mut expr = parse_wo_compile(text, ops, options)?;
+        if options.compile {
+            expr.compile();
+        }
         Ok(expr)
     }
 
-    pub fn parse_wo_compile<T, OF, LMF>(
+    pub fn parse_wo_compile<T, OF, LMF, VM>(
         text: &str,
-        ops: &[Operator<T>],
-    ) -> ExResult<FlatEx<T, OF, LMF>>
+        ops: &[Operator<'static, T>],
+        options: &ParseOptions,
+    ) -> ExResult<FlatEx<T, OF, LMF, VM>>
     where
         T: DataType,
         <T as FromStr>::Err: Debug,
         OF: MakeOperators<T>,
         LMF: MatchLiteral,
+        VM: MatchVariable,
     {
-        let parsed_tokens = parser::tokenize_and_analyze(text, ops, LMF::is_literal)?;
+        let text = options.preprocess(text)?;
+        let parsed_tokens =
+            parser::tokenize_statements(&text, ops, LMF::is_literal, VM::is_variable, options)?;
         parser::check_parsed_token_preconditions(&parsed_tokens)?;
         let parsed_vars = parser::find_parsed_vars(&parsed_tokens);
-        make_expression(text, &parsed_tokens[0..], &parsed_vars)
+        make_expression(&text, &parsed_tokens[0..], &parsed_vars, ops)
+    }
+
+    /// Orders binary operators of the same priority by their index `idx` such that
+    /// left-associative operators are applied from left to right and right-associative ones
+    /// from right to left.
+    pub fn assoc_order(idx: usize, is_right_assoc: bool) -> i64 {
+        if is_right_assoc {
+            -(idx as i64)
+        } else {
+            idx as i64
+        }
     }
 
     pub fn prioritized_indices_flat<T: Clone + Debug>(
@@ -334,7 +565,10 @@ mod detail {
         indices.sort_by(|i1, i2| {
             let prio_i1 = prio_increase(*i1);
             let prio_i2 = prio_increase(*i2);
-            prio_i2.partial_cmp(&prio_i1).unwrap()
+            prio_i2.partial_cmp(&prio_i1).unwrap().then_with(|| {
+                assoc_order(*i1, ops[*i1].bin_op.is_right_assoc)
+                    .cmp(&assoc_order(*i2, ops[*i2].bin_op.is_right_assoc))
+            })
         });
         indices
     }
@@ -366,26 +600,30 @@ mod detail {
 /// In this example, we want to evaluate the expression for the varibale values `x=2.0` and `y=1.5`.
 ///
 #[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Debug)]
-pub struct FlatEx<T, OF = FloatOpsFactory<T>, LM = NumberMatcher>
+pub struct FlatEx<T, OF = FloatOpsFactory<T>, LM = NumberMatcher, VM = VarNameMatcher>
 where
     T: Debug + Clone,
     OF: MakeOperators<T>,
     LM: MatchLiteral,
+    VM: MatchVariable,
 {
     nodes: FlatNodeVec<T>,
     ops: FlatOpVec<T>,
     prio_indices: ExprIdxVec,
     var_names: SmallVec<[String; N_VARS_ON_STACK]>,
     text: String,
+    tape: Option<Tape<T>>,
     dummy_ops_factory: PhantomData<OF>,
     dummy_literal_matcher_factory: PhantomData<LM>,
+    dummy_variable_matcher_factory: PhantomData<VM>,
 }
 
-impl<T, OF, LMF> FlatEx<T, OF, LMF>
+impl<T, OF, LMF, VM> FlatEx<T, OF, LMF, VM>
 where
     T: DataType,
     OF: MakeOperators<T>,
     LMF: MatchLiteral,
+    VM: MatchVariable,
 {
     pub fn new(
         nodes: FlatNodeVec<T>,
@@ -400,13 +638,45 @@ where
             prio_indices,
             var_names,
             text,
+            tape: None,
             dummy_ops_factory: PhantomData,
             dummy_literal_matcher_factory: PhantomData,
+            dummy_variable_matcher_factory: PhantomData,
         }
     }
 
+    pub(crate) fn nodes(&self) -> &FlatNodeVec<T> {
+        &self.nodes
+    }
+
+    pub(crate) fn ops(&self) -> &FlatOpVec<T> {
+        &self.ops
+    }
+
+    pub(crate) fn prio_indices(&self) -> &ExprIdxVec {
+        &self.prio_indices
+    }
+
+    /// Whether the expression contains operators with short-circuit evaluation such as `&&`.
+    pub(crate) fn has_short_circuit(&self) -> bool {
+        self.ops.iter().any(|op| op.short_circuit.is_some())
+    }
+
+    /// Detects structurally identical sub-expressions such as `sin(x)` in `sin(x)^2+sin(x)`
+    /// such that they are evaluated only once per call of [`eval`](Express::eval). Thereby,
+    /// operands of commutative operators are considered in both orders, i.e., `a+b` and `b+a`
+    /// are identified. This is part of [`compile`](FlatEx::compile). Expressions with
+    /// short-circuit operators are skipped, since the tape evaluates all operands.
+    fn eliminate_common_subexpressions(&mut self) {
+        self.tape = if self.has_short_circuit() {
+            None
+        } else {
+            tape::make_if_beneficial(&self.nodes, &self.ops, &self.prio_indices)
+        };
+    }
+
     /// Executes calculations that can trivially be executed, e.g., multiplies two numbers that
-    /// need to be multiplied anyway.
+    /// need to be multiplied anyway. Afterwards, common sub-expressions are eliminated.
     pub fn compile(&mut self) {
         let mut num_inds = self.prio_indices.clone();
         let mut used_prio_indices = ExprIdxVec::new();
@@ -423,14 +693,31 @@ where
             let num_idx = num_inds[i];
             let node_1 = &self.nodes[num_idx];
             let node_2 = &self.nodes[num_idx + 1];
-            if let (FlatNodeKind::Num(num_1), FlatNodeKind::Num(num_2)) =
-                (node_1.kind.clone(), node_2.kind.clone())
+            let op = &self.ops[bin_op_idx];
+            let short_circuit_res = match (&node_1.kind, &node_2.kind, &op.short_circuit) {
+                (FlatNodeKind::Num(num_1), FlatNodeKind::Num(num_2), Some(short_circuit)) => {
+                    match short_circuit.first {
+                        Operand::Left => (short_circuit.apply)(num_1),
+                        Operand::Right => (short_circuit.apply)(num_2),
+                    }
+                }
+                _ => None,
+            };
+            // numbers are always selected, hence, conditions that do not select their operand
+            // are not folded
+            let is_unselected = short_circuit_res.is_some()
+                && op
+                    .short_circuit
+                    .as_ref()
+                    .and_then(|short_circuit| short_circuit.selection)
+                    == Some(Selection::Condition);
+            if let (FlatNodeKind::Num(num_1), FlatNodeKind::Num(num_2), false) =
+                (node_1.kind.clone(), node_2.kind.clone(), is_unselected)
             {
                 if !(already_declined[num_idx] || already_declined[num_idx + 1]) {
-                    let op_result =
-                        self.ops[bin_op_idx]
-                            .unary_op
-                            .apply((self.ops[bin_op_idx].bin_op.apply)(num_1, num_2));
+                    let bin_result =
+                        short_circuit_res.unwrap_or_else(|| (op.bin_op.apply)(num_1, num_2));
+                    let op_result = op.unary_op.apply(bin_result);
                     self.nodes[num_idx] = FlatNode::from_kind(FlatNodeKind::Num(op_result));
                     self.nodes.remove(num_idx + 1);
                     already_declined.remove(num_idx + 1);
@@ -460,6 +747,17 @@ where
             .collect();
 
         self.prio_indices = detail::prioritized_indices_flat(&self.ops, &self.nodes);
+        self.eliminate_common_subexpressions();
+    }
+
+    fn eval_unchecked(&self, vars: &[T]) -> ExResult<T> {
+        match &self.tape {
+            Some(tape) => Ok(tape.eval(vars)),
+            None if self.has_short_circuit() => {
+                detail::eval_flatex_lazy(vars, &self.nodes, &self.ops, &self.prio_indices)
+            }
+            None => detail::eval_flatex(vars, &self.nodes, &self.ops, &self.prio_indices),
+        }
     }
 
     /// Parses into an expression without compilation. Allow slightly faster direct evaluation of strings.
@@ -469,15 +767,112 @@ where
         <T as FromStr>::Err: Debug,
     {
         let ops = OF::make();
-        detail::parse_wo_compile(text, &ops)
+        detail::parse_wo_compile(text, &ops, &ParseOptions::default())
+    }
+
+    /// Parses an expression where juxtaposed operands are multiplied as on a calculator, e.g.,
+    /// `2x`, `3(x+1)`, `(a+b)(a-b)`, and `2sin(x)` are `2*x`, `3*(x+1)`, `(a+b)*(a-b)`, and
+    /// `2*sin(x)`. The inserted multiplications have the usual priority, i.e., `1/2x` is
+    /// `(1/2)*x`. Variable names are matched as long as possible such that `xy` is one variable
+    /// and `x y` is a product. A variable directly followed by an opening parenthesis is a call
+    /// of a user-defined function such as `f(x)`, whereas `x (y+1)` is a product.
+    ///
+    /// ```rust
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use exmex::prelude::*;
+    /// let expr = FlatEx::<f64>::from_str_with_implicit_mul("2x y + 3(x+1)(y-1)")?;
+    /// assert!((expr.eval(&[2.0, 3.0])? - 30.0).abs() < 1e-12);
+    /// #
+    /// #     Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// An [`ExError`](crate::ExError) is returned if the text cannot be parsed or if the
+    /// operator factory does not provide a binary operator `*`.
+    pub fn from_str_with_implicit_mul(text: &str) -> ExResult<Self>
+    where
+        T: DataType,
+        <T as FromStr>::Err: Debug,
+    {
+        Self::from_str_with_options(
+            text,
+            &ParseOptions {
+                implicit_mul: true,
+                ..ParseOptions::default()
+            },
+        )
+    }
+
+    /// Parses an expression whose number literals use the given decimal separator, e.g.,
+    /// `3,5 * max(x; 2)` with [`DecimalSeparator::Comma`](crate::DecimalSeparator::Comma).
+    /// The expression is stored in the default syntax, see
+    /// [`unparse_localized`](FlatEx::unparse_localized) for the localized representation.
+    pub fn from_str_localized(text: &str, separator: DecimalSeparator) -> ExResult<Self>
+    where
+        T: DataType,
+        <T as FromStr>::Err: Debug,
+    {
+        Self::from_str_with_options(
+            text,
+            &ParseOptions {
+                decimal_separator: separator,
+                ..ParseOptions::default()
+            },
+        )
+    }
+
+    /// Parses an expression with the given options, e.g., to restrict the variables of
+    /// untrusted input or to skip compilation, see [`ParseOptions`](crate::ParseOptions).
+    /// The expression is stored in the default syntax with the whitespace of the text.
+    ///
+    /// # Errors
+    ///
+    /// An [`ExError`](crate::ExError) is returned if the text cannot be parsed or if it
+    /// violates one of the options.
+    pub fn from_str_with_options(text: &str, options: &ParseOptions) -> ExResult<Self>
+    where
+        T: DataType,
+        <T as FromStr>::Err: Debug,
+    {
+        let ops = OF::make();
+        detail::parse(text, &ops, options)
+    }
+
+    /// Creates an expression string like [`unparse`](Express::unparse) with the given decimal
+    /// separator such that the expression can be shown and edited in the user's locale.
+    pub fn unparse_localized(&self, separator: DecimalSeparator) -> String {
+        separator.localize(&self.text)
+    }
+
+    /// Parses an expression that can call the user-defined functions of the environment.
+    /// The definitions of all called functions are prepended as statements to the text
+    /// returned by [`unparse`](FlatEx::unparse) such that the expression can be re-parsed
+    /// without the environment.
+    ///
+    /// # Errors
+    ///
+    /// An [`ExError`](crate::ExError) is returned if the text or one of the definitions
+    /// of the called functions cannot be parsed.
+    pub fn from_str_with_env(text: &str, env: &FuncEnv<VM>) -> ExResult<Self>
+    where
+        T: DataType,
+        <T as FromStr>::Err: Debug,
+    {
+        let ops = OF::make();
+        detail::parse(&env.prepend_definitions(text), &ops, &ParseOptions::default())
     }
 }
 
-impl<T, OF, LM> Express<T> for FlatEx<T, OF, LM>
+impl<T, OF, LM, VM> Express<T> for FlatEx<T, OF, LM, VM>
 where
     T: DataType,
     OF: MakeOperators<T>,
     LM: MatchLiteral,
+    VM: MatchVariable,
 {
     type LiteralMatcher = LM;
     type OperatorFactory = OF;
@@ -490,12 +885,7 @@ where
                 vars.len()
             ));
         }
-        detail::eval_flatex(
-            vars,
-            &self.nodes,
-            &self.ops,
-            &self.prio_indices
-        )
+        self.eval_unchecked(vars)
     }
 
     fn eval_relaxed(&self, vars: &[T]) -> ExResult<T> {
@@ -506,12 +896,7 @@ where
                 vars.len()
             ));
         }
-        detail::eval_flatex(
-            vars,
-            &self.nodes,
-            &self.ops,
-            &self.prio_indices,
-        )
+        self.eval_unchecked(vars)
     }
 
     fn unparse(&self) -> &str {
@@ -522,11 +907,12 @@ where
     }
 }
 
-impl<T, OF, LMF> FromStr for FlatEx<T, OF, LMF>
+impl<T, OF, LMF, VM> FromStr for FlatEx<T, OF, LMF, VM>
 where
     T: DataType,
     OF: MakeOperators<T>,
     LMF: MatchLiteral,
+    VM: MatchVariable,
     <T as FromStr>::Err: Debug,
 {
     type Err = ExError;
@@ -537,16 +923,17 @@ where
         T: DataType,
     {
         let ops = OF::make();
-        detail::parse(text, &ops)
+        detail::parse(text, &ops, &ParseOptions::default())
     }
 }
 
 /// The expression is displayed as a string created by [`unparse`](FlatEx::unparse).
-impl<T, OF, LMF> Display for FlatEx<T, OF, LMF>
+impl<T, OF, LMF, VM> Display for FlatEx<T, OF, LMF, VM>
 where
     T: DataType,
     OF: MakeOperators<T>,
     LMF: MatchLiteral,
+    VM: MatchVariable,
 {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         let unparsed = self.unparse();
@@ -555,16 +942,16 @@ where
 }
 
 #[cfg(feature = "partial")]
-use {
-    crate::partial::{DeepEx, DeepNode, Differentiate},
-    num::Float,
-};
+use crate::partial::{DeepEx, DeepNode, DiffDataType, Differentiate};
+/// Converts a deep expression into the nodes and operators of a flat expression. The
+/// operators of the factory provide the representations and short-circuit evaluations.
 #[cfg(feature = "partial")]
 pub fn flatten_vecs<T: Clone + Debug>(
     deep_expr: &DeepEx<T>,
     prio_offset: i64,
-) -> (FlatNodeVec<T>, FlatOpVec<T>) {
-    use self::detail::FlatOp;
+    ops: &[Operator<'static, T>],
+) -> ExResult<(FlatNodeVec<T>, FlatOpVec<T>)> {
+    use self::detail::{find_static_repr, FlatOp, UnaryReprVec};
 
     let mut flat_nodes = FlatNodeVec::<T>::new();
     let mut flat_ops = FlatOpVec::<T>::new();
@@ -580,7 +967,7 @@ pub fn flatten_vecs<T: Clone + Debug>(
                 flat_nodes.push(flat_node);
             }
             DeepNode::Expr(e) => {
-                let (mut sub_nodes, mut sub_ops) = flatten_vecs(e, prio_offset + 100i64);
+                let (mut sub_nodes, mut sub_ops) = flatten_vecs(e, prio_offset + 100i64, ops)?;
                 flat_nodes.append(&mut sub_nodes);
                 flat_ops.append(&mut sub_ops);
             }
@@ -590,32 +977,64 @@ pub fn flatten_vecs<T: Clone + Debug>(
                 apply: deep_expr.bin_ops().ops[node_idx].apply,
                 prio: deep_expr.bin_ops().ops[node_idx].prio + prio_offset,
                 is_commutative: deep_expr.bin_ops().ops[node_idx].is_commutative,
+                is_right_assoc: deep_expr.bin_ops().ops[node_idx].is_right_assoc,
             };
+            let bin_repr = find_static_repr(ops, deep_expr.bin_ops().reprs[node_idx])?;
+            let short_circuit = ops
+                .iter()
+                .find(|op| op.repr() == bin_repr && op.has_bin())
+                .and_then(|op| op.short_circuit());
             flat_ops.push(FlatOp {
                 bin_op: prio_adapted_bin_op,
+                bin_repr,
                 unary_op: UnaryOp::new(),
+                unary_reprs: UnaryReprVec::new(),
+                short_circuit,
             });
         }
     }
 
     if deep_expr.unary_op().op.len() > 0 {
+        let unary_op = &deep_expr.unary_op().op;
+        let unary_reprs = deep_expr
+            .unary_op()
+            .reprs
+            .iter()
+            .map(|repr| find_static_repr(ops, repr))
+            .collect::<ExResult<UnaryReprVec>>()?;
+        let unaries = unary_op
+            .funcs_to_be_composed()
+            .iter()
+            .copied()
+            .zip(unary_reprs);
         if !flat_ops.is_empty() {
-            // find the last binary operator with the lowest priority of this expression,
-            // since this will be executed as the last one
-            let low_prio_op = match flat_ops.iter_mut().rev().min_by_key(|op| op.bin_op.prio) {
+            // find the binary operator with the lowest priority of this expression that will be
+            // executed as the last one, i.e., the last one if it is left-associative and the
+            // first one if it is right-associative
+            let low_prio_op = match flat_ops
+                .iter_mut()
+                .rev()
+                .enumerate()
+                .min_by_key(|(i, op)| {
+                    (
+                        op.bin_op.prio,
+                        detail::assoc_order(*i, op.bin_op.is_right_assoc),
+                    )
+                }) {
                 None => panic!("cannot have more than one flat node but no binary ops"),
-                Some(x) => x,
+                Some((_, x)) => x,
             };
-            low_prio_op
-                .unary_op
-                .append_after(deep_expr.unary_op().op.clone());
+            detail::append_unaries(
+                &mut low_prio_op.unary_op,
+                &mut low_prio_op.unary_reprs,
+                unaries,
+            );
         } else {
-            flat_nodes[0]
-                .unary_op
-                .append_after(deep_expr.unary_op().op.clone());
+            let node = &mut flat_nodes[0];
+            detail::append_unaries(&mut node.unary_op, &mut node.unary_reprs, unaries);
         }
     }
-    (flat_nodes, flat_ops)
+    Ok((flat_nodes, flat_ops))
 }
 #[cfg(feature = "partial")]
 mod detail_diff {
@@ -625,69 +1044,26 @@ mod detail_diff {
 
     use crate::{
         definitions::N_UNARYOPS_OF_DEEPEX_ON_STACK,
-        operators::UnaryOp,
         partial::{BinOpsWithReprs, DeepEx, DeepNode, UnaryOpWithReprs},
-        ExError, ExResult, Operator,
+        ExResult,
     };
 
     use super::{
-        detail::{FlatNode, FlatNodeKind, FlatNodeVec, FlatOp, FlatOpVec},
+        detail::{FlatNode, FlatNodeKind, FlatNodeVec, FlatOp, FlatOpVec, UnaryReprVec},
         DEPTH_PRIO_STEP,
     };
 
-    pub fn collect_reprs<'a, F, T, I>(
-        funcs: I,
-        ops: &[Operator<'a, T>],
-        predicate: fn(&Operator<T>, F) -> bool,
-    ) -> ExResult<SmallVec<[&'a str; N_UNARYOPS_OF_DEEPEX_ON_STACK]>>
-    where
-        T: Clone,
-        I: Iterator<Item = F>,
-        F: Clone,
-    {
-        funcs
-            .map(|func| {
-                Ok(ops
-                    .iter()
-                    .find(|op| predicate(op, func.clone()))
-                    .ok_or_else(|| ExError::new("could not find unary operator"))?
-                    .repr())
-            })
-            .collect::<ExResult<SmallVec<[&str; N_UNARYOPS_OF_DEEPEX_ON_STACK]>>>()
-    }
-
-    pub fn unary_predicate<T: Clone>(op: &Operator<T>, func: &fn(T) -> T) -> bool {
-        if op.has_unary() {
-            op.unary().unwrap() == *func
-        } else {
-            false
-        }
-    }
-
-    pub fn binary_predicate<T: Clone>(op: &Operator<T>, func: &fn(T, T) -> T) -> bool {
-        if op.has_bin() {
-            op.bin().unwrap().apply == *func
-        } else {
-            false
-        }
-    }
-
-    pub fn collect_unary_reprs<'a, T: Clone>(
-        ops: &[Operator<'a, T>],
-        unary_op: &UnaryOp<T>,
-    ) -> ExResult<SmallVec<[&'a str; N_UNARYOPS_OF_DEEPEX_ON_STACK]>> {
-        collect_reprs::<&fn(T) -> T, _, _>(
-            unary_op.funcs_to_be_composed().iter(),
-            ops,
-            unary_predicate,
-        )
+    /// Representations of unary operators of a flat expression as needed by deep expressions.
+    fn to_deep_reprs<'a>(
+        reprs: &UnaryReprVec,
+    ) -> SmallVec<[&'a str; N_UNARYOPS_OF_DEEPEX_ON_STACK]> {
+        reprs.iter().copied().collect()
     }
 
     pub fn make_unary<'a, T: Clone>(
         start: usize,
         end: usize,
         consumed_op_inds: &[usize],
-        ops: &[Operator<'a, T>],
         flat_ops: &[FlatOp<T>],
     ) -> ExResult<(Option<usize>, UnaryOpWithReprs<'a, T>)> {
         let unary_op_idx = (start..end)
@@ -696,7 +1072,7 @@ mod detail_diff {
             Some(idx) => (
                 Some(idx),
                 UnaryOpWithReprs {
-                    reprs: collect_unary_reprs(ops, &flat_ops[idx].unary_op)?,
+                    reprs: to_deep_reprs(&flat_ops[idx].unary_reprs),
                     op: flat_ops[idx].unary_op.clone(),
                 },
             ),
@@ -707,7 +1083,6 @@ mod detail_diff {
     pub fn convert_node<'a, T>(
         node: &FlatNode<T>,
         var_names: &'a [String],
-        ops: &[Operator<'a, T>],
     ) -> ExResult<DeepNode<'a, T>>
     where
         T: Clone + Debug,
@@ -717,7 +1092,7 @@ mod detail_diff {
             FlatNodeKind::Var(var_idx) => DeepNode::Var((var_idx, var_names[var_idx].as_str())),
         };
 
-        let reprs = collect_unary_reprs(ops, &node.unary_op)?;
+        let reprs = to_deep_reprs(&node.unary_reprs);
         let n_reprs = reprs.len();
         let unary_op = UnaryOpWithReprs {
             reprs,
@@ -739,8 +1114,6 @@ mod detail_diff {
         flat_nodes: &FlatNodeVec<T>,
         flat_ops: &FlatOpVec<T>,
         var_names: &'a [String],
-        bin_reprs: &[&'a str],
-        ops: &[Operator<'a, T>],
         consumed_op_inds: &mut SmallVec<[usize; N_UNARYOPS_OF_DEEPEX_ON_STACK]>,
     ) -> ExResult<(DeepNode<'a, T>, usize)> {
         let mut bin_ops = BinOpsWithReprs::<T>::new();
@@ -756,11 +1129,11 @@ mod detail_diff {
             match prio_current.cmp(&prio_prev) {
                 Ordering::Less => {
                     let (uop_idx, unary_op) =
-                        make_unary(start_idx, i, consumed_op_inds, ops, flat_ops)?;
+                        make_unary(start_idx, i, consumed_op_inds, flat_ops)?;
                     if let Some(idx) = uop_idx {
                         consumed_op_inds.push(idx);
                     };
-                    nodes.push(convert_node(&flat_nodes[i], var_names, ops)?);
+                    nodes.push(convert_node(&flat_nodes[i], var_names)?);
                     if start_idx > 0 {
                         return Ok((
                             DeepNode::Expr(Box::new(DeepEx::new(nodes, bin_ops, unary_op)?)),
@@ -776,7 +1149,7 @@ mod detail_diff {
                         bin_ops.ops.clear();
                         bin_ops.reprs.clear();
                         bin_ops.ops.push(flat_ops[i].bin_op.clone());
-                        bin_ops.reprs.push(bin_reprs[i]);
+                        bin_ops.reprs.push(flat_ops[i].bin_repr);
                         nodes.push(node);
                         i += 1;
                     }
@@ -787,30 +1160,28 @@ mod detail_diff {
                         flat_nodes,
                         flat_ops,
                         var_names,
-                        bin_reprs,
-                        ops,
                         consumed_op_inds,
                     )?;
                     i = i_tmp;
                     nodes.push(node);
                     if i < flat_ops.len() {
                         bin_ops.ops.push(flat_ops[i].bin_op.clone());
-                        bin_ops.reprs.push(bin_reprs[i]);
+                        bin_ops.reprs.push(flat_ops[i].bin_repr);
                         i += 1;
                     }
                 }
                 Ordering::Equal => {
-                    nodes.push(convert_node(&flat_nodes[i], var_names, ops)?);
+                    nodes.push(convert_node(&flat_nodes[i], var_names)?);
                     bin_ops.ops.push(flat_ops[i].bin_op.clone());
-                    bin_ops.reprs.push(bin_reprs[i]);
+                    bin_ops.reprs.push(flat_ops[i].bin_repr);
                     i += 1;
                 }
             }
         }
         if nodes.len() == bin_ops.reprs.len() {
-            nodes.push(convert_node(&flat_nodes[i], var_names, ops)?);
+            nodes.push(convert_node(&flat_nodes[i], var_names)?);
         }
-        let (uop_idx, unary_op) = make_unary(start_idx, i, consumed_op_inds, ops, flat_ops)?;
+        let (uop_idx, unary_op) = make_unary(start_idx, i, consumed_op_inds, flat_ops)?;
         if let Some(idx) = uop_idx {
             consumed_op_inds.push(idx);
         };
@@ -822,31 +1193,25 @@ mod detail_diff {
 }
 
 #[cfg(feature = "partial")]
-impl<T, OF, LM> Differentiate<T> for FlatEx<T, OF, LM>
+impl<T, OF, LM, VM> Differentiate<T> for FlatEx<T, OF, LM, VM>
 where
     T: DataType,
     OF: MakeOperators<T> + Debug,
     LM: MatchLiteral + Debug,
+    VM: MatchVariable + Debug,
 {
-    fn to_deepex<'a>(&'a self, ops: &[Operator<'a, T>]) -> ExResult<DeepEx<'a, T>>
+    fn to_deepex<'a>(&'a self, _: &[Operator<'a, T>]) -> ExResult<DeepEx<'a, T>>
     where
         Self: Sized,
-        T: DataType + Float,
+        T: DiffDataType,
         <T as FromStr>::Err: Debug,
     {
-        let bin_reprs = detail_diff::collect_reprs::<&fn(T, T) -> T, _, _>(
-            self.ops.iter().map(|op| &op.bin_op.apply),
-            ops,
-            detail_diff::binary_predicate,
-        )?;
         let mut consumed_op_inds_buffer = SmallVec::new();
         let mut deepex = match detail_diff::collect_deepex::<T>(
             0,
             &self.nodes,
             &self.ops,
             self.var_names(),
-            &bin_reprs,
-            ops,
             &mut consumed_op_inds_buffer,
         )? {
             (DeepNode::Expr(e), _) => e,
@@ -861,13 +1226,13 @@ where
     fn from_deepex(deepex: DeepEx<T>, _: &[Operator<T>]) -> ExResult<Self>
     where
         Self: Sized,
-        T: DataType + Float,
+        T: DiffDataType,
         <T as FromStr>::Err: Debug,
     {
         {
-            let (nodes, ops) = flatten_vecs(&deepex, 0);
+            let (nodes, ops) = flatten_vecs(&deepex, 0, &OF::make())?;
             let indices = detail::prioritized_indices_flat(&ops, &nodes);
-            Ok(FlatEx::new(
+            let mut flatex = FlatEx::new(
                 nodes,
                 ops,
                 indices,
@@ -877,7 +1242,9 @@ where
                     .map(|s| s.to_string())
                     .collect::<SmallVec<_>>(),
                 deepex.unparse(),
-            ))
+            );
+            flatex.eliminate_common_subexpressions();
+            Ok(flatex)
         }
     }
 }
@@ -975,3 +1342,112 @@ fn test_flat_compile() -> ExResult<()> {
     }
     Ok(())
 }
+
+#[test]
+fn test_cse() -> ExResult<()> {
+    fn test(text: &str, vars: &[f64], n_operations: Option<usize>) -> ExResult<()> {
+        println!("testing {}...", text);
+        let flatex = FlatEx::<f64>::from_str(text)?;
+        let flatex_wo_cse = FlatEx::<f64>::from_str_wo_compile(text)?;
+        assert_eq!(
+            flatex.tape.as_ref().map(|tape| tape.n_operations()),
+            n_operations
+        );
+        assert_float_eq_f64(flatex.eval(vars)?, flatex_wo_cse.eval(vars)?);
+        Ok(())
+    }
+    test("sin(x)+sin(x)", &[0.3], Some(2))?;
+    test("sin(x)*cos(x)", &[0.3], None)?;
+    test("(a+b)*(b+a)", &[0.3, 1.2], None)?;
+    test("(a+b)*(a+b)", &[0.3, 1.2], Some(2))?;
+    test("(a-b)*(b-a)", &[0.3, 1.2], None)?;
+    test("(a-b)*(a-b)", &[0.3, 1.2], Some(2))?;
+    test("x^2+y*x^2-cos(x^2)", &[0.3, 1.2], Some(5))?;
+    test("-(x+y)/sin(x+y)*(-(x+y))", &[0.3, 1.2], Some(5))?;
+    test("2*x*3*x", &[0.5], None)?;
+    #[cfg(feature = "value")]
+    {
+        use crate::{FlatExVal, Val};
+        // comparisons are not commutative, e.g., `x<y` and `y<x` must not share a slot
+        let vars = [Val::Int(1), Val::Int(2)];
+        for cmp in ["<", ">", "<=", ">="] {
+            let text = format!("(x {0} y) == (y {0} x)", cmp);
+            println!("testing {}...", text);
+            let flatex = FlatExVal::<i32, f64>::from_str(&text)?;
+            let flatex_wo_cse = FlatExVal::<i32, f64>::from_str_wo_compile(&text)?;
+            assert_eq!(flatex.eval(&vars)?, flatex_wo_cse.eval(&vars)?);
+            assert!(!flatex.eval(&vars)?.to_bool()?);
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn test_short_circuit() -> ExResult<()> {
+    use crate::{BinOp, Operator, ShortCircuit};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    static N_CALLS: AtomicUsize = AtomicUsize::new(0);
+    // `a ? b` returns `b` if `a` is non-zero and NaN otherwise, `a : b` returns `b` if `a` is NaN
+    crate::ops_factory!(
+        LazyOpsFactory,
+        f64,
+        Operator::make_bin_short_circuit(
+            "?",
+            BinOp {
+                apply: |a, b| if a != 0.0 { b } else { f64::NAN },
+                prio: 0,
+                is_commutative: false,
+                is_right_assoc: false,
+            },
+            ShortCircuit {
+                first: Operand::Left,
+                apply: |a| if *a == 0.0 { Some(f64::NAN) } else { None },
+                selection: None,
+            },
+        ),
+        Operator::make_bin_short_circuit(
+            ":",
+            BinOp {
+                apply: |a, b| if a.is_nan() { b } else { a },
+                prio: 0,
+                is_commutative: false,
+                is_right_assoc: false,
+            },
+            ShortCircuit {
+                first: Operand::Left,
+                apply: |a| if a.is_nan() { None } else { Some(*a) },
+                selection: None,
+            },
+        ),
+        Operator::make_bin(
+            "+",
+            BinOp {
+                apply: |a, b| a + b,
+                prio: 1,
+                is_commutative: true,
+                is_right_assoc: false,
+            },
+        ),
+        Operator::make_unary("count", |a| {
+            N_CALLS.fetch_add(1, Ordering::SeqCst);
+            a
+        })
+    );
+    fn test(text: &str, vars: &[f64], reference: f64, n_calls: usize) -> ExResult<()> {
+        println!("testing {}...", text);
+        let flatex = FlatEx::<f64, LazyOpsFactory>::from_str(text)?;
+        N_CALLS.store(0, Ordering::SeqCst);
+        assert_float_eq_f64(flatex.eval(vars)?, reference);
+        assert_eq!(N_CALLS.load(Ordering::SeqCst), n_calls);
+        Ok(())
+    }
+    test("x ? count(y) : count(y + 1)", &[1.0, 2.0], 2.0, 1)?;
+    test("x ? count(y) : count(y + 1)", &[0.0, 2.0], 3.0, 1)?;
+    test("count(x) ? (y ? count(x) : 1) : 2", &[1.0, 0.0], 1.0, 1)?;
+    test("count(x + count(y)) + count(x) + (0 ? count(y) : 3)", &[1.0, 2.0], 7.0, 3)?;
+    // constant operands are folded according to the short-circuit
+    let flatex = FlatEx::<f64, LazyOpsFactory>::from_str("0 ? 1 : x")?;
+    assert_eq!(flatex.nodes.len(), 2);
+    assert!(flatex.tape.is_none());
+    Ok(())
+}
@@ -0,0 +1,204 @@
+use std::marker::PhantomData;
+
+use crate::{format_exerr, parser, ExError, ExResult, MatchVariable, VarNameMatcher};
+
+/// Names of all functions that are potentially called in the text, i.e., names matched by
+/// `VM` that are followed by an opening parenthesis.
+fn called_names<VM: MatchVariable>(text: &str) -> Vec<&str> {
+    let mut names = vec![];
+    let mut rest = text;
+    while let Some(c) = rest.chars().next() {
+        match VM::is_variable(rest).filter(|name| !name.is_empty()) {
+            Some(name) => {
+                rest = &rest[name.len()..];
+                if rest.trim_start().starts_with('(') {
+                    names.push(name);
+                }
+            }
+            // names can only start after the last name or a character that is not part of a name
+            None => rest = &rest[c.len_utf8()..],
+        }
+    }
+    names
+}
+
+/// Environment of user-defined functions such as `f(x) = x^2 + 1` that can be called in
+/// expressions parsed with [`FlatEx::from_str_with_env`](crate::FlatEx::from_str_with_env).
+/// Calls are expanded at parse time, i.e., the body of the function is inserted for each call.
+/// Hence, expressions using functions of the environment are ordinary expressions that can,
+/// e.g., be differentiated. A function can call functions that are defined before or after it
+/// but recursive definitions, direct or indirect, are not supported.
+///
+/// ```rust
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// #
+/// use exmex::prelude::*;
+/// use exmex::FuncEnv;
+/// let mut env = FuncEnv::new();
+/// env.define("f(x) = x^2 + 1")?;
+/// env.define("g(x, y) = f(x) * y")?;
+/// let expr = FlatEx::<f64>::from_str_with_env("g(z, 2) + f(3)", &env)?;
+/// assert_eq!(expr.var_names(), ["z"]);
+/// assert!((expr.eval(&[2.0])? - 20.0).abs() < 1e-12);
+/// assert!(env.define("h(n) = n * h(n - 1)").is_err());
+/// env.define("p(x) = q(x) + 1")?;
+/// assert!(env.define("q(x) = p(x) * 2").is_err());
+/// #
+/// #     Ok(())
+/// # }
+/// ```
+///
+/// Names of functions and parameters are matched by `VM`, which should be the variable matcher
+/// of the expressions that use the environment, e.g.,
+/// `FuncEnv::<DottedMatcher>::default()` for expressions of type
+/// `FlatEx<f64, FloatOpsFactory<f64>, NumberMatcher, DottedMatcher>`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FuncEnv<VM: MatchVariable = VarNameMatcher> {
+    // pairs of function name and definition text
+    definitions: Vec<(String, String)>,
+    dummy_variable_matcher_factory: PhantomData<VM>,
+}
+
+impl FuncEnv {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<VM: MatchVariable> Default for FuncEnv<VM> {
+    fn default() -> Self {
+        FuncEnv {
+            definitions: vec![],
+            dummy_variable_matcher_factory: PhantomData,
+        }
+    }
+}
+
+impl<VM: MatchVariable> FuncEnv<VM> {
+    /// Adds a function definition such as `f(x, y) = x^2 + y` to the environment. The body
+    /// of the function is checked when an expression calling it is parsed, since only then
+    /// the operators are known.
+    ///
+    /// # Errors
+    ///
+    /// An [`ExError`](crate::ExError) is returned if the text is not a function definition,
+    /// if the parameters are invalid, if a function with the same name has already been
+    /// defined, if the function calls itself directly or via other functions of the environment,
+    /// or if the body is not a single expression.
+    pub fn define(&mut self, definition: &str) -> ExResult<()> {
+        let (name, params, body) = parser::split_definition(definition, VM::is_variable)
+            .ok_or_else(|| {
+            format_exerr!(
+                "'{}' is not a function definition such as 'f(x) = x^2 + 1'",
+                definition.trim()
+            )
+        })?;
+        parser::check_definition(name, &params, VM::is_variable)?;
+        if self.contains(name) {
+            return Err(format_exerr!("function '{}' is already defined", name));
+        }
+        let mut is_visited = vec![false; self.definitions.len()];
+        let mut deps = vec![];
+        self.collect_deps(body, &mut is_visited, &mut deps);
+        let is_recursive = called_names::<VM>(body)
+            .into_iter()
+            .chain(
+                deps.iter()
+                    .flat_map(|idx| called_names::<VM>(&self.definitions[*idx].1)),
+            )
+            .any(|n| n == name);
+        if is_recursive {
+            return Err(format_exerr!(
+                "function '{}' calls itself, recursive definitions are not supported",
+                name
+            ));
+        }
+        if body.trim().is_empty() {
+            return Err(format_exerr!("function '{}' has an empty body", name));
+        }
+        if body.contains(';') {
+            return Err(format_exerr!(
+                "the body of function '{}' must be a single expression",
+                name
+            ));
+        }
+        self.definitions
+            .push((name.to_string(), definition.trim().to_string()));
+        Ok(())
+    }
+
+    /// Returns whether a function with the given name has been defined.
+    pub fn contains(&self, name: &str) -> bool {
+        self.definitions.iter().any(|(n, _)| n == name)
+    }
+
+    /// Returns the names of the defined functions in the order of their definition.
+    pub fn func_names(&self) -> Vec<&str> {
+        self.definitions.iter().map(|(n, _)| n.as_str()).collect()
+    }
+
+    /// Appends the indices of all functions the text depends on to `deps` such that each
+    /// function comes after the functions it calls.
+    fn collect_deps(&self, text: &str, is_visited: &mut [bool], deps: &mut Vec<usize>) {
+        for name in called_names::<VM>(text) {
+            if let Some(idx) = self.definitions.iter().position(|(n, _)| n == name) {
+                if !is_visited[idx] {
+                    is_visited[idx] = true;
+                    self.collect_deps(&self.definitions[idx].1, is_visited, deps);
+                    deps.push(idx);
+                }
+            }
+        }
+    }
+
+    /// Prepends the definitions of all functions the text depends on as statements to the text.
+    /// Definitions are ordered such that each function is defined before it is called.
+    pub(crate) fn prepend_definitions(&self, text: &str) -> String {
+        let mut is_visited = vec![false; self.definitions.len()];
+        let mut deps = vec![];
+        self.collect_deps(text, &mut is_visited, &mut deps);
+        deps.iter()
+            .map(|idx| self.definitions[*idx].1.as_str())
+            .chain(std::iter::once(text))
+            .collect::<Vec<_>>()
+            .join("; ")
+    }
+}
+
+#[test]
+fn test_func_env() -> ExResult<()> {
+    let names = called_names::<VarNameMatcher>("f(x) + sinf (y) + 2*h(f(1))");
+    assert_eq!(names, ["f", "sinf", "h", "f"]);
+    let mut env = FuncEnv::new();
+    env.define("f(x) = x^2 + 1")?;
+    env.define(" g( a , b) = f(a) * b ")?;
+    env.define("k() = 7")?;
+    assert_eq!(env.func_names(), ["f", "g", "k"]);
+    assert_eq!(env.prepend_definitions("sin(x)"), "sin(x)");
+    assert_eq!(
+        env.prepend_definitions("g(x, 2)"),
+        "f(x) = x^2 + 1; g( a , b) = f(a) * b; g(x, 2)"
+    );
+    assert!(env.define("f(y) = y").is_err());
+    assert!(env.define("h(x) = 2*h(x)").is_err());
+    assert!(env.define("h(x) = ").is_err());
+    assert!(env.define("h(x) = a = 2; a").is_err());
+    assert!(env.define("h(x, x) = x").is_err());
+    assert!(env.define("h(2) = 2").is_err());
+    assert!(env.define("h = 2").is_err());
+    assert!(env.define("h(x) == 2").is_err());
+    assert!(!env.contains("h"));
+
+    let mut env = FuncEnv::new();
+    env.define("f(x) = g(x) + 1")?;
+    env.define("g(x) = 2 * h(x)")?;
+    assert!(env.define("h(x) = f(x)").is_err());
+    assert!(env.define("h(x) = g(x) + 1").is_err());
+    env.define("h(x) = x^2")?;
+    assert_eq!(
+        env.prepend_definitions("f(y) + h(y)"),
+        "h(x) = x^2; g(x) = 2 * h(x); f(x) = g(x) + 1; f(y) + h(y)"
+    );
+    Ok(())
+}
@@ -0,0 +1,442 @@
+use std::fmt::{self, Debug, Display, Formatter};
+use std::mem;
+use std::str::FromStr;
+
+use cranelift_codegen::{
+    ir::{types, AbiParam, InstBuilder, MemFlags, UserFuncName, Value},
+    settings::{self, Configurable},
+    Context,
+};
+use cranelift_frontend::{FunctionBuilder, FunctionBuilderContext};
+use cranelift_jit::{JITBuilder, JITModule};
+use cranelift_module::{default_libcall_names, FuncId, Linkage, Module};
+use smallvec::SmallVec;
+
+use crate::{
+    definitions::N_NODES_ON_STACK,
+    expression::{
+        tape::{Instr, Tape, TapeBuilder},
+        Express,
+    },
+    format_exerr,
+    operators::UnaryOp,
+    BinOp, ExError, ExResult, FlatEx, FloatOpsFactory, NumberMatcher,
+};
+
+type NativeFn = extern "C" fn(*const f64) -> f64;
+type UnaryFn = fn(f64) -> f64;
+type BinFn = fn(f64, f64) -> f64;
+
+const CALL_UNARY: &str = "exmex_jit_call_unary";
+const CALL_BIN: &str = "exmex_jit_call_bin";
+
+/// Trampoline with C calling convention through which compiled code calls unary operators
+/// that have no native lowering.
+extern "C" fn call_unary(func: *const UnaryFn, x: f64) -> f64 {
+    // the pointer points into the call table of the `JitEx` that owns the compiled code
+    let func = unsafe { *func };
+    func(x)
+}
+
+/// Trampoline with C calling convention through which compiled code calls binary operators
+/// that have no native lowering.
+extern "C" fn call_bin(func: *const BinFn, a: f64, b: f64) -> f64 {
+    // the pointer points into the call table of the `JitEx` that owns the compiled code
+    let func = unsafe { *func };
+    func(a, b)
+}
+
+fn to_exerr<E: Display>(e: E) -> ExError {
+    ExError::new(format!("jit compilation failed, {}", e).as_str())
+}
+
+/// Function pointers of operators without native lowering. Compiled code references the
+/// entries by their addresses. Hence, the capacities are reserved upfront such that the
+/// entries are never moved.
+struct CallTable {
+    unary: Vec<UnaryFn>,
+    bin: Vec<BinFn>,
+}
+
+impl CallTable {
+    fn with_capacity_for(tape: &Tape<f64>) -> Self {
+        let (n_unary, n_bin) = tape
+            .instrs()
+            .iter()
+            .fold((0, 0), |(n_unary, n_bin), instr| match instr {
+                Instr::Unary(uop, _, _) => (n_unary + uop.len(), n_bin),
+                Instr::Bin(..) => (n_unary, n_bin + 1),
+                _ => (n_unary, n_bin),
+            });
+        CallTable {
+            unary: Vec::with_capacity(n_unary),
+            bin: Vec::with_capacity(n_bin),
+        }
+    }
+
+    fn push_unary(&mut self, func: UnaryFn) -> i64 {
+        assert!(self.unary.len() < self.unary.capacity());
+        self.unary.push(func);
+        self.unary.last().unwrap() as *const UnaryFn as i64
+    }
+
+    fn push_bin(&mut self, func: BinFn) -> i64 {
+        assert!(self.bin.len() < self.bin.capacity());
+        self.bin.push(func);
+        self.bin.last().unwrap() as *const BinFn as i64
+    }
+}
+
+struct Translator<'a, 'b> {
+    builder: FunctionBuilder<'b>,
+    module: &'a mut JITModule,
+    calls: &'a mut CallTable,
+    call_unary: FuncId,
+    call_bin: FuncId,
+}
+
+impl<'a, 'b> Translator<'a, 'b> {
+    /// Operators are identified by their representations in
+    /// [`FloatOpsFactory`](FloatOpsFactory) since the compiled expression is a `FlatEx<f64>`.
+    fn apply_unary(&mut self, uop: &UnaryOp<f64>, reprs: &[&str], x: Value) -> ExResult<Value> {
+        let funcs = uop.funcs_to_be_composed();
+        if funcs.len() != reprs.len() {
+            return Err(format_exerr!(
+                "cannot compile unary operator with {} functions and {} representations",
+                funcs.len(),
+                reprs.len()
+            ));
+        }
+        // the last function is applied first, see UnaryOp::apply
+        let mut result = x;
+        for (&func, &repr) in funcs.iter().zip(reprs).rev() {
+            result = match repr {
+                "+" => result,
+                "-" => self.builder.ins().fneg(result),
+                "abs" => self.builder.ins().fabs(result),
+                "sqrt" => self.builder.ins().sqrt(result),
+                _ => {
+                    let callee = self
+                        .module
+                        .declare_func_in_func(self.call_unary, self.builder.func);
+                    let addr = self.calls.push_unary(func);
+                    let addr = self.builder.ins().iconst(types::I64, addr);
+                    let call = self.builder.ins().call(callee, &[addr, result]);
+                    self.builder.inst_results(call)[0]
+                }
+            };
+        }
+        Ok(result)
+    }
+
+    fn apply_bin(&mut self, bop: &BinOp<f64>, repr: &str, a: Value, b: Value) -> Value {
+        match repr {
+            "+" => self.builder.ins().fadd(a, b),
+            "-" => self.builder.ins().fsub(a, b),
+            "*" => self.builder.ins().fmul(a, b),
+            "/" => self.builder.ins().fdiv(a, b),
+            _ => {
+                let callee = self
+                    .module
+                    .declare_func_in_func(self.call_bin, self.builder.func);
+                let addr = self.calls.push_bin(bop.apply);
+                let addr = self.builder.ins().iconst(types::I64, addr);
+                let call = self.builder.ins().call(callee, &[addr, a, b]);
+                self.builder.inst_results(call)[0]
+            }
+        }
+    }
+
+    /// Emits the instructions of the tape, i.e., the operations of the interpreter in
+    /// `eval_flatex` where common sub-expressions are computed once.
+    fn translate(mut self, tape: &Tape<f64>) -> ExResult<()> {
+        let block = self.builder.create_block();
+        self.builder.append_block_params_for_function_params(block);
+        self.builder.switch_to_block(block);
+        self.builder.seal_block(block);
+        let vars_ptr = self.builder.block_params(block)[0];
+
+        let mut slots = SmallVec::<[Value; N_NODES_ON_STACK]>::new();
+        for instr in tape.instrs() {
+            let res = match instr {
+                Instr::Num(n) => self.builder.ins().f64const(*n),
+                Instr::Var(idx) => self.builder.ins().load(
+                    types::F64,
+                    MemFlags::trusted(),
+                    vars_ptr,
+                    (idx * mem::size_of::<f64>()) as i32,
+                ),
+                Instr::Unary(uop, reprs, idx) => self.apply_unary(uop, reprs, slots[*idx])?,
+                Instr::Bin(bop, repr, idx_1, idx_2) => {
+                    self.apply_bin(bop, repr, slots[*idx_1], slots[*idx_2])
+                }
+            };
+            slots.push(res);
+        }
+        self.builder.ins().return_(&[slots[tape.outputs()[0]]]);
+        self.builder.finalize();
+        Ok(())
+    }
+}
+
+fn compile(flatex: &FlatEx<f64>) -> ExResult<(JITModule, NativeFn, CallTable)> {
+    let mut tape_builder = TapeBuilder::new();
+    tape_builder.push_flat(flatex.nodes(), flatex.ops(), flatex.prio_indices(), |idx| idx);
+    let tape = tape_builder.build();
+
+    let mut flag_builder = settings::builder();
+    flag_builder
+        .set("use_colocated_libcalls", "false")
+        .map_err(to_exerr)?;
+    flag_builder.set("is_pic", "false").map_err(to_exerr)?;
+    flag_builder.set("opt_level", "speed").map_err(to_exerr)?;
+    let isa = cranelift_native::builder()
+        .map_err(to_exerr)?
+        .finish(settings::Flags::new(flag_builder))
+        .map_err(to_exerr)?;
+    let mut jit_builder = JITBuilder::with_isa(isa, default_libcall_names());
+    jit_builder.symbol(CALL_UNARY, call_unary as *const u8);
+    jit_builder.symbol(CALL_BIN, call_bin as *const u8);
+    let mut module = JITModule::new(jit_builder);
+
+    let ptr_type = module.target_config().pointer_type();
+    let mut sig_unary = module.make_signature();
+    sig_unary.params.push(AbiParam::new(types::I64));
+    sig_unary.params.push(AbiParam::new(types::F64));
+    sig_unary.returns.push(AbiParam::new(types::F64));
+    let call_unary = module
+        .declare_function(CALL_UNARY, Linkage::Import, &sig_unary)
+        .map_err(to_exerr)?;
+    let mut sig_bin = module.make_signature();
+    sig_bin.params.push(AbiParam::new(types::I64));
+    sig_bin.params.push(AbiParam::new(types::F64));
+    sig_bin.params.push(AbiParam::new(types::F64));
+    sig_bin.returns.push(AbiParam::new(types::F64));
+    let call_bin = module
+        .declare_function(CALL_BIN, Linkage::Import, &sig_bin)
+        .map_err(to_exerr)?;
+
+    let mut sig = module.make_signature();
+    sig.params.push(AbiParam::new(ptr_type));
+    sig.returns.push(AbiParam::new(types::F64));
+    let func_id = module
+        .declare_function("eval", Linkage::Local, &sig)
+        .map_err(to_exerr)?;
+
+    let mut ctx: Context = module.make_context();
+    ctx.func.signature = sig;
+    ctx.func.name = UserFuncName::user(0, func_id.as_u32());
+    let mut func_ctx = FunctionBuilderContext::new();
+    let mut calls = CallTable::with_capacity_for(&tape);
+    let translator = Translator {
+        builder: FunctionBuilder::new(&mut ctx.func, &mut func_ctx),
+        module: &mut module,
+        calls: &mut calls,
+        call_unary,
+        call_bin,
+    };
+    translator.translate(&tape)?;
+    module
+        .define_function(func_id, &mut ctx)
+        .map_err(to_exerr)?;
+    module.clear_context(&mut ctx);
+    module.finalize_definitions().map_err(to_exerr)?;
+
+    let code = module.get_finalized_function(func_id);
+    // the signature of the generated function is `sig` from above
+    let func = unsafe { mem::transmute::<*const u8, NativeFn>(code) };
+    Ok((module, func, calls))
+}
+
+/// An expression compiled to native machine code with [Cranelift](https://cranelift.dev/).
+/// Available with the feature `jit`.
+///
+/// Binary operators `+`, `-`, `*`, `/` and unary operators `+`, `-`, `abs`, and `sqrt` of
+/// [`FloatOpsFactory`](FloatOpsFactory) are lowered to native instructions. All other operators
+/// are called from the compiled code through their function pointers. Common sub-expressions
+/// are computed only once. If the host machine
+/// is not supported by Cranelift, the expression is evaluated by the interpreter of
+/// [`FlatEx`](FlatEx).
+///
+/// ```rust
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// #
+/// use exmex::prelude::*;
+/// use exmex::JitEx;
+/// let expr = JitEx::from_flatex(exmex::parse::<f64>("sin(x)*y + 2^x")?);
+/// let result = expr.call(&[1.0, 2.0]);
+/// assert!((result - (1.0f64.sin() * 2.0 + 2.0)).abs() < 1e-12);
+/// #
+/// #     Ok(())
+/// # }
+/// ```
+pub struct JitEx {
+    flatex: FlatEx<f64>,
+    module: Option<JITModule>,
+    native: Option<NativeFn>,
+    // referenced by the compiled code, hence dropped after the module
+    _calls: Option<CallTable>,
+}
+
+impl JitEx {
+    /// Compiles a flat expression. Falls back to the interpreter if the compilation fails.
+    pub fn from_flatex(flatex: FlatEx<f64>) -> Self {
+        match compile(&flatex) {
+            Ok((module, native, calls)) => JitEx {
+                flatex,
+                module: Some(module),
+                native: Some(native),
+                _calls: Some(calls),
+            },
+            Err(_) => JitEx {
+                flatex,
+                module: None,
+                native: None,
+                _calls: None,
+            },
+        }
+    }
+
+    /// Returns `true` if the expression has been compiled to native code and `false` if it
+    /// is evaluated by the interpreter.
+    pub fn is_native(&self) -> bool {
+        self.native.is_some()
+    }
+
+    /// Returns the underlying flat expression.
+    pub fn flatex(&self) -> &FlatEx<f64> {
+        &self.flatex
+    }
+
+    /// Evaluates the expression without returning a `Result`. The variable values are
+    /// passed in alphabetical order of the variable names as in [`eval`](Express::eval).
+    ///
+    /// # Panics
+    ///
+    /// If `vars` contains less values than the expression has variables.
+    pub fn call(&self, vars: &[f64]) -> f64 {
+        assert!(
+            vars.len() >= self.flatex.var_names().len(),
+            "expression contains {} vars but only {} values have been passed",
+            self.flatex.var_names().len(),
+            vars.len()
+        );
+        match self.native {
+            Some(native) => native(vars.as_ptr()),
+            None => self.flatex.eval_relaxed(vars).unwrap(),
+        }
+    }
+}
+
+impl Drop for JitEx {
+    fn drop(&mut self) {
+        self.native = None;
+        if let Some(module) = self.module.take() {
+            // no function pointer into the module's memory is left
+            unsafe { module.free_memory() };
+        }
+    }
+}
+
+impl Clone for JitEx {
+    fn clone(&self) -> Self {
+        JitEx::from_flatex(self.flatex.clone())
+    }
+}
+
+impl Debug for JitEx {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct("JitEx")
+            .field("flatex", &self.flatex)
+            .field("is_native", &self.is_native())
+            .finish()
+    }
+}
+
+impl Express<f64> for JitEx {
+    type LiteralMatcher = NumberMatcher;
+    type OperatorFactory = FloatOpsFactory<f64>;
+
+    fn eval(&self, vars: &[f64]) -> ExResult<f64> {
+        if self.flatex.var_names().len() != vars.len() {
+            return Err(format_exerr!(
+                "expression contains {} vars which is different to the length {} of the passed slice",
+                self.flatex.var_names().len(),
+                vars.len()
+            ));
+        }
+        Ok(self.call(vars))
+    }
+
+    fn eval_relaxed(&self, vars: &[f64]) -> ExResult<f64> {
+        if self.flatex.var_names().len() > vars.len() {
+            return Err(format_exerr!(
+                "expression contains {} vars which is higher than the length {} of the passed slice",
+                self.flatex.var_names().len(),
+                vars.len()
+            ));
+        }
+        Ok(self.call(vars))
+    }
+
+    fn unparse(&self) -> &str {
+        self.flatex.unparse()
+    }
+
+    fn var_names(&self) -> &[String] {
+        self.flatex.var_names()
+    }
+}
+
+impl FromStr for JitEx {
+    type Err = ExError;
+
+    fn from_str(text: &str) -> ExResult<Self> {
+        Ok(JitEx::from_flatex(FlatEx::from_str(text)?))
+    }
+}
+
+/// The expression is displayed as a string created by [`unparse`](JitEx::unparse).
+impl Display for JitEx {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}", self.unparse())
+    }
+}
+
+#[cfg(test)]
+use crate::util::assert_float_eq_f64;
+
+#[test]
+fn test_jit() -> ExResult<()> {
+    fn test(text: &str, vars: &[f64]) -> ExResult<()> {
+        let flatex = FlatEx::<f64>::from_str(text)?;
+        let jitex = JitEx::from_flatex(flatex.clone());
+        assert!(jitex.is_native());
+        assert_float_eq_f64(jitex.eval(vars)?, flatex.eval(vars)?);
+        let flatex = FlatEx::<f64>::from_str_wo_compile(text)?;
+        let jitex = JitEx::from_flatex(flatex.clone());
+        assert_float_eq_f64(jitex.call(vars), flatex.eval(vars)?);
+        Ok(())
+    }
+    test("1.5", &[])?;
+    test("x", &[2.3])?;
+    test("-x+y*z/2-abs(-sqrt(4))", &[1.0, 2.0, 3.0])?;
+    test("sin(x)^cos(y)+--ln(2.5)", &[0.7, 0.2])?;
+    test(
+        "x*0.02*sin(-(3*(2*sin(x-1/(sin(y*5)+(5.0-1/z))))))",
+        &[1.0, 3.0, 4.0],
+    )?;
+    test(
+        "x*0.2*5/4+x*2*4*1*1*1*1*1*1*1+7*sin(y)-z/sin(3.0/2/(1-x*4*1*1*1*1))",
+        &[2.21, 2.0, 3.0],
+    )?;
+    test("-(-(1+x))^-(2-y)", &[1.5, 0.5])?;
+    test("(((a+x^2*x^2)))", &[3.0, 2.21])?;
+    test("sin(x)*sin(x) + abs(sin(x)) - log(y)*ln(y)", &[0.3, 2.5])?;
+    let jitex = JitEx::from_str("x+y")?;
+    assert!(jitex.eval(&[1.0]).is_err());
+    assert_float_eq_f64(jitex.eval_relaxed(&[1.0, 2.0, 3.0])?, 3.0);
+    assert_eq!(jitex.clone().unparse(), "x+y");
+    Ok(())
+}
@@ -1,9 +1,14 @@
 use std::fmt::Debug;
 
 use crate::{parser, ExResult, MakeOperators};
+pub mod expr_set;
 pub mod flat;
+pub mod func_env;
+#[cfg(feature = "jit")]
+pub mod jit;
 #[cfg(feature = "serde")]
 mod serde;
+mod tape;
 
 /// Expressions implementing this trait can be parsed from stings,
 /// evaluated for specific variable values, and unparsed, i.e.,
@@ -79,7 +84,7 @@ pub trait MatchLiteral {
     fn is_literal(text: &str) -> Option<&str>;
 }
 
-/// Default factory to match numeric literals.
+/// Default factory to match numeric literals such as `2`, `.5`, `1e-3`, or `6.022E23`.
 #[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Debug)]
 pub struct NumberMatcher;
 impl MatchLiteral for NumberMatcher {
@@ -88,6 +93,24 @@ impl MatchLiteral for NumberMatcher {
     }
 }
 
+/// Implement this trait to create a matcher for custom variable names. Variable names between
+/// curly brackets such as `{x y}` are always possible.
+pub trait MatchVariable {
+    /// This method is expected to return `Some(matching_str)` in case of a match of
+    /// a variable name at the beginning of the input and `None` otherwise.
+    fn is_variable(text: &str) -> Option<&str>;
+}
+
+/// Default factory to match variable names consisting of letters, Greek letters, numbers, and
+/// underscores that do not start with a number.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Debug)]
+pub struct VarNameMatcher;
+impl MatchVariable for VarNameMatcher {
+    fn is_variable(text: &str) -> Option<&str> {
+        parser::is_var_name_text(text)
+    }
+}
+
 /// Helper to implement a struct called `$matcher_name` that implements
 /// [`MatchLiteral`](MatchLiteral) and matches the regex pattern `$regex_pattern`.
 ///
@@ -114,3 +137,30 @@ macro_rules! literal_matcher_from_pattern {
         }
     };
 }
+
+/// Helper to implement a struct called `$matcher_name` that implements
+/// [`MatchVariable`](MatchVariable) and matches the regex pattern `$regex_pattern`.
+///
+/// For instance, to allow dotted variable names such as `sensor.temp` one can create a struct
+/// with name `DottedMatcher` via
+/// ```rust
+/// use exmex::{variable_matcher_from_pattern, MatchVariable};
+/// variable_matcher_from_pattern!(DottedMatcher, r"^[a-zA-Z_][a-zA-Z_0-9]*(\.[a-zA-Z_][a-zA-Z_0-9]*)*");
+/// ```
+#[macro_export]
+macro_rules! variable_matcher_from_pattern {
+    ($matcher_name:ident, $regex_pattern:expr) => {
+        /// Variable matcher type that was created with the macro
+        /// [`variable_matcher_from_pattern`](variable_matcher_from_pattern).
+        #[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Debug)]
+        pub struct $matcher_name;
+        impl MatchVariable for $matcher_name {
+            fn is_variable(text: &str) -> Option<&str> {
+                $crate::lazy_static::lazy_static! {
+                    static ref RE_VAR_NAME: $crate::regex::Regex = $crate::regex::Regex::new($regex_pattern).unwrap();
+                }
+                RE_VAR_NAME.find(text).map(|m|m.as_str())
+            }
+        }
+    };
+}
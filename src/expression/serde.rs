@@ -4,7 +4,7 @@ use serde::{de, de::Visitor, Deserialize, Deserializer, Serialize, Serializer};
 
 use crate::data_type::DataType;
 
-use crate::{prelude::*, MatchLiteral, MakeOperators};
+use crate::{prelude::*, MatchLiteral, MatchVariable, MakeOperators};
 
 fn serialize<T: Clone, S: Serializer, Ex: Express<T>>(
     serializer: S,
@@ -15,8 +15,8 @@ fn serialize<T: Clone, S: Serializer, Ex: Express<T>>(
     )
 }
 
-impl<T: DataType, OF: MakeOperators<T>, LMF: MatchLiteral> Serialize
-    for FlatEx<T, OF, LMF>
+impl<T: DataType, OF: MakeOperators<T>, LMF: MatchLiteral, VM: MatchVariable> Serialize
+    for FlatEx<T, OF, LMF, VM>
 {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -26,8 +26,8 @@ impl<T: DataType, OF: MakeOperators<T>, LMF: MatchLiteral> Serialize
     }
 }
 
-impl<'de, T: DataType + 'de, OF: MakeOperators<T>, LMF: MatchLiteral> Deserialize<'de>
-    for FlatEx<T, OF, LMF>
+impl<'de, T: DataType + 'de, OF: MakeOperators<T>, LMF: MatchLiteral, VM: MatchVariable>
+    Deserialize<'de> for FlatEx<T, OF, LMF, VM>
 where
     <T as std::str::FromStr>::Err: Debug,
 {
@@ -39,23 +39,25 @@ where
             lifetime_dummy: PhantomData,
             of_dummy: PhantomData,
             literal_matcher_dummy: PhantomData,
+            variable_matcher_dummy: PhantomData,
         })
     }
 }
 
 #[derive(Debug)]
-struct FlatExVisitor<'a, T, OF, LMF> {
+struct FlatExVisitor<'a, T, OF, LMF, VM> {
     lifetime_dummy: PhantomData<&'a T>,
     of_dummy: PhantomData<OF>,
     literal_matcher_dummy: PhantomData<LMF>,
+    variable_matcher_dummy: PhantomData<VM>,
 }
 
-impl<'de, T: DataType, OF: MakeOperators<T>, LMF: MatchLiteral> Visitor<'de>
-    for FlatExVisitor<'de, T, OF, LMF>
+impl<'de, T: DataType, OF: MakeOperators<T>, LMF: MatchLiteral, VM: MatchVariable> Visitor<'de>
+    for FlatExVisitor<'de, T, OF, LMF, VM>
 where
     <T as std::str::FromStr>::Err: Debug,
 {
-    type Value = FlatEx<T, OF, LMF>;
+    type Value = FlatEx<T, OF, LMF, VM>;
 
     fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "a borrowed &str that can be parsed by `exmex` crate")
@@ -119,6 +121,7 @@ fn test_ser_de_non_float() {
                             apply: |a: i32, b: i32| a % b,
                             prio: 1,
                             is_commutative: false,
+                            is_right_assoc: false,
                         },
                     ),
                     Operator::make_bin(
@@ -127,6 +130,7 @@ fn test_ser_de_non_float() {
                             apply: |a: i32, b: i32| a / b,
                             prio: 1,
                             is_commutative: false,
+                            is_right_assoc: false,
                         },
                     ),
                 ]
@@ -0,0 +1,228 @@
+use std::{collections::HashMap, fmt::Debug};
+
+use smallvec::SmallVec;
+
+use crate::{
+    definitions::N_NODES_ON_STACK,
+    operators::{BinOp, UnaryOp},
+};
+
+use super::flat::{
+    detail::{FlatNodeKind, FlatNodeVec, FlatOpVec, UnaryReprVec},
+    ExprIdxVec,
+};
+
+pub type SlotVec<T> = SmallVec<[T; N_NODES_ON_STACK]>;
+
+/// Instruction of a [`Tape`](Tape). Operands are referenced by the indices of the instructions
+/// that compute them. Operators come with their representations.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Debug)]
+pub enum Instr<T: Clone> {
+    Num(T),
+    Var(usize),
+    Unary(UnaryOp<T>, UnaryReprVec, usize),
+    Bin(BinOp<T>, &'static str, usize, usize),
+}
+
+/// Keys identify structurally identical sub-expressions. Operators are identified by their
+/// function pointers as done in the conversion of flat expressions to deep expressions.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+enum Key {
+    Num(String),
+    Var(usize),
+    Unary(SmallVec<[usize; 4]>, usize),
+    Bin(usize, usize, usize),
+}
+
+/// Sequence of instructions where each structurally identical sub-expression occurs only once.
+/// The tape is evaluated front to back and can have multiple outputs.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Debug)]
+pub struct Tape<T: Clone> {
+    instrs: Vec<Instr<T>>,
+    outputs: SmallVec<[usize; 1]>,
+}
+
+impl<T> Tape<T>
+where
+    T: Clone + Debug,
+{
+    #[cfg(feature = "jit")]
+    pub fn instrs(&self) -> &[Instr<T>] {
+        &self.instrs
+    }
+
+    pub fn outputs(&self) -> &[usize] {
+        &self.outputs
+    }
+
+    /// Number of instructions that apply operators.
+    pub fn n_operations(&self) -> usize {
+        self.instrs
+            .iter()
+            .filter(|instr| matches!(instr, Instr::Unary(..) | Instr::Bin(..)))
+            .count()
+    }
+
+    pub fn eval_slots(&self, vars: &[T]) -> SlotVec<T> {
+        let mut slots = SlotVec::<T>::with_capacity(self.instrs.len());
+        for instr in &self.instrs {
+            let res = match instr {
+                Instr::Num(n) => n.clone(),
+                Instr::Var(idx) => vars[*idx].clone(),
+                Instr::Unary(uop, _, idx) => uop.apply(slots[*idx].clone()),
+                Instr::Bin(bop, _, idx_1, idx_2) => {
+                    (bop.apply)(slots[*idx_1].clone(), slots[*idx_2].clone())
+                }
+            };
+            slots.push(res);
+        }
+        slots
+    }
+
+    /// Evaluates the tape and returns the value of the first output.
+    pub fn eval(&self, vars: &[T]) -> T {
+        self.eval_slots(vars)[self.outputs[0]].clone()
+    }
+}
+
+/// Creates a [`Tape`](Tape) from one or more flat expressions.
+pub struct TapeBuilder<T: Clone> {
+    instrs: Vec<Instr<T>>,
+    outputs: SmallVec<[usize; 1]>,
+    known: HashMap<Key, usize>,
+}
+
+impl<T> TapeBuilder<T>
+where
+    T: Clone + Debug,
+{
+    pub fn new() -> Self {
+        TapeBuilder {
+            instrs: vec![],
+            outputs: SmallVec::new(),
+            known: HashMap::new(),
+        }
+    }
+
+    fn push(&mut self, key: Key, instr: Instr<T>) -> usize {
+        let n_instrs = self.instrs.len();
+        let idx = *self.known.entry(key).or_insert(n_instrs);
+        if idx == n_instrs {
+            self.instrs.push(instr);
+        }
+        idx
+    }
+
+    fn push_unary(&mut self, uop: &UnaryOp<T>, reprs: &UnaryReprVec, idx: usize) -> usize {
+        if uop.len() == 0 {
+            idx
+        } else {
+            let funcs = uop
+                .funcs_to_be_composed()
+                .iter()
+                .map(|f| *f as usize)
+                .collect();
+            self.push(
+                Key::Unary(funcs, idx),
+                Instr::Unary(uop.clone(), reprs.clone(), idx),
+            )
+        }
+    }
+
+    /// Operands are never swapped to identify, e.g., `a+b` with `b+a`. The flag
+    /// [`is_commutative`](BinOp::is_commutative) is not guaranteed to hold for all operands, e.g.,
+    /// for comparisons.
+    fn push_bin(&mut self, bop: &BinOp<T>, repr: &'static str, idx_1: usize, idx_2: usize) -> usize {
+        self.push(
+            Key::Bin(bop.apply as usize, idx_1, idx_2),
+            Instr::Bin(bop.clone(), repr, idx_1, idx_2),
+        )
+    }
+
+    /// Appends the instructions of a flat expression and registers its result as output.
+    /// The operations are visited in the same order as during the evaluation of flat expressions.
+    ///
+    /// # Arguments
+    ///
+    /// * `nodes`, `ops`, `prio_indices` - components of the flat expression
+    /// * `var_map` - maps the variable indices of the expression to indices of the variables
+    ///   passed for evaluation of the tape
+    pub fn push_flat<F>(
+        &mut self,
+        nodes: &FlatNodeVec<T>,
+        ops: &FlatOpVec<T>,
+        prio_indices: &ExprIdxVec,
+        var_map: F,
+    ) -> usize
+    where
+        F: Fn(usize) -> usize,
+    {
+        let mut slots = nodes
+            .iter()
+            .map(|node| {
+                let leaf = match &node.kind {
+                    // literals are identified via their debug representation since we do
+                    // not require data types to be comparable
+                    FlatNodeKind::Num(n) => {
+                        self.push(Key::Num(format!("{:?}", n)), Instr::Num(n.clone()))
+                    }
+                    FlatNodeKind::Var(idx) => {
+                        let idx = var_map(*idx);
+                        self.push(Key::Var(idx), Instr::Var(idx))
+                    }
+                };
+                self.push_unary(&node.unary_op, &node.unary_reprs, leaf)
+            })
+            .collect::<SmallVec<[usize; N_NODES_ON_STACK]>>();
+        let mut ignore: SmallVec<[bool; N_NODES_ON_STACK]> =
+            smallvec::smallvec![false; nodes.len()];
+        for &bin_op_idx in prio_indices {
+            let mut shift_left = 0usize;
+            while ignore[bin_op_idx - shift_left] {
+                shift_left += 1usize;
+            }
+            let mut shift_right = 1usize;
+            while ignore[bin_op_idx + shift_right] {
+                shift_right += 1usize;
+            }
+            let op = &ops[bin_op_idx];
+            let bin_res = self.push_bin(
+                &op.bin_op,
+                op.bin_repr,
+                slots[bin_op_idx - shift_left],
+                slots[bin_op_idx + shift_right],
+            );
+            slots[bin_op_idx - shift_left] = self.push_unary(&op.unary_op, &op.unary_reprs, bin_res);
+            ignore[bin_op_idx + shift_right] = true;
+        }
+        self.outputs.push(slots[0]);
+        slots[0]
+    }
+
+    pub fn build(self) -> Tape<T> {
+        Tape {
+            instrs: self.instrs,
+            outputs: self.outputs,
+        }
+    }
+}
+
+/// Returns a tape for the flat expression if common sub-expressions make it cheaper to
+/// evaluate than the flat expression itself.
+pub fn make_if_beneficial<T: Clone + Debug>(
+    nodes: &FlatNodeVec<T>,
+    ops: &FlatOpVec<T>,
+    prio_indices: &ExprIdxVec,
+) -> Option<Tape<T>> {
+    let mut builder = TapeBuilder::new();
+    builder.push_flat(nodes, ops, prio_indices, |idx| idx);
+    let tape = builder.build();
+    let n_flat_operations = nodes.iter().filter(|n| n.unary_op.len() > 0).count()
+        + ops.len()
+        + ops.iter().filter(|op| op.unary_op.len() > 0).count();
+    if tape.n_operations() < n_flat_operations {
+        Some(tape)
+    } else {
+        None
+    }
+}
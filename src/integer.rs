@@ -0,0 +1,373 @@
+use std::{
+    fmt::{self, Debug, Display, Formatter},
+    marker::PhantomData,
+    str::FromStr,
+};
+
+use num::{
+    traits::{CheckedEuclid, CheckedRem, CheckedShl, CheckedShr},
+    PrimInt,
+};
+
+use crate::{
+    format_exerr, BinOp, ExError, ExResult, FlatEx, MakeOperators, NumberMatcher, Operator,
+};
+
+/// Signed or unsigned primitive integer types such as `i64` or `u32` that can be wrapped
+/// into a [`CheckedInt`](CheckedInt).
+pub trait IntType:
+    PrimInt + CheckedRem + CheckedShl + CheckedShr + CheckedEuclid + FromStr + Debug + Display
+{
+}
+impl<I> IntType for I where
+    I: PrimInt + CheckedRem + CheckedShl + CheckedShr + CheckedEuclid + FromStr + Debug + Display
+{
+}
+
+/// Integer whose operations are checked by the operators of [`IntOpsFactory`](IntOpsFactory).
+/// Since operators cannot return errors, overflows and divisions by zero result in the
+/// variant `Error` instead of a panic or a silent wrap-around. Similar to [`Val`](crate::Val),
+/// the error can be transformed into a `Result` via [`to_result`](CheckedInt::to_result).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CheckedInt<I: IntType = i64> {
+    Value(I),
+    Error(ExError),
+}
+
+impl<I: IntType> CheckedInt<I> {
+    /// Returns the integer or the contained error.
+    pub fn to_result(self) -> ExResult<I> {
+        match self {
+            CheckedInt::Value(x) => Ok(x),
+            CheckedInt::Error(e) => Err(e),
+        }
+    }
+}
+
+impl<I: IntType> From<I> for CheckedInt<I> {
+    fn from(x: I) -> Self {
+        CheckedInt::Value(x)
+    }
+}
+
+impl<I: IntType> Display for CheckedInt<I> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            CheckedInt::Value(x) => write!(f, "{}", x),
+            CheckedInt::Error(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl<I: IntType> FromStr for CheckedInt<I> {
+    type Err = ExError;
+    fn from_str(s: &str) -> ExResult<Self> {
+        s.parse::<I>()
+            .map(CheckedInt::Value)
+            .map_err(|_| format_exerr!("cannot parse '{}' as integer", s))
+    }
+}
+
+/// Semantics of the integer division `/` and the remainder `%` of an [`IntOpsFactory`](IntOpsFactory).
+/// The methods return `None` in case of an overflow. Divisors are never zero.
+pub trait DivisionMode: Clone + Debug {
+    fn div<I: IntType>(a: I, b: I) -> Option<I>;
+    fn rem<I: IntType>(a: I, b: I) -> Option<I>;
+}
+
+/// The quotient is rounded towards zero and the remainder has the sign of the dividend as in
+/// Rust and C, e.g., `-7 / 2 == -3` and `-7 % 2 == -1`.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Debug)]
+pub struct Truncated;
+impl DivisionMode for Truncated {
+    fn div<I: IntType>(a: I, b: I) -> Option<I> {
+        a.checked_div(&b)
+    }
+    fn rem<I: IntType>(a: I, b: I) -> Option<I> {
+        a.checked_rem(&b)
+    }
+}
+
+/// The quotient is rounded towards negative infinity and the remainder has the sign of the
+/// divisor as in Python, e.g., `-7 / 2 == -4` and `-7 % 2 == 1`.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Debug)]
+pub struct Floored;
+impl DivisionMode for Floored {
+    fn div<I: IntType>(a: I, b: I) -> Option<I> {
+        let q = a.checked_div(&b)?;
+        let r = a.checked_rem(&b)?;
+        if r != I::zero() && ((r < I::zero()) != (b < I::zero())) {
+            q.checked_sub(&I::one())
+        } else {
+            Some(q)
+        }
+    }
+    fn rem<I: IntType>(a: I, b: I) -> Option<I> {
+        let r = a.checked_rem(&b)?;
+        if r != I::zero() && ((r < I::zero()) != (b < I::zero())) {
+            r.checked_add(&b)
+        } else {
+            Some(r)
+        }
+    }
+}
+
+/// The remainder is always non-negative, e.g., `-7 / 2 == -4`, `-7 % 2 == 1`, `7 / -2 == -3`,
+/// and `7 % -2 == 1`.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Debug)]
+pub struct Euclidean;
+impl DivisionMode for Euclidean {
+    fn div<I: IntType>(a: I, b: I) -> Option<I> {
+        a.checked_div_euclid(&b)
+    }
+    fn rem<I: IntType>(a: I, b: I) -> Option<I> {
+        a.checked_rem_euclid(&b)
+    }
+}
+
+fn checked_bin<I: IntType>(
+    a: CheckedInt<I>,
+    b: CheckedInt<I>,
+    op: &str,
+    f: fn(I, I) -> Option<I>,
+) -> CheckedInt<I> {
+    match (a, b) {
+        (CheckedInt::Value(a), CheckedInt::Value(b)) => match f(a, b) {
+            Some(x) => CheckedInt::Value(x),
+            None => CheckedInt::Error(format_exerr!("overflow in {} {} {}", a, op, b)),
+        },
+        (CheckedInt::Error(e), _) | (_, CheckedInt::Error(e)) => CheckedInt::Error(e),
+    }
+}
+
+fn div_or_rem<I: IntType>(
+    a: CheckedInt<I>,
+    b: CheckedInt<I>,
+    op: &str,
+    f: fn(I, I) -> Option<I>,
+) -> CheckedInt<I> {
+    match (a, b) {
+        (CheckedInt::Value(a), CheckedInt::Value(b)) if b == I::zero() => {
+            CheckedInt::Error(format_exerr!("division by zero in {} {} {}", a, op, b))
+        }
+        (a, b) => checked_bin(a, b, op, f),
+    }
+}
+
+fn checked_unary<I: IntType>(a: CheckedInt<I>, op: &str, f: fn(I) -> Option<I>) -> CheckedInt<I> {
+    match a {
+        CheckedInt::Value(a) => match f(a) {
+            Some(x) => CheckedInt::Value(x),
+            None => CheckedInt::Error(format_exerr!("overflow in {}({})", op, a)),
+        },
+        CheckedInt::Error(e) => CheckedInt::Error(e),
+    }
+}
+
+fn pow<I: IntType>(a: I, b: I) -> Option<I> {
+    num::checked_pow(a, b.to_usize()?)
+}
+
+fn shift<I: IntType>(a: I, b: I, f: fn(&I, u32) -> Option<I>) -> Option<I> {
+    f(&a, b.to_u32()?)
+}
+
+/// Factory of default operators for [`CheckedInt`](CheckedInt)s based on signed or unsigned
+/// primitive integers such as `i64` or `u32`. All operations are checked. Overflows, divisions by
+/// zero, negative exponents, and shifts by negative amounts or by at least the number of bits
+/// result in an error instead of a panic. The type parameter `D` determines the semantics of `/`
+/// and `%` and can be one of [`Truncated`](Truncated), [`Floored`](Floored), or [`Euclidean`](Euclidean).
+///
+/// |representation|description|
+/// |--------------|-----------|
+/// |`^`| power with non-negative exponent |
+/// |`*`| product |
+/// |`/`| integer division |
+/// |`%`| remainder |
+/// |`+`| addition as binary or identity as unary operator|
+/// |`-`| subtraction as binary or inverting the sign as unary operator |
+/// |`&`| bitwise and |
+/// |`\|`| bitwise or |
+/// |`XOR`| bitwise exclusive or |
+/// |`<<`| left shift |
+/// |`>>`| right shift, arithmetic for signed integers |
+/// |`abs`| absolute value |
+/// |`signum`| signum |
+/// |`MIN`| smallest representable integer |
+/// |`MAX`| largest representable integer |
+///
+/// ```rust
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// #
+/// use exmex::prelude::*;
+/// use exmex::{CheckedInt, Floored, FlatExInt};
+/// let expr = FlatExInt::<i64>::from_str("(x + 3) / 2 & 6")?;
+/// assert_eq!(expr.eval(&[CheckedInt::Value(8)])?.to_result()?, 4);
+/// let expr = FlatExInt::<i64, Floored>::from_str("-7 / 2")?;
+/// assert_eq!(expr.eval(&[])?.to_result()?, -4);
+/// let expr = FlatExInt::<u8>::from_str("x * 2")?;
+/// let err = expr.eval(&[CheckedInt::Value(200)])?.to_result().unwrap_err();
+/// assert_eq!(err.msg(), "overflow in 200 * 2");
+/// #
+/// #     Ok(())
+/// # }
+/// ```
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Debug)]
+pub struct IntOpsFactory<I: IntType = i64, D: DivisionMode = Truncated> {
+    dummy_i: PhantomData<I>,
+    dummy_d: PhantomData<D>,
+}
+
+impl<I: IntType, D: DivisionMode> MakeOperators<CheckedInt<I>> for IntOpsFactory<I, D> {
+    /// Returns the default operators.
+    fn make<'a>() -> Vec<Operator<'a, CheckedInt<I>>> {
+        vec![
+            Operator::make_bin(
+                "^",
+                BinOp {
+                    apply: |a, b| checked_bin(a, b, "^", pow),
+                    prio: 6,
+                    is_commutative: false,
+                    is_right_assoc: true,
+                },
+            ),
+            Operator::make_bin(
+                "*",
+                BinOp {
+                    apply: |a, b| checked_bin(a, b, "*", |a, b| a.checked_mul(&b)),
+                    prio: 4,
+                    is_commutative: true,
+                    is_right_assoc: false,
+                },
+            ),
+            Operator::make_bin(
+                "/",
+                BinOp {
+                    apply: |a, b| div_or_rem(a, b, "/", D::div),
+                    prio: 5,
+                    is_commutative: false,
+                    is_right_assoc: false,
+                },
+            ),
+            Operator::make_bin(
+                "%",
+                BinOp {
+                    apply: |a, b| div_or_rem(a, b, "%", D::rem),
+                    prio: 5,
+                    is_commutative: false,
+                    is_right_assoc: false,
+                },
+            ),
+            Operator::make_bin_unary(
+                "+",
+                BinOp {
+                    apply: |a, b| checked_bin(a, b, "+", |a, b| a.checked_add(&b)),
+                    prio: 3,
+                    is_commutative: true,
+                    is_right_assoc: false,
+                },
+                |a| a,
+            ),
+            Operator::make_bin_unary(
+                "-",
+                BinOp {
+                    apply: |a, b| checked_bin(a, b, "-", |a, b| a.checked_sub(&b)),
+                    prio: 3,
+                    is_commutative: false,
+                    is_right_assoc: false,
+                },
+                |a| checked_unary(a, "-", |a| I::zero().checked_sub(&a)),
+            ),
+            Operator::make_bin(
+                "&",
+                BinOp {
+                    apply: |a, b| checked_bin(a, b, "&", |a, b| Some(a & b)),
+                    prio: 2,
+                    is_commutative: true,
+                    is_right_assoc: false,
+                },
+            ),
+            Operator::make_bin(
+                "|",
+                BinOp {
+                    apply: |a, b| checked_bin(a, b, "|", |a, b| Some(a | b)),
+                    prio: 2,
+                    is_commutative: true,
+                    is_right_assoc: false,
+                },
+            ),
+            Operator::make_bin(
+                "XOR",
+                BinOp {
+                    apply: |a, b| checked_bin(a, b, "XOR", |a, b| Some(a ^ b)),
+                    prio: 2,
+                    is_commutative: true,
+                    is_right_assoc: false,
+                },
+            ),
+            Operator::make_bin(
+                "<<",
+                BinOp {
+                    apply: |a, b| checked_bin(a, b, "<<", |a, b| shift(a, b, I::checked_shl)),
+                    prio: 2,
+                    is_commutative: false,
+                    is_right_assoc: false,
+                },
+            ),
+            Operator::make_bin(
+                ">>",
+                BinOp {
+                    apply: |a, b| checked_bin(a, b, ">>", |a, b| shift(a, b, I::checked_shr)),
+                    prio: 2,
+                    is_commutative: false,
+                    is_right_assoc: false,
+                },
+            ),
+            Operator::make_unary("abs", |a| {
+                checked_unary(a, "abs", |a| {
+                    if a < I::zero() {
+                        I::zero().checked_sub(&a)
+                    } else {
+                        Some(a)
+                    }
+                })
+            }),
+            Operator::make_unary("signum", |a| {
+                checked_unary(a, "signum", |a| {
+                    if a > I::zero() {
+                        Some(I::one())
+                    } else if a < I::zero() {
+                        I::zero().checked_sub(&I::one())
+                    } else {
+                        Some(I::zero())
+                    }
+                })
+            }),
+            Operator::make_constant("MIN", CheckedInt::Value(I::min_value())),
+            Operator::make_constant("MAX", CheckedInt::Value(I::max_value())),
+        ]
+    }
+}
+
+/// Alias for [`FlatEx`](FlatEx) with [`CheckedInt`](CheckedInt) as data type and
+/// [`IntOpsFactory`](IntOpsFactory) as operator factory. Integer literals are matched by the
+/// default [`NumberMatcher`](NumberMatcher). Literals with decimal points lead to a parse error.
+pub type FlatExInt<I = i64, D = Truncated> =
+    FlatEx<CheckedInt<I>, IntOpsFactory<I, D>, NumberMatcher>;
+
+#[test]
+fn test_division_modes() {
+    let cases: [(i32, i32); 4] = [(7, 2), (-7, 2), (7, -2), (-7, -2)];
+    let trunc = cases.map(|(a, b)| (Truncated::div(a, b), Truncated::rem(a, b)));
+    let floor = cases.map(|(a, b)| (Floored::div(a, b), Floored::rem(a, b)));
+    let euclid = cases.map(|(a, b)| (Euclidean::div(a, b), Euclidean::rem(a, b)));
+    let some = |q, r| (Some(q), Some(r));
+    assert_eq!(trunc, [some(3, 1), some(-3, -1), some(-3, 1), some(3, -1)]);
+    assert_eq!(floor, [some(3, 1), some(-4, 1), some(-4, -1), some(3, -1)]);
+    assert_eq!(euclid, [some(3, 1), some(-4, 1), some(-3, 1), some(4, 1)]);
+    assert_eq!(Truncated::div(i32::MIN, -1), None);
+    assert_eq!(Floored::rem(i32::MIN, -1), None);
+    assert_eq!(Euclidean::div(i32::MIN, -1), None);
+    assert_eq!(Floored::div(5u8, 2), Some(2));
+}
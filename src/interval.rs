@@ -0,0 +1,510 @@
+use std::{
+    f64::consts::{E, FRAC_PI_2, PI, TAU},
+    fmt::{self, Display, Formatter},
+    ops::{Add, Div, Mul, Neg, Sub},
+    str::FromStr,
+};
+
+use crate::{
+    expression::MatchLiteral, format_exerr, literal_matcher_from_pattern, BinOp, ExError, FlatEx,
+    MakeOperators, Operator,
+};
+
+/// Smallest `f64` that is greater than `x`. Equivalent to `f64::next_up` that requires Rust 1.86.
+fn next_up(x: f64) -> f64 {
+    if x.is_nan() || x == f64::INFINITY {
+        x
+    } else if x == 0.0 {
+        f64::from_bits(1)
+    } else if x > 0.0 {
+        f64::from_bits(x.to_bits() + 1)
+    } else {
+        f64::from_bits(x.to_bits() - 1)
+    }
+}
+
+/// Largest `f64` that is less than `x`. Equivalent to `f64::next_down` that requires Rust 1.86.
+fn next_down(x: f64) -> f64 {
+    -next_up(-x)
+}
+
+/// Closed interval `[lo, hi]` of `f64`s that encloses all values a quantity can take, e.g.,
+/// `x in [0, 1]`. Evaluating an expression over intervals with [`IntervalOpsFactory`](IntervalOpsFactory)
+/// yields an interval that is guaranteed to contain all results of the expression for values in the
+/// input intervals. Intervals whose bounds are NaN are empty and result from operations outside
+/// the domain of a function such as `sqrt([-2, -1])`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Interval {
+    pub lo: f64,
+    pub hi: f64,
+}
+
+impl Interval {
+    /// Creates the interval `[lo, hi]`. If `lo > hi` the interval is empty.
+    pub fn new(lo: f64, hi: f64) -> Self {
+        if lo > hi {
+            Interval::empty()
+        } else {
+            Interval { lo, hi }
+        }
+    }
+    /// Creates the interval `[x, x]` that contains only `x`.
+    pub fn point(x: f64) -> Self {
+        Interval { lo: x, hi: x }
+    }
+    /// Creates the empty interval.
+    pub fn empty() -> Self {
+        Interval {
+            lo: f64::NAN,
+            hi: f64::NAN,
+        }
+    }
+    /// Creates the interval `[-∞, ∞]`.
+    pub fn entire() -> Self {
+        Interval {
+            lo: f64::NEG_INFINITY,
+            hi: f64::INFINITY,
+        }
+    }
+    pub fn is_empty(&self) -> bool {
+        self.lo.is_nan() || self.hi.is_nan()
+    }
+    pub fn contains(&self, x: f64) -> bool {
+        self.lo <= x && x <= self.hi
+    }
+    pub fn width(&self) -> f64 {
+        self.hi - self.lo
+    }
+
+    /// Widens the interval by one unit in the last place in both directions. This encloses the
+    /// rounding errors of IEEE arithmetic and of the standard library's elementary functions.
+    fn outward(lo: f64, hi: f64) -> Self {
+        if lo.is_nan() || hi.is_nan() {
+            Interval::empty()
+        } else {
+            Interval {
+                lo: next_down(lo),
+                hi: next_up(hi),
+            }
+        }
+    }
+    fn hull(values: &[f64]) -> Self {
+        if values.iter().any(|v| v.is_nan()) {
+            return Interval::empty();
+        }
+        let lo = values.iter().copied().fold(f64::INFINITY, f64::min);
+        let hi = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        Interval::outward(lo, hi)
+    }
+    fn map_increasing(self, f: fn(f64) -> f64) -> Self {
+        Interval::outward(f(self.lo), f(self.hi))
+    }
+    fn clamp_to(self, lo: f64, hi: f64) -> Self {
+        if self.is_empty() || self.hi < lo || self.lo > hi {
+            Interval::empty()
+        } else {
+            Interval::new(self.lo.max(lo), self.hi.min(hi))
+        }
+    }
+    /// Evaluates a periodic function with period 2π that attains its maximum 1 at `max_at`
+    /// and its minimum -1 at `max_at + π`.
+    fn periodic(self, f: fn(f64) -> f64, max_at: f64) -> Self {
+        if self.is_empty() {
+            return self;
+        }
+        if self.width() >= TAU || !self.width().is_finite() {
+            return Interval::new(-1.0, 1.0);
+        }
+        let is_attained = |at: f64| {
+            // smallest at + 2kπ that is larger than or equal to lo
+            let k = ((self.lo - at) / TAU).ceil();
+            at + k * TAU <= self.hi
+        };
+        let (f_lo, f_hi) = (f(self.lo), f(self.hi));
+        let lo = if is_attained(max_at + PI) {
+            -1.0
+        } else {
+            f_lo.min(f_hi)
+        };
+        let hi = if is_attained(max_at) {
+            1.0
+        } else {
+            f_lo.max(f_hi)
+        };
+        Interval::outward(lo, hi).clamp_to(-1.0, 1.0)
+    }
+
+    fn mul_impl(self, other: Self) -> Self {
+        let products = [
+            self.lo * other.lo,
+            self.lo * other.hi,
+            self.hi * other.lo,
+            self.hi * other.hi,
+        ];
+        // 0 * ∞ is 0 in interval arithmetic since the bounds are attained by finite numbers
+        let products = products.map(|p| if p.is_nan() { 0.0 } else { p });
+        if self.is_empty() || other.is_empty() {
+            Interval::empty()
+        } else {
+            Interval::hull(&products)
+        }
+    }
+    fn div_impl(self, other: Self) -> Self {
+        if other.lo == 0.0 && other.hi == 0.0 {
+            Interval::empty()
+        } else if other.contains(0.0) {
+            if self.is_empty() {
+                Interval::empty()
+            } else {
+                Interval::entire()
+            }
+        } else {
+            self * Interval::outward(1.0 / other.hi, 1.0 / other.lo)
+        }
+    }
+    /// Computes `self^exponent`. For point exponents that are integers, negative bases are
+    /// supported. Otherwise, the base is restricted to non-negative numbers.
+    pub fn pow(self, exponent: Self) -> Self {
+        if self.is_empty() || exponent.is_empty() {
+            return Interval::empty();
+        }
+        let n = exponent.lo;
+        if exponent.lo == exponent.hi && n.fract() == 0.0 && n.abs() < 2f64.powi(53) {
+            let is_even = n % 2.0 == 0.0;
+            if n == 0.0 {
+                Interval::point(1.0)
+            } else if n < 0.0 {
+                Interval::point(1.0) / self.pow(Interval::point(-n))
+            } else if is_even && self.contains(0.0) {
+                let hi = self.lo.abs().max(self.hi.abs()).powf(n);
+                Interval::outward(0.0, hi).clamp_to(0.0, f64::INFINITY)
+            } else if is_even && self.hi < 0.0 {
+                Interval::outward(self.hi.powf(n), self.lo.powf(n))
+            } else {
+                // odd exponents and positive bases are monotone
+                Interval::outward(self.lo.powf(n), self.hi.powf(n))
+            }
+        } else {
+            let base = self.clamp_to(0.0, f64::INFINITY);
+            if base.is_empty() {
+                return base;
+            }
+            // x^y is monotone in x and in y for x >= 0 and hence the extrema are at the corners
+            let corners = [
+                base.lo.powf(exponent.lo),
+                base.lo.powf(exponent.hi),
+                base.hi.powf(exponent.lo),
+                base.hi.powf(exponent.hi),
+            ];
+            let res = Interval::hull(&corners);
+            if base.contains(0.0) && exponent.contains(0.0) {
+                // 0^0 is 1, hull with limits of 0^y and x^0
+                Interval::hull(&[res.lo, res.hi, 0.0, 1.0])
+            } else {
+                res
+            }
+        }
+    }
+    pub fn abs(self) -> Self {
+        if self.lo >= 0.0 {
+            self
+        } else if self.hi <= 0.0 {
+            -self
+        } else {
+            Interval::new(0.0, (-self.lo).max(self.hi))
+        }
+    }
+    pub fn sqrt(self) -> Self {
+        self.clamp_to(0.0, f64::INFINITY)
+            .map_increasing(f64::sqrt)
+            .clamp_to(0.0, f64::INFINITY)
+    }
+    pub fn exp(self) -> Self {
+        self.map_increasing(f64::exp).clamp_to(0.0, f64::INFINITY)
+    }
+    pub fn ln(self) -> Self {
+        self.clamp_to(0.0, f64::INFINITY).map_increasing(f64::ln)
+    }
+    pub fn sin(self) -> Self {
+        self.periodic(f64::sin, FRAC_PI_2)
+    }
+    pub fn cos(self) -> Self {
+        self.periodic(f64::cos, 0.0)
+    }
+    /// The tangent of intervals that contain a pole results in `[-∞, ∞]`.
+    pub fn tan(self) -> Self {
+        if self.is_empty() {
+            return self;
+        }
+        let k = ((self.lo - FRAC_PI_2) / PI).ceil();
+        if self.width() >= PI || FRAC_PI_2 + k * PI <= self.hi {
+            Interval::entire()
+        } else {
+            self.map_increasing(f64::tan)
+        }
+    }
+    pub fn cosh(self) -> Self {
+        let abs = self.abs();
+        abs.map_increasing(f64::cosh).clamp_to(1.0, f64::INFINITY)
+    }
+}
+
+impl Add for Interval {
+    type Output = Self;
+    fn add(self, other: Self) -> Self {
+        Interval::outward(self.lo + other.lo, self.hi + other.hi)
+    }
+}
+
+impl Sub for Interval {
+    type Output = Self;
+    fn sub(self, other: Self) -> Self {
+        Interval::outward(self.lo - other.hi, self.hi - other.lo)
+    }
+}
+
+impl Neg for Interval {
+    type Output = Self;
+    fn neg(self) -> Self {
+        Interval {
+            lo: -self.hi,
+            hi: -self.lo,
+        }
+    }
+}
+
+impl Mul for Interval {
+    type Output = Self;
+    fn mul(self, other: Self) -> Self {
+        self.mul_impl(other)
+    }
+}
+
+/// Division by intervals that contain zero results in `[-∞, ∞]` or, if the denominator is
+/// `[0, 0]`, in the empty interval.
+impl Div for Interval {
+    type Output = Self;
+    fn div(self, other: Self) -> Self {
+        self.div_impl(other)
+    }
+}
+
+impl Display for Interval {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "[{}, {}]", self.lo, self.hi)
+    }
+}
+
+/// Checks whether the decimal number `text` is exactly represented by its parsed value `x`.
+fn is_exact(text: &str, x: f64) -> bool {
+    let trim = |s: &str| {
+        if s.contains('.') {
+            s.trim_end_matches('0').trim_end_matches('.').to_string()
+        } else {
+            s.to_string()
+        }
+    };
+    // 1100 fractional digits suffice to print every f64 exactly
+    x.is_finite() && trim(&format!("{:.1100}", x)) == trim(text)
+}
+
+/// Parses numbers and intervals such as `[0, 1]`. Numbers that are not exactly representable as
+/// `f64` such as `0.1` are parsed into the smallest interval that contains them.
+impl FromStr for Interval {
+    type Err = ExError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse_bound = |b: &str| {
+            let b = b.trim();
+            b.parse::<f64>()
+                .map(|x| {
+                    if is_exact(b, x) {
+                        Interval::point(x)
+                    } else {
+                        Interval::outward(x, x)
+                    }
+                })
+                .map_err(|e| format_exerr!("cannot parse '{}' as interval bound, {:?}", b, e))
+        };
+        let s = s.trim();
+        if let Some(inner) = s.strip_prefix('[').and_then(|s| s.strip_suffix(']')) {
+            let mut bounds = inner.split(',');
+            match (bounds.next(), bounds.next(), bounds.next()) {
+                (Some(lo), Some(hi), None) => {
+                    let (lo, hi) = (parse_bound(lo)?, parse_bound(hi)?);
+                    if lo.lo > hi.hi {
+                        Err(format_exerr!(
+                            "lower bound larger than upper bound in '{}'",
+                            s
+                        ))
+                    } else {
+                        Ok(Interval::new(lo.lo, hi.hi))
+                    }
+                }
+                _ => Err(format_exerr!("'{}' is not an interval like [0, 1]", s)),
+            }
+        } else {
+            parse_bound(s)
+        }
+    }
+}
+
+/// Factory of operators that evaluate expressions over [`Interval`](Interval)s with outward rounding.
+/// Non-monotone functions such as `sin` or `^` with even exponents as well as divisions by
+/// intervals containing zero are handled such that the result always encloses the true range.
+///
+/// |representation|description|
+/// |--------------|-----------|
+/// |`^`| power |
+/// |`*`| product |
+/// |`/`| division |
+/// |`+`| addition as binary or identity as unary operator|
+/// |`-`| subtraction as binary or inverting the sign as unary operator |
+/// |`abs`| absolute value |
+/// |`sqrt`| square root |
+/// |`cbrt`| cube root |
+/// |`exp`| exponential function |
+/// |`ln`| natural logarithm |
+/// |`log2`| logarithm with basis 2 |
+/// |`log10`| logarithm with basis 10 |
+/// |`sin`| sine |
+/// |`cos`| cosine |
+/// |`tan`| tangent |
+/// |`asin`| inverse sine |
+/// |`acos`| inverse cosine |
+/// |`atan`| inverse tangent |
+/// |`sinh`| hyperbolic sine |
+/// |`cosh`| hyperbolic cosine |
+/// |`tanh`| hyperbolic tangent |
+/// |`PI`| constant π |
+/// |`π`| second representation of constant π |
+/// |`E`| Euler's number |
+/// |`e`| second representation of Euler's number |
+///
+/// ```rust
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// #
+/// use exmex::prelude::*;
+/// use exmex::{FlatExInterval, Interval};
+/// let expr = FlatExInterval::from_str("x^2 + sin(y)")?;
+/// let range = expr.eval(&[Interval::new(-1.0, 2.0), Interval::new(0.0, 3.2)])?;
+/// assert!(range.lo < -0.0583 && range.lo > -0.0584);
+/// assert!(range.hi >= 5.0 && range.hi < 5.0 + 1e-12);
+/// #
+/// #     Ok(())
+/// # }
+/// ```
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Debug)]
+pub struct IntervalOpsFactory;
+
+impl MakeOperators<Interval> for IntervalOpsFactory {
+    /// Returns the default operators.
+    fn make<'a>() -> Vec<Operator<'a, Interval>> {
+        vec![
+            Operator::make_bin(
+                "^",
+                BinOp {
+                    apply: Interval::pow,
+                    prio: 4,
+                    is_commutative: false,
+                    is_right_assoc: true,
+                },
+            ),
+            Operator::make_bin(
+                "*",
+                BinOp {
+                    apply: |a, b| a * b,
+                    prio: 2,
+                    is_commutative: true,
+                    is_right_assoc: false,
+                },
+            ),
+            Operator::make_bin(
+                "/",
+                BinOp {
+                    apply: |a, b| a / b,
+                    prio: 3,
+                    is_commutative: false,
+                    is_right_assoc: false,
+                },
+            ),
+            Operator::make_bin_unary(
+                "+",
+                BinOp {
+                    apply: |a, b| a + b,
+                    prio: 0,
+                    is_commutative: true,
+                    is_right_assoc: false,
+                },
+                |a| a,
+            ),
+            Operator::make_bin_unary(
+                "-",
+                BinOp {
+                    apply: |a, b| a - b,
+                    prio: 1,
+                    is_commutative: false,
+                    is_right_assoc: false,
+                },
+                |a| -a,
+            ),
+            Operator::make_unary("abs", Interval::abs),
+            Operator::make_unary("sqrt", Interval::sqrt),
+            Operator::make_unary("cbrt", |a| a.map_increasing(f64::cbrt)),
+            Operator::make_unary("exp", Interval::exp),
+            Operator::make_unary("ln", Interval::ln),
+            Operator::make_unary("log2", |a| {
+                a.clamp_to(0.0, f64::INFINITY).map_increasing(f64::log2)
+            }),
+            Operator::make_unary("log10", |a| {
+                a.clamp_to(0.0, f64::INFINITY).map_increasing(f64::log10)
+            }),
+            Operator::make_unary("sin", Interval::sin),
+            Operator::make_unary("cos", Interval::cos),
+            Operator::make_unary("tan", Interval::tan),
+            Operator::make_unary("asin", |a| {
+                a.clamp_to(-1.0, 1.0)
+                    .map_increasing(f64::asin)
+                    .clamp_to(next_down(-FRAC_PI_2), next_up(FRAC_PI_2))
+            }),
+            Operator::make_unary("acos", |a| {
+                // acos is decreasing
+                (-a.clamp_to(-1.0, 1.0))
+                    .map_increasing(|x| f64::acos(-x))
+                    .clamp_to(0.0, next_up(PI))
+            }),
+            Operator::make_unary("atan", |a| a.map_increasing(f64::atan)),
+            Operator::make_unary("sinh", |a| a.map_increasing(f64::sinh)),
+            Operator::make_unary("cosh", Interval::cosh),
+            Operator::make_unary("tanh", |a| a.map_increasing(f64::tanh).clamp_to(-1.0, 1.0)),
+            Operator::make_constant("PI", Interval::outward(PI, PI)),
+            Operator::make_constant("π", Interval::outward(PI, PI)),
+            Operator::make_constant("E", Interval::outward(E, E)),
+            Operator::make_constant("e", Interval::outward(E, E)),
+        ]
+    }
+}
+
+// Numbers and intervals of numbers such as [0, 1.5]
+const PATTERN: &str =
+    r"^(\[\s*-?[0-9]+(\.[0-9]+)?\s*,\s*-?[0-9]+(\.[0-9]+)?\s*\]|[0-9]+(\.[0-9]+)?)";
+literal_matcher_from_pattern!(IntervalMatcher, PATTERN);
+
+/// Alias for [`FlatEx`](FlatEx) with [`Interval`](Interval) as data type, [`IntervalOpsFactory`](IntervalOpsFactory)
+/// as operator factory, and [`IntervalMatcher`](IntervalMatcher) that matches numbers and intervals
+/// such as `[0, 1]`.
+pub type FlatExInterval = FlatEx<Interval, IntervalOpsFactory, IntervalMatcher>;
+
+#[test]
+fn test_interval_from_str() -> crate::ExResult<()> {
+    assert_eq!(Interval::from_str("2")?, Interval::point(2.0));
+    assert_eq!(Interval::from_str("[-1, 2.5]")?, Interval::new(-1.0, 2.5));
+    let tenth = Interval::from_str("0.1")?;
+    assert!(tenth.lo < 0.1 && 0.1 < tenth.hi);
+    assert_eq!(Interval::from_str("0.5000")?, Interval::point(0.5));
+    assert!(Interval::from_str("[2, 1]").is_err());
+    assert!(Interval::from_str("[1, 2, 3]").is_err());
+    assert_eq!(IntervalMatcher::is_literal("[0, 1]*x"), Some("[0, 1]"));
+    assert_eq!(IntervalMatcher::is_literal("1.5+x"), Some("1.5"));
+    Ok(())
+}
@@ -1,4 +1,4 @@
-#![doc(html_root_url = "https://docs.rs/exmex/0.16.0")]
+#![doc(html_root_url = "https://docs.rs/exmex/0.17.0")]
 //! Exmex is an extendable mathematical expression parser and evaluator. Ease of use, flexibility, and efficient evaluations are its main design goals.
 //! Exmex can parse mathematical expressions possibly containing variables and operators. On the one hand, it comes with a list of default operators
 //! for floating point values. For differentiable default operators, Exmex can compute partial derivatives. On the other hand, users can define their
@@ -60,20 +60,101 @@
 //! #     Ok(())
 //! # }
 //! ```
+//! The syntax of variable names without curly brackets can be changed by implementing the trait
+//! [`MatchVariable`](MatchVariable), e.g., with the macro
+//! [`variable_matcher_from_pattern`](variable_matcher_from_pattern), and passing the matcher as
+//! fourth type parameter to [`FlatEx`](FlatEx).
+//! ```rust
+//! # use std::error::Error;
+//! # fn main() -> Result<(), Box<dyn Error>> {
+//! #
+//! use exmex::prelude::*;
+//! use exmex::{variable_matcher_from_pattern, FloatOpsFactory, MatchVariable, NumberMatcher};
+//! variable_matcher_from_pattern!(DottedMatcher, r"^\$?[a-zA-Z_]+(\.[a-zA-Z_]+)*");
+//! type FlatExDotted = FlatEx<f64, FloatOpsFactory<f64>, NumberMatcher, DottedMatcher>;
+//! let expr = FlatExDotted::from_str("sensor.temp * $price")?;
+//! assert_eq!(expr.var_names(), ["$price", "sensor.temp"]);
+//! #
+//! #     Ok(())
+//! # }
+//! ```
+//! Subterms can be assigned to local variables in statements separated by `;`. The last
+//! statement is the expression to be evaluated. Local variables are not part of the
+//! expression's variables.
+//! ```rust
+//! # use std::error::Error;
+//! # fn main() -> Result<(), Box<dyn Error>> {
+//! #
+//! use exmex::prelude::*;
+//! let expr = exmex::parse::<f64>("r = sqrt(x^2+y^2); atan(y/x) * r")?;
+//! assert_eq!(expr.var_names(), ["x", "y"]);
+//! assert!((expr.eval(&[3.0, 4.0])? - 5.0 * (4.0f64 / 3.0).atan()).abs() < 1e-12);
+//! #
+//! #     Ok(())
+//! # }
+//! ```
+//! Statements can also define functions that are called in subsequent statements. Reusable
+//! definitions can be stored in a [`FuncEnv`](FuncEnv), see
+//! [`FlatEx::from_str_with_env`](FlatEx::from_str_with_env). Calls are expanded at parse time
+//! and recursive definitions are rejected.
+//! ```rust
+//! # use std::error::Error;
+//! # fn main() -> Result<(), Box<dyn Error>> {
+//! #
+//! use exmex::prelude::*;
+//! let expr = exmex::parse::<f64>("f(a, b) = a^2 + b; f(x, 1) * f(2, y)")?;
+//! assert_eq!(expr.var_names(), ["x", "y"]);
+//! assert!((expr.eval(&[3.0, 4.0])? - 80.0).abs() < 1e-12);
+//! assert!(exmex::parse::<f64>("f(n) = n * f(n - 1); f(3)").is_err());
+//! #
+//! #     Ok(())
+//! # }
+//! ```
+//! Calculator-style juxtapositions such as `2x`, `3(x+1)`, or `(a+b)(a-b)` are rejected by
+//! default. They are parsed as products by
+//! [`FlatEx::from_str_with_implicit_mul`](FlatEx::from_str_with_implicit_mul).
+//! Further options such as the treatment of whitespace, limits of the length and nesting depth
+//! of the text, or the admissible variable names can be set via
+//! [`FlatEx::from_str_with_options`](FlatEx::from_str_with_options) and
+//! [`ParseOptions`](ParseOptions). For untrusted input, the allowed variables and a subset of
+//! the operators can be fixed such that typos like `sinn(x)` are rejected with a suggestion.
 //! The value returned by [`parse`](parse) is an instance of the struct [`FlatEx`](FlatEx)
 //! that implements the [`Express`](Express) trait. Moreover, [`FlatEx`](FlatEx) and
 //! [`Express`](Express) are the only items made accessible by the wildcard import from
 //! [`prelude`](prelude) if the feature `partial` is not used.
 //!
 //! ## Features
-//! Exmex comes with three features that can be activated in the `Cargo.toml` via
+//! Exmex comes with seven features that can be activated in the `Cargo.toml` via
 //! ```text
 //! [dependencies]
-//! exmex = { ..., features = ["partial", "serde", "value"] }
+//! exmex = { ..., features = ["bigfloat", "decimal", "jit", "partial", "serde", "units", "value"] }
 //! ```
 //!
-//! `partial` allows the computation of partal derivatives, `serde` enables serialization and
-//! deserialization, and `value` makes a more general value type accessible.
+//! `jit` compiles expressions to native code, `partial` allows the computation of partal derivatives,
+//! `serde` enables serialization and deserialization, and `value` makes a more general value type accessible.
+//! `decimal` and `bigfloat` provide operators for exact decimals and floats with arbitrary precision.
+//! `units` adds physical quantities with units whose dimensions are checked during evaluation.
+//!
+//! ### Just-in-time Compilation
+//!
+//! Expressions of type `FlatEx<f64>` can be compiled to native machine code with
+//! [Cranelift](https://cranelift.dev/) after activating the feature `jit`. The resulting
+//! [`JitEx`](JitEx) implements [`Express`](Express) and can additionally be called without
+//! returning a `Result`, which is handy for expressions that are evaluated very often.
+//! ```rust
+//! # #[cfg(feature = "jit")]
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! #
+//! use exmex::JitEx;
+//! let expr = JitEx::from_flatex(exmex::parse::<f64>("x^2 + 2*x*y + y^2")?);
+//! let sum = (0..1000).map(|i| expr.call(&[i as f64, 1.0])).sum::<f64>();
+//! assert!((sum - 333833500.0).abs() < 1e-6);
+//! #
+//! #     Ok(())
+//! # }
+//! # #[cfg(not(feature = "jit"))]
+//! # fn main() {}
+//! ```
 //!
 //! ### Partial Derivatives
 //!
@@ -94,6 +175,54 @@
 //! To use different data types within an expression, one can activate the feature `value` and
 //! use the more general type `Val`. The additional flexibility comes with higher parsing
 //! and evaluation run times, see the [benchmarks](https://github.com/bertiqwerty/exmex#benchmarks-v0130).
+//! Conditionals and the boolean operators `&&` and `||` skip the evaluation of operands that do
+//! not influence the result. Custom operators can do the same, see
+//! [`Operator::make_bin_short_circuit`](Operator::make_bin_short_circuit).
+//! Type mismatches such as adding a boolean to a float can be detected before the evaluation
+//! with [`check_val_types`](check_val_types) given the types of the variables.
+//!
+//! ### Decimals and Arbitrary Precision
+//!
+//! With the feature `decimal`, expressions of type [`FlatExDecimal`](FlatExDecimal) evaluate
+//! exact decimal arithmetic such that `0.1 + 0.2` equals `0.3`. Divisions by zero and overflows
+//! result in an error of the [`CheckedDecimal`](CheckedDecimal) instead of a panic. The feature `bigfloat` provides
+//! [`FlatExBigFloat`](FlatExBigFloat) for floats with a precision of a configurable number of bits.
+//! Functions that are not available for these types, e.g., `asin` for decimals, are reported
+//! as errors during parsing.
+//! ```rust
+//! # #[cfg(feature = "decimal")]
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! #
+//! use exmex::prelude::*;
+//! use exmex::{Decimal, FlatExDecimal};
+//! let expr = FlatExDecimal::from_str("0.1 + 0.2")?;
+//! assert_eq!(expr.eval(&[])?.to_result()?, Decimal::from_str("0.3")?);
+//! #
+//! #     Ok(())
+//! # }
+//! # #[cfg(not(feature = "decimal"))]
+//! # fn main() {}
+//! ```
+//!
+//! ### Physical Units
+//!
+//! The feature `units` provides [`FlatExUnits`](FlatExUnits) that evaluates expressions with
+//! quantities such as `3 m/s * 2 s`. Adding incompatible dimensions results in a
+//! [`DimensionError`](DimensionError), and the operator `to` converts a quantity into a given unit.
+//! ```rust
+//! # #[cfg(feature = "units")]
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! #
+//! use exmex::prelude::*;
+//! use exmex::FlatExUnits;
+//! let expr = FlatExUnits::from_str("(3 m/s * 2 s) to km")?;
+//! assert_eq!(expr.eval(&[])?.to_string(), "0.006 km");
+//! #
+//! #     Ok(())
+//! # }
+//! # #[cfg(not(feature = "units"))]
+//! # fn main() {}
+//! ```
 //!
 //! ## Extendability
 //!
@@ -119,6 +248,8 @@
 //! is parsed as variable name, but  `sin 4` is equivalent to `sin(4)`.
 //! * Constant operators are handled as if they were numbers and are replaced by their numeric values during parsing.
 //! They can be used as in `sin(PI)` or `4 + E`. Note that the calling notation of constant operators such as `PI()` is invalid.
+//! * Binary operators can also be called in function notation, e.g., `^(2, 3)` is equivalent to `(2)^(3)`.
+//! * A binary operator with the representation `[]` is used for indexing, e.g., `v[2]` is equivalent to `v [] (2)`.
 //!
 //! Binary, unary, and constant operators can be created with the functions [`make_bin`](Operator::make_bin), [`make_unary`](Operator::make_unary),
 //! and [`make_constant`](Operator::make_constant), respectively.
@@ -139,6 +270,7 @@
 //!             apply: |a, b| a % b,
 //!             prio: 1,
 //!             is_commutative: false,
+//!             is_right_assoc: false,
 //!         }
 //!     ),
 //!     Operator::make_bin(
@@ -147,6 +279,7 @@
 //!             apply: |a, b| a / b,
 //!             prio: 1,
 //!             is_commutative: false,
+//!             is_right_assoc: false,
 //!         }
 //!     ),
 //!     Operator::make_constant("TWO", 2)
@@ -212,6 +345,7 @@
 //!             apply: |a, b| a && b,
 //!             prio: 1,
 //!             is_commutative: true,
+//!             is_right_assoc: false,
 //!         }
 //!     ),
 //!     Operator::make_bin(
@@ -220,6 +354,7 @@
 //!             apply: |a, b| a || b,
 //!             prio: 1,
 //!             is_commutative: true,
+//!             is_right_assoc: false,
 //!         }
 //!     ),
 //!     Operator::make_unary("!", |a| !a)
@@ -233,15 +368,27 @@
 //! #     Ok(())
 //! # }
 //! ```
-//! Two examples of exmex with non-trivial data types are:
+//! Six examples of exmex with non-trivial data types are:
 //! * Numbers can be operators and operators can operate on operators, see, e.g.,
 //! also a blog post on [ninety.de](https://www.ninety.de/log/index.php/en/2021/11/11/parsing-operators-in-rust/).
-//! * The value type implemented as part of the feature `value` allows expressions containing integers, floats, and bools.
+//! * The value type implemented as part of the feature `value` allows expressions containing integers, floats, bools, strings, and arrays.
 //! Therewith, Pythonesque expressions of the form `"x if a > b else y"` are possible.
+//! * Complex numbers with imaginary literals such as `2i` or `j` can be used via [`FlatExComplex`](FlatExComplex),
+//!   see [`ComplexOpsFactory`](ComplexOpsFactory).
+//! * Guaranteed bounds of an expression over input boxes such as `x in [0, 1]` can be computed
+//!   with interval arithmetic via [`FlatExInterval`](FlatExInterval).
+//! * Exact fractions such that `1/3 + 1/6` equals `1/2` are available via [`FlatExRational`](FlatExRational),
+//!   see [`RationalOpsFactory`](RationalOpsFactory).
+//! * Integers whose overflows and divisions by zero result in errors instead of panics are available
+//!   via [`FlatExInt`](FlatExInt), see [`IntOpsFactory`](IntOpsFactory).
 //!
 //! ## Priorities and Parentheses
-//! In Exmex-land, unary operators always have higher priority than binary operators, e.g.,
-//! `-2^2=4` instead of `-2^2=-4`. Moreover, we are not too strict regarding parentheses.
+//! In Exmex-land, unary operators have higher priority than binary operators by default, e.g.,
+//! `-2^2=4` instead of `-2^2=-4`. The mathematical convention `-2^2=-4` can be activated by
+//! wrapping the operator factory into [`MathConvention`](MathConvention). Binary operators of
+//! the same priority are applied from left to right apart from right-associative operators such
+//! as `^`, i.e., `2^3^2=2^9`, see [`BinOp`](BinOp). Moreover, we are not too strict regarding
+//! parentheses.
 //! For instance
 //! ```rust
 //! # use std::error::Error;
@@ -278,18 +425,37 @@ use std::{fmt::Debug, str::FromStr};
 
 use data_type::DataType;
 use num::Float;
+mod complex;
 mod definitions;
 mod expression;
+mod integer;
+mod interval;
+mod locale;
 #[macro_use]
 mod operators;
 mod data_type;
 mod parser;
+mod rational;
 mod result;
 mod util;
 
 pub use {
-    expression::{flat::FlatEx, Express, MatchLiteral, NumberMatcher},
-    operators::{BinOp, FloatOpsFactory, MakeOperators, Operator},
+    complex::{ComplexMatcher, ComplexOpsFactory, FlatExComplex},
+    expression::{
+        expr_set::ExprSet, flat::FlatEx, func_env::FuncEnv, Express, MatchLiteral, MatchVariable,
+        NumberMatcher, VarNameMatcher,
+    },
+    integer::{
+        CheckedInt, DivisionMode, Euclidean, FlatExInt, Floored, IntOpsFactory, IntType, Truncated,
+    },
+    interval::{FlatExInterval, Interval, IntervalMatcher, IntervalOpsFactory},
+    locale::DecimalSeparator,
+    parser::{ParseOptions, Whitespace},
+    operators::{
+        BinOp, FloatOpsFactory, MakeOperators, MathConvention, Operand, Operator, Selection,
+        ShortCircuit,
+    },
+    rational::{FlatExRational, Rational, RationalInt, RationalOpsFactory},
     result::{ExError, ExResult},
 };
 
@@ -299,11 +465,36 @@ pub use {lazy_static, regex};
 #[cfg(feature = "value")]
 mod value;
 #[cfg(feature = "value")]
-pub use value::{parse_val, FlatExVal, Val, ValMatcher, ValOpsFactory};
+pub use value::{
+    parse_val, FlatExVal, NoneIsError, NoneIsNull, NoneMode, Val, ValMatcher, ValOpsFactory,
+};
+#[cfg(feature = "value")]
+mod val_type;
+#[cfg(feature = "value")]
+pub use val_type::{check_val_types, parse_val_typed, TypeError, ValType};
+#[cfg(feature = "decimal")]
+mod decimal;
+#[cfg(feature = "decimal")]
+pub use {
+    decimal::{CheckedDecimal, DecimalOpsFactory, FlatExDecimal},
+    rust_decimal::Decimal,
+};
+#[cfg(feature = "bigfloat")]
+mod big_float;
+#[cfg(feature = "bigfloat")]
+pub use big_float::{BigFloat, BigFloatMatcher, BigFloatOpsFactory, FlatExBigFloat};
+#[cfg(feature = "units")]
+mod units;
+#[cfg(feature = "units")]
+pub use units::{
+    Dimension, DimensionError, FlatExUnits, Quantity, Unit, UnitsMatcher, UnitsOpsFactory,
+};
+#[cfg(feature = "jit")]
+pub use expression::jit::JitEx;
 #[cfg(feature = "partial")]
 mod partial;
 #[cfg(feature = "partial")]
-pub use partial::Differentiate;
+pub use partial::{DiffDataType, Differentiate};
 
 /// Exmex' prelude can be imported via `use exmex::prelude::*;`.
 ///
@@ -0,0 +1,133 @@
+/// Decimal separator of number literals that determines also the separator of arguments and
+/// elements. Texts written with [`Comma`](DecimalSeparator::Comma) such as `f(3,5; x)` are
+/// translated into the default syntax such as `f(3.5, x)` before they are parsed. Hence,
+/// the translation applies to all literal matchers, e.g., `[1,5; 2]` is an array of
+/// [`Val`](crate::Val)s. Statements are still separated by `;` outside of parentheses and
+/// brackets. Strings in quotes and variable names in curly brackets are not translated.
+///
+/// ```rust
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// #
+/// use exmex::prelude::*;
+/// use exmex::DecimalSeparator;
+/// let text = "f(a; b) = a + b; 3,5 * f(x; 0,5)";
+/// let expr = FlatEx::<f64>::from_str_localized(text, DecimalSeparator::Comma)?;
+/// assert!((expr.eval(&[2.0])? - 8.75).abs() < 1e-12);
+/// assert_eq!(expr.unparse(), "f(a, b) = a + b; 3.5 * f(x, 0.5)");
+/// assert_eq!(expr.unparse_localized(DecimalSeparator::Comma), text);
+/// #
+/// #     Ok(())
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum DecimalSeparator {
+    /// Numbers such as `3.5` and arguments separated by `,` such as `f(x, 2)`.
+    #[default]
+    Dot,
+    /// Numbers such as `3,5` and arguments separated by `;` such as `f(x; 2)`.
+    Comma,
+}
+
+/// Calls `f` with each character, its preceding character, and the depth of parentheses and
+/// brackets for all characters that are neither in quotes nor in curly brackets. All other
+/// characters are passed through unchanged.
+pub(crate) fn translate<F>(text: &str, mut f: F) -> String
+where
+    F: FnMut(char, Option<char>, usize) -> char,
+{
+    let mut res = String::with_capacity(text.len());
+    let mut depth = 0usize;
+    let mut closing = None;
+    let mut prev = None;
+    for c in text.chars() {
+        match (closing, c) {
+            (Some(cl), _) if c == cl => {
+                closing = None;
+                res.push(c);
+            }
+            (Some(_), _) => res.push(c),
+            (None, '"') => {
+                closing = Some('"');
+                res.push(c);
+            }
+            (None, '{') => {
+                closing = Some('}');
+                res.push(c);
+            }
+            (None, '(') | (None, '[') => {
+                depth += 1;
+                res.push(c);
+            }
+            (None, ')') | (None, ']') => {
+                depth = depth.saturating_sub(1);
+                res.push(c);
+            }
+            (None, _) => res.push(f(c, prev, depth)),
+        }
+        prev = Some(c);
+    }
+    res
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '.'
+}
+
+impl DecimalSeparator {
+    /// Translates a text with this decimal separator into the default syntax with `.` as
+    /// decimal separator.
+    pub fn delocalize(self, text: &str) -> String {
+        match self {
+            DecimalSeparator::Dot => text.to_string(),
+            DecimalSeparator::Comma => translate(text, |c, _, depth| match c {
+                ',' => '.',
+                ';' if depth > 0 => ',',
+                _ => c,
+            }),
+        }
+    }
+
+    /// Translates a text in the default syntax with `.` as decimal separator into a text with
+    /// this decimal separator. Only dots in number literals are replaced such that dots in
+    /// variable names such as `sensor.temp` are kept.
+    pub fn localize(self, text: &str) -> String {
+        match self {
+            DecimalSeparator::Dot => text.to_string(),
+            DecimalSeparator::Comma => {
+                let mut is_in_number = false;
+                translate(text, |c, prev, _| {
+                    let is_word_start = !matches!(prev, Some(p) if is_word_char(p));
+                    if is_word_start {
+                        is_in_number = c.is_ascii_digit() || c == '.';
+                    } else if !is_word_char(c) {
+                        is_in_number = false;
+                    }
+                    match c {
+                        '.' if is_in_number => ',',
+                        ',' => ';',
+                        _ => c,
+                    }
+                })
+            }
+        }
+    }
+}
+
+#[test]
+fn test_decimal_separator() {
+    let comma = DecimalSeparator::Comma;
+    let pairs = [
+        ("3,5 * x", "3.5 * x"),
+        ("max(1,5; ,5; 2,e-3)", "max(1.5, .5, 2.e-3)"),
+        ("[1,5; 2] + sensor.temp", "[1.5, 2] + sensor.temp"),
+        ("a = f(1;2); \"x,y;\" + {a,b;}", "a = f(1,2); \"x,y;\" + {a,b;}"),
+        ("x1 * (2,5i)", "x1 * (2.5i)"),
+    ];
+    for (localized, delocalized) in pairs {
+        assert_eq!(comma.delocalize(localized), delocalized);
+        assert_eq!(comma.localize(delocalized), localized);
+    }
+    assert_eq!(comma.delocalize("1.5"), "1.5");
+    assert_eq!(DecimalSeparator::Dot.localize("max(1.5, 2)"), "max(1.5, 2)");
+}
@@ -1,34 +1,74 @@
 use crate::{definitions::N_UNARYOPS_OF_DEEPEX_ON_STACK, format_exerr, ExError, ExResult};
 use num::Float;
 use smallvec::{smallvec, SmallVec};
-use std::{fmt::Debug, marker::PhantomData};
+use std::{cmp::Ordering, fmt::Debug, marker::PhantomData};
 
 enum OperatorType {
     Bin,
     Unary,
+    Postfix,
 }
 
 fn make_op_not_available_error(repr: &str, op_type: OperatorType) -> ExError {
     let op_type_str = match op_type {
         OperatorType::Bin => "binary",
         OperatorType::Unary => "unary",
+        OperatorType::Postfix => "postfix",
     };
     format_exerr!("{} operator '{}' not available", op_type_str, repr)
 }
 
 /// Operators can be unary such as `sin`, binary such as `*`, unary and binary such as `-`, 
-/// or constants such as `π`. To use custom operators, see also the macro [`ops_factory`](ops_factory).
+/// postfix such as `!` in `5!`, or constants such as `π`. To use custom operators, see also the macro [`ops_factory`](ops_factory).
 #[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Debug)]
 pub struct Operator<'a, T: Clone> {
     /// Representation of the operator in the string to be parsed, e.g., `-` or `sin`.
     repr: &'a str,
     /// Binary operator that contains a priority besides a function pointer.
     bin_op: Option<BinOp<T>>,
-    /// Unary operator that does not have an explicit priority. Unary operators have
+    /// Unary operator that does not have an explicit priority by default. Unary operators have
     /// higher priority than binary opertors, e.g., `-1^2 == 1`.
     unary_op: Option<fn(T) -> T>,
+    /// Priority of the unary operator if it binds looser than binary operators with a higher
+    /// priority, see [`MathConvention`](MathConvention).
+    unary_prio: Option<i64>,
     /// An operator can also be constant.
     constant: Option<T>,
+    /// Binary operators can skip the evaluation of one operand depending on the other one.
+    short_circuit: Option<ShortCircuit<T>>,
+    /// Postfix operator that is applied to the operand on its left, e.g., the factorial in `5!`.
+    /// Postfix operators bind stronger than unary and binary operators.
+    postfix_op: Option<PostfixOp<T>>,
+    /// Expression that replaces calls of a binary operator with three arguments, see
+    /// [`with_ternary_call`](Operator::with_ternary_call).
+    ternary_call: Option<&'a str>,
+    /// Representations of the comparison and the joining operator that expand tuples on the
+    /// right-hand side, see [`with_tuple_expansion`](Operator::with_tuple_expansion).
+    tuple_expansion: Option<(&'a str, &'a str)>,
+    /// Whether the binary operator is applied by indexing such as `v[2]`.
+    is_index: bool,
+}
+
+/// Function of a postfix operator. Comparisons ignore the function pointer, since addresses of
+/// functions are not guaranteed to be unique.
+#[derive(Clone, Copy, Debug)]
+struct PostfixOp<T>(fn(T) -> T);
+
+impl<T> PartialEq for PostfixOp<T> {
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
+}
+impl<T> Eq for PostfixOp<T> {}
+impl<T> PartialOrd for PostfixOp<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl<T> Ord for PostfixOp<T> {
+    fn cmp(&self, _: &Self) -> Ordering {
+        Ordering::Equal
+    }
 }
 
 fn unwrap_operator<'a, O>(
@@ -67,6 +107,12 @@ impl<'a, T: Clone> Operator<'a, T> {
             bin_op,
             unary_op,
             constant,
+            short_circuit: None,
+            unary_prio: None,
+            postfix_op: None,
+            ternary_call: None,
+            tuple_expansion: None,
+            is_index: false,
         }
     }
 
@@ -88,6 +134,132 @@ impl<'a, T: Clone> Operator<'a, T> {
     ) -> Operator<'a, T> {
         Operator::new(repr, Some(bin_op), Some(unary_op), None)
     }
+    /// Creates a binary operator with short-circuit evaluation. The operand selected by the
+    /// [`ShortCircuit`](ShortCircuit) is evaluated first. The other operand is only evaluated
+    /// if the result of the operation is not already determined by the first one.
+    /// ```rust
+    /// use exmex::{BinOp, Operand, Operator, ShortCircuit};
+    /// let and = Operator::make_bin_short_circuit(
+    ///     "&&",
+    ///     BinOp {
+    ///         apply: |a: f64, b: f64| if a != 0.0 && b != 0.0 { 1.0 } else { 0.0 },
+    ///         prio: 0,
+    ///         is_commutative: true,
+    ///         is_right_assoc: false,
+    ///     },
+    ///     ShortCircuit {
+    ///         first: Operand::Left,
+    ///         apply: |a| if *a == 0.0 { Some(0.0) } else { None },
+    ///         selection: None,
+    ///     },
+    /// );
+    /// assert!(and.short_circuit().is_some());
+    /// ```
+    pub fn make_bin_short_circuit(
+        repr: &'a str,
+        bin_op: BinOp<T>,
+        short_circuit: ShortCircuit<T>,
+    ) -> Operator<'a, T> {
+        let mut op = Operator::new(repr, Some(bin_op), None, None);
+        op.short_circuit = Some(short_circuit);
+        op
+    }
+    /// Creates a binary operator that is written as index such as `v[2]`, where `v` is the first
+    /// and `2` the second operand. An opening bracket directly after an operand starts the
+    /// index. The operator can also be used in infix notation with its representation.
+    pub fn make_bin_index(repr: &'a str, bin_op: BinOp<T>) -> Operator<'a, T> {
+        let mut op = Operator::new(repr, Some(bin_op), None, None);
+        op.is_index = true;
+        op
+    }
+    /// Creates a postfix operator such as the factorial `!` in `5!`.
+    pub fn make_postfix(repr: &'a str, postfix_op: fn(T) -> T) -> Operator<'a, T> {
+        let mut op = Operator::new(repr, None, None, None);
+        op.postfix_op = Some(PostfixOp(postfix_op));
+        op
+    }
+    /// Creates an operator that is either unary or postfix based on its positioning in the string
+    /// to be parsed. For instance, `!` could be the negation in `!a` and the factorial in `5!`.
+    pub fn make_unary_postfix(
+        repr: &'a str,
+        unary_op: fn(T) -> T,
+        postfix_op: fn(T) -> T,
+    ) -> Operator<'a, T> {
+        let mut op = Operator::new(repr, None, Some(unary_op), None);
+        op.postfix_op = Some(PostfixOp(postfix_op));
+        op
+    }
+    /// Creates an operator that is either binary or postfix based on its positioning in the string
+    /// to be parsed. The operator is postfix if it is not followed by an operand. For instance,
+    /// `%` could be the remainder in `5 % 2` and `5 % -2` and the percentage in `50%` and `50% * x`.
+    pub fn make_bin_postfix(
+        repr: &'a str,
+        bin_op: BinOp<T>,
+        postfix_op: fn(T) -> T,
+    ) -> Operator<'a, T> {
+        let mut op = Operator::new(repr, Some(bin_op), None, None);
+        op.postfix_op = Some(PostfixOp(postfix_op));
+        op
+    }
+    /// Allows to call a binary operator with three arguments in function notation. The call is
+    /// replaced by the template in infix notation, where the variables `{0}`, `{1}`, and `{2}`
+    /// stand for the parenthesized arguments. The template is parsed with the operators of the
+    /// same factory. Calls with two arguments such as `max(a, b)` are possible for all binary
+    /// operators and do not need a template.
+    /// ```rust
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use exmex::prelude::*;
+    /// use exmex::{ops_factory, BinOp, MakeOperators, Operator};
+    /// ops_factory!(
+    ///     MaxOpsFactory,
+    ///     f64,
+    ///     Operator::make_bin("max", BinOp::new(f64::max, 0, true))
+    ///         .with_ternary_call("{0} max {1} max {2}")
+    /// );
+    /// let expr = FlatEx::<f64, MaxOpsFactory>::from_str("max(1, x, 3)")?;
+    /// assert_eq!(expr.eval(&[5.0])?, 5.0);
+    /// #
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn with_ternary_call(self, template: &'a str) -> Self {
+        Operator {
+            ternary_call: Some(template),
+            ..self
+        }
+    }
+    /// Allows a binary operator to have a tuple as right-hand side. The left-hand side is
+    /// compared with each element of the tuple by the binary operator `compare` and the results
+    /// are joined by the binary operator `join`, e.g., `x in (1, 2)` becomes `(x == 1) || (x == 2)`
+    /// for `compare` being `==` and `join` being `||`. Both operators are looked up in the same
+    /// factory. If one of them does not exist, tuples are not expanded.
+    /// ```rust
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use exmex::prelude::*;
+    /// use exmex::{ops_factory, BinOp, MakeOperators, Operator};
+    /// ops_factory!(
+    ///     DistOpsFactory,
+    ///     f64,
+    ///     Operator::make_bin("+", BinOp::new(|a, b| a + b, 0, true)),
+    ///     Operator::make_bin("dist", BinOp::new(|a: f64, b| (a - b).abs(), 1, true))
+    ///         .with_tuple_expansion("dist", "+")
+    /// );
+    /// let expr = FlatEx::<f64, DistOpsFactory>::from_str("x dist (1, 5)")?;
+    /// assert_eq!(expr.eval(&[2.0])?, 4.0);
+    /// #
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn with_tuple_expansion(self, compare: &'a str, join: &'a str) -> Self {
+        Operator {
+            tuple_expansion: Some((compare, join)),
+            ..self
+        }
+    }
     /// Creates a constant operator. If an operator is constant it cannot be additionally binary or unary.
     pub fn make_constant(repr: &'a str, constant: T) -> Operator<'a, T> {
         Operator::new(repr, None, None, Some(constant))
@@ -104,6 +276,9 @@ impl<'a, T: Clone> Operator<'a, T> {
             OperatorType::Unary,
         )?)
     }
+    pub fn postfix(&self) -> ExResult<fn(T) -> T> {
+        Ok(unwrap_operator(&self.postfix_op, self.repr, OperatorType::Postfix)?.0)
+    }
     pub fn repr(&self) -> &'a str {
         self.repr
     }
@@ -113,9 +288,27 @@ impl<'a, T: Clone> Operator<'a, T> {
     pub fn has_unary(&self) -> bool {
         self.unary_op.is_some()
     }
+    pub fn has_postfix(&self) -> bool {
+        self.postfix_op.is_some()
+    }
     pub fn constant(&self) -> Option<T> {
         self.constant.clone()
     }
+    pub fn short_circuit(&self) -> Option<ShortCircuit<T>> {
+        self.short_circuit.clone()
+    }
+    pub fn unary_prio(&self) -> Option<i64> {
+        self.unary_prio
+    }
+    pub fn ternary_call(&self) -> Option<&'a str> {
+        self.ternary_call
+    }
+    pub fn tuple_expansion(&self) -> Option<(&'a str, &'a str)> {
+        self.tuple_expansion
+    }
+    pub fn is_index(&self) -> bool {
+        self.is_index
+    }
 }
 
 pub type VecOfUnaryFuncs<T> = SmallVec<[fn(T) -> T; N_UNARYOPS_OF_DEEPEX_ON_STACK]>;
@@ -208,7 +401,17 @@ impl<T: Clone> Default for UnaryOp<T> {
     }
 }
 
-/// A binary operator that consists of a function pointer, a priority, and a commutativity-flag.
+/// A binary operator that consists of a function pointer, a priority, a commutativity-flag, and
+/// an associativity-flag. Besides struct literals, binary operators can be created with
+/// [`BinOp::new`](BinOp::new) and [`right_assoc`](BinOp::right_assoc), which are not affected
+/// by additional fields.
+///
+/// ```rust
+/// use exmex::BinOp;
+/// let pow = BinOp::new(|a: f64, b| a.powf(b), 4, false).right_assoc();
+/// assert!(pow.is_right_assoc);
+/// assert!(!BinOp::new(|a: f64, b| a - b, 1, false).is_right_assoc);
+/// ```
 #[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Debug)]
 pub struct BinOp<T: Clone> {
     /// Implementation of the binary operation, e.g., `|a, b| a * b` for multiplication.
@@ -221,6 +424,84 @@ pub struct BinOp<T: Clone> {
     /// True if this is a commutative operator such as `*` or `+`, false if not such as `-`, `/`, or `^`.
     /// Commutativity is used to compile sub-expressions of numbers correctly.
     pub is_commutative: bool,
+    /// True if this is a right-associative operator such as `^`, where `2^3^2` is `2^(3^2)`, and
+    /// false if subsequent operations of the same priority are applied from left to right, e.g.,
+    /// `8/4/2` is `(8/4)/2`. Operators of the same priority should have the same associativity.
+    pub is_right_assoc: bool,
+}
+
+impl<T: Clone> BinOp<T> {
+    /// Creates a left-associative binary operator.
+    pub fn new(apply: fn(T, T) -> T, prio: i64, is_commutative: bool) -> Self {
+        BinOp {
+            apply,
+            prio,
+            is_commutative,
+            is_right_assoc: false,
+        }
+    }
+    /// Turns the operator into a right-associative one such as `^`.
+    pub fn right_assoc(self) -> Self {
+        BinOp {
+            is_right_assoc: true,
+            ..self
+        }
+    }
+}
+
+/// Operand of a binary operator.
+#[derive(Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Debug)]
+pub enum Operand {
+    Left,
+    Right,
+}
+
+/// Short-circuit evaluation of a binary operator such as `&&`, where the right operand does
+/// not need to be evaluated if the left one is `false`, see
+/// [`Operator::make_bin_short_circuit`](Operator::make_bin_short_circuit). Comparisons ignore
+/// the function, since addresses of functions are not guaranteed to be unique.
+#[derive(Clone, Debug)]
+pub struct ShortCircuit<T: Clone> {
+    /// Operand that is evaluated first.
+    pub first: Operand,
+    /// Returns the result of the binary operation if it is determined by the value of the
+    /// first operand alone. In case of `None`, the other operand is evaluated and the binary
+    /// operation is applied as usual.
+    pub apply: fn(&T) -> Option<T>,
+    /// Role of the operator in the selection of operands, e.g., by `if` and `else`.
+    pub selection: Option<Selection>,
+}
+
+/// Role of a binary operator with short-circuit evaluation in conditionals such as
+/// `x if c else y`, where `if` selects `x` by the condition `c` and `else` falls back to `y`
+/// if `x` has not been selected. Whether an operand has been selected is tracked by the
+/// evaluation separately from the values. Hence, the value of an operand that has not been
+/// selected, e.g., `Val::None`, can also be the value of a selected operand.
+#[derive(Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Debug)]
+pub enum Selection {
+    /// The other operand is not selected if the short circuit applies, e.g., if the condition
+    /// of `if` is false.
+    Condition,
+    /// The short circuit only applies if the first operand has been selected. Otherwise, the
+    /// second operand is evaluated and the binary operation is applied as usual.
+    Alternative,
+}
+
+impl<T: Clone> PartialEq for ShortCircuit<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.first == other.first && self.selection == other.selection
+    }
+}
+impl<T: Clone> Eq for ShortCircuit<T> {}
+impl<T: Clone> PartialOrd for ShortCircuit<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl<T: Clone> Ord for ShortCircuit<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.first, self.selection).cmp(&(other.first, other.selection))
+    }
 }
 
 /// To use custom operators one needs to create a factory that implements this trait.
@@ -242,6 +523,7 @@ pub struct BinOp<T: Clone> {
 ///                     apply: |a, b| a - b,
 ///                     prio: 0,
 ///                     is_commutative: false,
+///                     is_right_assoc: false,
 ///                 },
 ///                 |a| (-a),
 ///             ),
@@ -309,6 +591,7 @@ impl<T: Float> MakeOperators<T> for FloatOpsFactory<T> {
                     apply: |a, b| a.powf(b),
                     prio: 4,
                     is_commutative: false,
+                    is_right_assoc: true,
                 },
             ),
             Operator::make_bin(
@@ -317,6 +600,7 @@ impl<T: Float> MakeOperators<T> for FloatOpsFactory<T> {
                     apply: |a, b| a * b,
                     prio: 2,
                     is_commutative: true,
+                    is_right_assoc: false,
                 },
             ),
             Operator::make_bin(
@@ -325,6 +609,7 @@ impl<T: Float> MakeOperators<T> for FloatOpsFactory<T> {
                     apply: |a, b| a / b,
                     prio: 3,
                     is_commutative: false,
+                    is_right_assoc: false,
                 },
             ),
             Operator::make_bin_unary(
@@ -333,6 +618,7 @@ impl<T: Float> MakeOperators<T> for FloatOpsFactory<T> {
                     apply: |a, b| a + b,
                     prio: 0,
                     is_commutative: true,
+                    is_right_assoc: false,
                 },
                 |a| a,
             ),
@@ -342,6 +628,7 @@ impl<T: Float> MakeOperators<T> for FloatOpsFactory<T> {
                     apply: |a, b| a - b,
                     prio: 1,
                     is_commutative: false,
+                    is_right_assoc: false,
                 },
                 |a| -a,
             ),
@@ -379,6 +666,45 @@ impl<T: Float> MakeOperators<T> for FloatOpsFactory<T> {
     }
 }
 
+/// Factory that follows the mathematical convention that operators such as `-` bind as unary
+/// operators like they do as binary operators. The operators are taken from the factory `OF`.
+/// Operators that are both unary and binary get the priority of their binary version as unary
+/// priority. Hence, `-2^2 == -4` instead of `(-2)^2 == 4` and `2^-x^2` is `2^(-(x^2))`.
+/// ```rust
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// #
+/// use exmex::prelude::*;
+/// use exmex::{FloatOpsFactory, MathConvention, NumberMatcher};
+/// type FlatExMath = FlatEx<f64, MathConvention<FloatOpsFactory<f64>>, NumberMatcher>;
+/// let expr = FlatExMath::from_str("-2^2 + 2^3^2")?;
+/// assert_eq!(expr.eval(&[])?, 508.0);
+/// let expr = FlatEx::<f64>::from_str("-2^2")?;
+/// assert_eq!(expr.eval(&[])?, 4.0);
+/// #
+/// #     Ok(())
+/// # }
+/// ```
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Debug)]
+pub struct MathConvention<OF> {
+    dummy: PhantomData<OF>,
+}
+
+impl<T: Clone, OF: MakeOperators<T>> MakeOperators<T> for MathConvention<OF> {
+    /// Returns the operators of `OF` with adapted unary priorities.
+    fn make<'a>() -> Vec<Operator<'a, T>> {
+        OF::make()
+            .into_iter()
+            .map(|mut op| {
+                if op.unary_op.is_some() {
+                    op.unary_prio = op.bin_op.as_ref().map(|bin_op| bin_op.prio);
+                }
+                op
+            })
+            .collect()
+    }
+}
+
 /// This macro creates an operator factory struct that implements the trait
 /// [`MakeOperators`](MakeOperators). You have to pass the name of the struct
 /// as first, the type of the operands as second, and the [`Operator`](Operator)s as
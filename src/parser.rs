@@ -1,24 +1,241 @@
 use crate::data_type::DataType;
-use crate::definitions::{N_NODES_ON_STACK, N_VARS_ON_STACK};
+use crate::definitions::{N_EXPANDED_TOKENS_MAX, N_NODES_ON_STACK, N_VARS_ON_STACK};
 use crate::format_exerr;
+use crate::locale::{self, DecimalSeparator};
 use crate::{operators::Operator, ExError, ExResult};
 use lazy_static::lazy_static;
 use regex::Regex;
 use smallvec::SmallVec;
-use std::fmt::Debug;
+use std::{borrow::Cow, fmt::Debug, iter, ops::Range};
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub enum Paren {
     Open,
     Close,
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub enum ParsedToken<'a, T: DataType> {
     Num(T),
     Paren(Paren),
     Op(Operator<'a, T>),
     Var(&'a str),
+    /// Separates arguments of calls of user-defined functions
+    Comma,
+}
+
+pub type ParsedTokenVec<'a, T> = SmallVec<[ParsedToken<'a, T>; N_NODES_ON_STACK]>;
+
+/// Determines how whitespace between tokens is treated, see [`ParseOptions`](ParseOptions).
+/// Whitespace in quotes and in curly brackets is always kept.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Whitespace {
+    /// Spaces between tokens are skipped, other whitespace such as tabs results in an error.
+    #[default]
+    Spaces,
+    /// All whitespace between tokens such as tabs and line breaks is skipped.
+    Any,
+    /// Any whitespace between tokens results in an error.
+    Rejected,
+}
+
+/// Options that determine how texts are parsed by
+/// [`FlatEx::from_str_with_options`](crate::FlatEx::from_str_with_options). The default
+/// options parse like [`FlatEx::from_str`](crate::FlatEx::from_str). Options are typically
+/// created with struct update syntax.
+///
+/// ```rust
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// #
+/// use exmex::prelude::*;
+/// use exmex::{ParseOptions, Whitespace};
+/// let options = ParseOptions {
+///     whitespace: Whitespace::Any,
+///     case_insensitive_ops: true,
+///     max_len: Some(100),
+///     allowed_vars: Some(&["x", "y"]),
+///     allowed_ops: Some(&["sin", "cos", "+", "-", "*"]),
+///     ..ParseOptions::default()
+/// };
+/// let expr = FlatEx::<f64>::from_str_with_options("SIN(x)\t+ Cos(y)", &options)?;
+/// assert!((expr.eval(&[0.0, 0.0])? - 1.0).abs() < 1e-12);
+/// assert!(FlatEx::<f64>::from_str_with_options("sin(z)", &options).is_err());
+/// assert!(FlatEx::<f64>::from_str_with_options("tan(x)", &options).is_err());
+/// let err = FlatEx::<f64>::from_str_with_options("sinn(x)", &options).unwrap_err();
+/// assert_eq!(err.msg(), "unknown function 'sinn', did you mean 'sin'?");
+/// #
+/// #     Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseOptions<'a> {
+    /// Whether the expression is compiled after parsing for faster evaluation, see also
+    /// [`FlatEx::from_str_wo_compile`](crate::FlatEx::from_str_wo_compile). Defaults to `true`.
+    pub compile: bool,
+    /// Treatment of whitespace between tokens. Defaults to [`Whitespace::Spaces`].
+    pub whitespace: Whitespace,
+    /// Whether operators and constants are also found if their ASCII letters differ in case
+    /// such as `SIN(x)` or `PI`. Exact matches take precedence. Defaults to `false`.
+    pub case_insensitive_ops: bool,
+    /// Maximal length of the text in bytes. Defaults to `None`, i.e., unlimited.
+    pub max_len: Option<usize>,
+    /// Maximal nesting depth of parentheses and brackets in each statement. Defaults to `None`,
+    /// i.e., unlimited.
+    pub max_depth: Option<usize>,
+    /// Maximal number of tokens after local variables and calls of user-defined functions
+    /// have been expanded. Since each use of a local variable copies its definition, texts
+    /// such as `a = x + x; b = a + a; c = b + b; ...` grow exponentially. Defaults to 65536.
+    pub max_expanded_tokens: usize,
+    /// Whether the text may consist of multiple statements, i.e., assignments of local
+    /// variables and definitions of functions such as `f(a) = a^2; f(x) + 1`, see
+    /// [`parse`](crate::parse). Defaults to `true`.
+    pub allow_statements: bool,
+    /// Names of the variables the expression may contain. Identifiers that are neither
+    /// operators nor contained result in an error that suggests a similar name if there is
+    /// one. Local variables and parameters of user-defined functions are not variables of the
+    /// expression and need not be contained. Defaults to `None`, see
+    /// [`unknown_as_vars`](ParseOptions::unknown_as_vars).
+    pub allowed_vars: Option<&'a [&'a str]>,
+    /// Representations of the operators of the operator factory the expression may contain
+    /// such as `sin`, `+`, or `PI`. All other operators are unknown to the parser. Defaults
+    /// to `None`, i.e., all operators of the factory are allowed.
+    pub allowed_ops: Option<&'a [&'a str]>,
+    /// Whether identifiers that are not operators become variables if no
+    /// [`allowed_vars`](ParseOptions::allowed_vars) are given. Otherwise, any variable results
+    /// in an error. Defaults to `true`.
+    pub unknown_as_vars: bool,
+    /// Whether multiplications between juxtaposed operands such as `2x` are inserted, see
+    /// [`FlatEx::from_str_with_implicit_mul`](crate::FlatEx::from_str_with_implicit_mul).
+    /// Defaults to `false`.
+    pub implicit_mul: bool,
+    /// Decimal separator of number literals, see
+    /// [`DecimalSeparator`](crate::DecimalSeparator). Defaults to
+    /// [`DecimalSeparator::Dot`](crate::DecimalSeparator::Dot).
+    pub decimal_separator: DecimalSeparator,
+}
+
+impl Default for ParseOptions<'_> {
+    fn default() -> Self {
+        ParseOptions {
+            compile: true,
+            whitespace: Whitespace::default(),
+            case_insensitive_ops: false,
+            max_len: None,
+            max_depth: None,
+            max_expanded_tokens: N_EXPANDED_TOKENS_MAX,
+            allow_statements: true,
+            allowed_vars: None,
+            allowed_ops: None,
+            unknown_as_vars: true,
+            implicit_mul: false,
+            decimal_separator: DecimalSeparator::default(),
+        }
+    }
+}
+
+impl ParseOptions<'_> {
+    /// Checks the length of the text, translates the decimal separator, and treats whitespace
+    /// such that the result can be tokenized.
+    pub(crate) fn preprocess<'b>(&self, text: &'b str) -> ExResult<Cow<'b, str>> {
+        if let Some(max_len) = self.max_len {
+            if text.len() > max_len {
+                return Err(format_exerr!(
+                    "text has {} bytes but at most {} are allowed",
+                    text.len(),
+                    max_len
+                ));
+            }
+        }
+        let text = match self.decimal_separator {
+            DecimalSeparator::Dot => Cow::Borrowed(text),
+            sep => Cow::Owned(sep.delocalize(text)),
+        };
+        match self.whitespace {
+            Whitespace::Spaces => Ok(text),
+            Whitespace::Any => Ok(Cow::Owned(locale::translate(&text, |c, _, _| {
+                if c.is_whitespace() {
+                    ' '
+                } else {
+                    c
+                }
+            }))),
+            Whitespace::Rejected => {
+                let mut has_whitespace = false;
+                locale::translate(&text, |c, _, _| {
+                    has_whitespace |= c.is_whitespace();
+                    c
+                });
+                if has_whitespace {
+                    Err(format_exerr!("whitespace is not allowed in '{}'", text))
+                } else {
+                    Ok(text)
+                }
+            }
+        }
+    }
+
+    /// Returns an error for the first variable that is not allowed. Similar names of allowed
+    /// variables and of operators are suggested.
+    pub(crate) fn check_vars<T: DataType>(
+        &self,
+        tokens: &[ParsedToken<T>],
+        ops: &[Operator<T>],
+    ) -> ExResult<()> {
+        let is_allowed = |var: &str| match self.allowed_vars {
+            Some(allowed_vars) => allowed_vars.contains(&var),
+            None => self.unknown_as_vars,
+        };
+        let unknown_var = tokens.iter().find_map(|token| match token {
+            ParsedToken::Var(var) if !is_allowed(var) => Some(*var),
+            _ => None,
+        });
+        match unknown_var {
+            Some(var) => {
+                let candidates = self
+                    .allowed_vars
+                    .unwrap_or(&[])
+                    .iter()
+                    .copied()
+                    .chain(ops.iter().map(|op| op.repr()));
+                Err(format_exerr!(
+                    "unknown identifier '{}'{}",
+                    var,
+                    did_you_mean(var, candidates)
+                ))
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Returns the operators that are allowed or an error if an allowed operator is not
+    /// provided by the operator factory.
+    pub(crate) fn filter_ops<'b, 'c, T: DataType>(
+        &self,
+        ops: &'b [Operator<'c, T>],
+    ) -> ExResult<Cow<'b, [Operator<'c, T>]>> {
+        match self.allowed_ops {
+            None => Ok(Cow::Borrowed(ops)),
+            Some(allowed_ops) => {
+                if let Some(repr) = allowed_ops
+                    .iter()
+                    .find(|repr| !ops.iter().any(|op| op.repr() == **repr))
+                {
+                    return Err(format_exerr!(
+                        "operator '{}' is not provided by the operator factory{}",
+                        repr,
+                        did_you_mean(repr, ops.iter().map(|op| op.repr()))
+                    ));
+                }
+                Ok(Cow::Owned(
+                    ops.iter()
+                        .filter(|op| allowed_ops.contains(&op.repr()))
+                        .cloned()
+                        .collect(),
+                ))
+            }
+        }
+    }
 }
 
 /// Returns the index of the variable in the slice. Panics if not available!
@@ -48,13 +265,31 @@ pub fn is_operator_binary<'a, T: DataType>(
         Ok(match parsed_token_on_the_left {
             ParsedToken::Num(_) | ParsedToken::Var(_) => true,
             ParsedToken::Paren(p) => *p == Paren::Close,
-            ParsedToken::Op(_) => false,
+            ParsedToken::Op(_) | ParsedToken::Comma => false,
         })
     } else {
         Ok(false)
     }
 }
 
+/// Disambiguates postfix operators based on whether they are on the right of an operand and on
+/// the successor token. Operators that are also binary are only postfix if the successor cannot
+/// be the beginning of an operand, e.g., `%` is postfix in `50%` and `50% * x` but binary in
+/// `50 % x` and `50 % -x`.
+pub fn is_operator_postfix<'a, T: DataType>(
+    op: &Operator<'a, T>,
+    is_right_of_operand: bool,
+    parsed_token_on_the_right: Option<&ParsedToken<'a, T>>,
+) -> bool {
+    let is_left_of_operand = match parsed_token_on_the_right {
+        Some(ParsedToken::Num(_)) | Some(ParsedToken::Var(_)) => true,
+        Some(ParsedToken::Paren(p)) => *p == Paren::Open,
+        Some(ParsedToken::Op(op_r)) => op_r.has_unary(),
+        Some(ParsedToken::Comma) | None => false,
+    };
+    op.has_postfix() && is_right_of_operand && !(op.has_bin() && is_left_of_operand)
+}
+
 /// Returns variable names in sorted order.
 pub fn find_parsed_vars<'a, T: DataType>(
     parsed_tokens: &[ParsedToken<'a, T>],
@@ -85,16 +320,37 @@ pub fn is_numeric_text(text: &str) -> Option<&str> {
         })
         .count();
     if (n_num_chars > 1 && n_dots < 2) || (n_num_chars == 1 && n_dots == 0) {
-        Some(&text[0..n_num_chars])
+        Some(&text[0..n_num_chars + exponent_len(&text[n_num_chars..])])
     } else {
         None
     }
 }
 
-fn next_char_boundary(text: &str, start_idx: usize) -> usize {
-    (1..text.len())
-        .find(|idx| text.is_char_boundary(start_idx + idx))
-        .expect("there has to be a char boundary somewhere")
+/// Returns the number of bytes of an exponent such as `e-3` or `E23` at the beginning of the
+/// text or 0 if there is none. An `e` without digits is not an exponent, e.g., in `2e`.
+fn exponent_len(text: &str) -> usize {
+    let bytes = text.as_bytes();
+    if !matches!(bytes.first(), Some(b'e') | Some(b'E')) {
+        return 0;
+    }
+    let n_sign = usize::from(matches!(bytes.get(1), Some(b'+') | Some(b'-')));
+    let n_digits = bytes[1 + n_sign..]
+        .iter()
+        .take_while(|b| b.is_ascii_digit())
+        .count();
+    if n_digits > 0 {
+        1 + n_sign + n_digits
+    } else {
+        0
+    }
+}
+
+pub fn is_var_name_text(text: &str) -> Option<&str> {
+    lazy_static! {
+        static ref RE_VAR_NAME: Regex =
+            Regex::new(r"^[a-zA-Zα-ωΑ-Ω_]+[a-zA-Zα-ωΑ-Ω_0-9]*").unwrap();
+    }
+    RE_VAR_NAME.find(text).map(|m| m.as_str())
 }
 
 /// Parses tokens of a text with regexes and returns them as a vector
@@ -104,51 +360,126 @@ fn next_char_boundary(text: &str, start_idx: usize) -> usize {
 /// * `text` - text to be parsed
 /// * `ops_in` - slice of operator-pairs
 /// * `is_numeric` - closure that decides whether the current rest of the text starts with a number
+/// * `is_variable` - closure that decides whether the current rest of the text starts with a
+///   variable name
+/// * `options` - options of which the case insensitivity of operators, the maximal depth,
+///   and implicit multiplications are considered, see [`ParseOptions`](ParseOptions)
 ///
 /// # Errors
 ///
 /// See [`parse_with_number_pattern`](parse_with_number_pattern)
 ///
-pub fn tokenize_and_analyze<'a, T, F>(
+pub fn tokenize_and_analyze<'a, T, F, V>(
     text: &'a str,
     ops_in: &[Operator<'a, T>],
     is_numeric: F,
+    is_variable: V,
+    options: &ParseOptions,
 ) -> ExResult<SmallVec<[ParsedToken<'a, T>; N_NODES_ON_STACK]>>
 where
     <T as std::str::FromStr>::Err: Debug,
     T: DataType,
     F: Fn(&'a str) -> Option<&'a str>,
+    V: Fn(&'a str) -> Option<&'a str>,
+{
+    let (tokens, spans) = tokenize_with_spans(text, ops_in, &is_numeric, &is_variable, options)?;
+    if let Some(max_depth) = options.max_depth {
+        check_depth(&tokens, max_depth)?;
+    }
+    let tokens = if options.implicit_mul {
+        insert_implicit_muls(&tokens, &spans, ops_in)?
+    } else {
+        tokens
+    };
+    let tokens = expand_postfix_ops(&tokens)?;
+    let tokenize_template = |template: &'a str| {
+        let options = ParseOptions::default();
+        tokenize_with_spans(template, ops_in, &is_numeric, &is_variable, &options)
+            .map(|(tokens, _)| tokens)
+    };
+    let tokens = expand_bin_op_calls(&tokens, &tokenize_template)?;
+    let tokens = expand_in_tuples(&tokens, ops_in)?;
+    if ops_in.iter().any(|op| op.unary_prio().is_some()) {
+        Ok(wrap_unary_operands(&tokens))
+    } else {
+        Ok(tokens)
+    }
+}
+
+/// Returns an error if parentheses and brackets are nested deeper than `max_depth`.
+fn check_depth<T: DataType>(tokens: &[ParsedToken<T>], max_depth: usize) -> ExResult<()> {
+    let mut depth = 0usize;
+    for token in tokens {
+        match token {
+            ParsedToken::Paren(Paren::Open) => {
+                depth += 1;
+                if depth > max_depth {
+                    return Err(format_exerr!(
+                        "parentheses are nested deeper than {}",
+                        max_depth
+                    ));
+                }
+            }
+            ParsedToken::Paren(Paren::Close) => depth = depth.saturating_sub(1),
+            _ => (),
+        }
+    }
+    Ok(())
+}
+
+pub type SpanVec = SmallVec<[Range<usize>; N_NODES_ON_STACK]>;
+
+/// Parses tokens of a text like [`tokenize_and_analyze`](tokenize_and_analyze) but does not
+/// expand calls of binary operators in function notation. Additionally, the byte range of
+/// each token in the text is returned.
+pub fn tokenize_with_spans<'a, T, F, V>(
+    text: &'a str,
+    ops_in: &[Operator<'a, T>],
+    is_numeric: F,
+    is_variable: V,
+    options: &ParseOptions,
+) -> ExResult<(ParsedTokenVec<'a, T>, SpanVec)>
+where
+    <T as std::str::FromStr>::Err: Debug,
+    T: DataType,
+    F: Fn(&'a str) -> Option<&'a str>,
+    V: Fn(&'a str) -> Option<&'a str>,
 {
     // We sort operators inverse alphabetically such that log2 has higher priority than log (wlog :D).
     let mut ops_tmp = ops_in.iter().clone().collect::<SmallVec<[_; 64]>>();
     ops_tmp.sort_unstable_by(|o1, o2| o2.repr().partial_cmp(o1.repr()).unwrap());
     let ops = ops_tmp.to_vec(); // from now on const
 
-    lazy_static! {
-        static ref RE_VAR_NAME: Regex =
-            Regex::new(r"^[a-zA-Zα-ωΑ-Ω_]+[a-zA-Zα-ωΑ-Ω_0-9]*").unwrap();
-    }
-    lazy_static! {
-        static ref RE_VAR_NAME_EXACT: Regex =
-            Regex::new(r"^[a-zA-Zα-ωΑ-Ω_]+[a-zA-Zα-ωΑ-Ω_0-9]*$").unwrap();
-    }
-
-    let find_ops = |byte_offset: usize| {
-        ops.iter().find(|op| {
-            let range_end = byte_offset + op.repr().len();
-            if let Some(maybe_op) = text.get(byte_offset..range_end) {
-                op.repr() == maybe_op
-                    && (op.has_bin()
-                        || range_end >= text.len()
-                        || !RE_VAR_NAME_EXACT.is_match(
-                            &text[byte_offset..range_end + next_char_boundary(text, range_end)],
-                        ))
+    let find_ops = |byte_offset: usize, is_right_of_operand: bool| {
+        let find = |is_match: fn(&str, &str) -> bool| {
+            ops.iter().find(|op| {
+                let range_end = byte_offset + op.repr().len();
+                if let Some(maybe_op) = text.get(byte_offset..range_end) {
+                    // where an operand is expected, binary operators such as `in` are not
+                    // matched at the beginning of names such as `index`
+                    is_match(op.repr(), maybe_op)
+                        && ((op.has_bin() || op.has_postfix()) && is_right_of_operand
+                            || is_variable(&text[byte_offset..])
+                                .filter(|var_str| var_str.len() > op.repr().len())
+                                .is_none())
+                } else {
+                    false
+                }
+            })
+        };
+        find(|repr, maybe_op| repr == maybe_op).or_else(|| {
+            if options.case_insensitive_ops {
+                find(|repr, maybe_op| repr.eq_ignore_ascii_case(maybe_op))
             } else {
-                false
+                None
             }
         })
     };
+    // a binary index operator is used for indexing such as v[2]
+    let index_op = ops.iter().find(|op| op.is_index() && op.has_bin());
+    let mut n_open_brackets = 0usize;
     let mut res: SmallVec<[_; N_NODES_ON_STACK]> = SmallVec::new();
+    let mut spans = SpanVec::new();
     let mut cur_byte_offset = 0usize;
     for (i, c) in text.char_indices() {
         if c == ' ' && i == cur_byte_offset {
@@ -156,7 +487,25 @@ where
         } else if i == cur_byte_offset && cur_byte_offset < text.len() {
             let text_rest = &text[cur_byte_offset..];
             let cur_byte_offset_tmp = cur_byte_offset;
-            let next_parsed_token = if c == '(' {
+            let is_right_of_operand = matches!(
+                res.last(),
+                Some(ParsedToken::Num(_))
+                    | Some(ParsedToken::Var(_))
+                    | Some(ParsedToken::Paren(Paren::Close))
+            );
+            let next_parsed_token = if let (true, '[', Some(index_op)) =
+                (is_right_of_operand, c, index_op)
+            {
+                cur_byte_offset += 1;
+                n_open_brackets += 1;
+                res.push(ParsedToken::<T>::Op((*index_op).clone()));
+                spans.push(cur_byte_offset_tmp..cur_byte_offset);
+                ParsedToken::<T>::Paren(Paren::Open)
+            } else if c == ']' && n_open_brackets > 0 {
+                cur_byte_offset += 1;
+                n_open_brackets -= 1;
+                ParsedToken::<T>::Paren(Paren::Close)
+            } else if c == '(' {
                 cur_byte_offset += 1;
                 ParsedToken::<T>::Paren(Paren::Open)
             } else if c == ')' {
@@ -177,15 +526,17 @@ where
                 ParsedToken::<T>::Num(num_str.parse::<T>().map_err(|e| format_exerr!(
                     "could not parse '{}', {:?}", num_str, e)
                 )?)
-            } else if let Some(op) = find_ops(cur_byte_offset_tmp) {
+            } else if let Some(op) = find_ops(cur_byte_offset_tmp, is_right_of_operand) {
                 let n_bytes = op.repr().len();
                 cur_byte_offset += n_bytes;
                 match op.constant() {
                     Some(constant) => ParsedToken::<T>::Num(constant),
                     None => ParsedToken::<T>::Op((*op).clone()),
                 }
-            } else if let Some(var_str) = RE_VAR_NAME.find(text_rest) {
-                let var_str = var_str.as_str();
+            } else if c == ',' {
+                cur_byte_offset += 1;
+                ParsedToken::<T>::Comma
+            } else if let Some(var_str) = is_variable(text_rest) {
                 let n_bytes = var_str.len();
                 cur_byte_offset += n_bytes;
                 ParsedToken::<T>::Var(var_str)
@@ -193,11 +544,840 @@ where
                 return Err(format_exerr!("don't know how to parse {}", text_rest));
             };
             res.push(next_parsed_token);
+            spans.push(cur_byte_offset_tmp..cur_byte_offset);
+        }
+    }
+    Ok((res, spans))
+}
+
+/// Returns `((lhs) op (rhs))`.
+fn infix<'a, T: DataType>(
+    lhs: &[ParsedToken<'a, T>],
+    op: &Operator<'a, T>,
+    rhs: &[ParsedToken<'a, T>],
+) -> ParsedTokenVec<'a, T> {
+    iter::once(ParsedToken::Paren(Paren::Open))
+        .chain(iter::once(ParsedToken::Paren(Paren::Open)))
+        .chain(lhs.iter().cloned())
+        .chain(iter::once(ParsedToken::Paren(Paren::Close)))
+        .chain(iter::once(ParsedToken::Op(op.clone())))
+        .chain(iter::once(ParsedToken::Paren(Paren::Open)))
+        .chain(rhs.iter().cloned())
+        .chain(iter::once(ParsedToken::Paren(Paren::Close)))
+        .chain(iter::once(ParsedToken::Paren(Paren::Close)))
+        .collect()
+}
+
+fn find_bin_op<'a, 'b, T: DataType>(
+    ops: &'b [Operator<'a, T>],
+    repr: &str,
+) -> Option<&'b Operator<'a, T>> {
+    ops.iter().find(|op| op.repr() == repr && op.has_bin())
+}
+
+/// Inserts multiplications between juxtaposed operands, e.g., `2x`, `3(x+1)`, `(a+b)(a-b)`,
+/// `2sin(x)`, and `3!x` become `2*x`, `3*(x+1)`, `(a+b)*(a-b)`, `2*sin(x)`, and `3!*x`. A
+/// variable that is directly followed by an opening parenthesis without whitespace is a call of
+/// a user-defined function such as `f(x)`, whereas `x (y+1)` is a product.
+pub fn insert_implicit_muls<'a, T: DataType>(
+    tokens: &[ParsedToken<'a, T>],
+    spans: &[Range<usize>],
+    ops: &[Operator<'a, T>],
+) -> ExResult<ParsedTokenVec<'a, T>> {
+    let mut res = ParsedTokenVec::new();
+    let mut is_right_of_operand = false;
+    for (idx, token) in tokens.iter().enumerate() {
+        let (is_left_of_operand, is_postfix) = match token {
+            ParsedToken::Num(_) | ParsedToken::Var(_) => (true, false),
+            ParsedToken::Paren(Paren::Open) => {
+                let is_call = idx > 0
+                    && matches!(tokens[idx - 1], ParsedToken::Var(_))
+                    && spans[idx - 1].end == spans[idx].start;
+                (!is_call, false)
+            }
+            ParsedToken::Op(op) => {
+                let is_postfix = is_operator_postfix(op, is_right_of_operand, tokens.get(idx + 1));
+                let is_bin = is_right_of_operand && op.has_bin();
+                (op.has_unary() && !is_postfix && !is_bin, is_postfix)
+            }
+            ParsedToken::Paren(Paren::Close) | ParsedToken::Comma => (false, false),
+        };
+        if is_right_of_operand && is_left_of_operand {
+            let mul_op = find_bin_op(ops, "*").ok_or_else(|| {
+                ExError::new("implicit multiplication needs a binary operator '*'")
+            })?;
+            res.push(ParsedToken::Op(mul_op.clone()));
+        }
+        is_right_of_operand = is_postfix
+            || matches!(
+                token,
+                ParsedToken::Num(_) | ParsedToken::Var(_) | ParsedToken::Paren(Paren::Close)
+            );
+        res.push(token.clone());
+    }
+    Ok(res)
+}
+
+/// Returns the index of the first token of the operand of a postfix operator that directly
+/// follows the tokens. The operand is a number, a variable, a parenthesized expression, a call
+/// of a unary operator such as `sin(x)`, or an indexed value such as `v[2]`.
+fn find_postfix_operand<T: DataType>(tokens: &[ParsedToken<T>]) -> usize {
+    let last = tokens.len() - 1;
+    if !matches!(tokens[last], ParsedToken::Paren(Paren::Close)) {
+        return last;
+    }
+    let mut depth = 0;
+    let mut open = last;
+    loop {
+        match &tokens[open] {
+            ParsedToken::Paren(Paren::Close) => depth += 1,
+            ParsedToken::Paren(Paren::Open) => depth -= 1,
+            _ => (),
+        }
+        if depth == 0 || open == 0 {
+            break;
+        }
+        open -= 1;
+    }
+    match (open.checked_sub(1).map(|i| &tokens[i]), open.checked_sub(2)) {
+        (Some(ParsedToken::Op(op)), _) if op.is_index() && op.has_bin() => {
+            let prio = op.bin().map(|bin_op| bin_op.prio).unwrap_or(i64::MAX);
+            find_left_operand(&tokens[..open - 1], prio - 1)
+        }
+        (Some(ParsedToken::Op(_)), None) => open - 1,
+        (Some(ParsedToken::Op(op)), Some(i)) if !is_operator_binary(op, &tokens[i]).unwrap_or(true) => {
+            open - 1
+        }
+        _ => open,
+    }
+}
+
+/// Rewrites postfix operators such as the factorial in `x^5!` into unary operators applied to
+/// their parenthesized operand such as `x^!(5)`, see also [`is_operator_postfix`].
+fn expand_postfix_ops<'a, T: DataType>(
+    tokens: &[ParsedToken<'a, T>],
+) -> ExResult<ParsedTokenVec<'a, T>> {
+    let mut res = ParsedTokenVec::new();
+    for (idx, token) in tokens.iter().enumerate() {
+        let is_right_of_operand = matches!(
+            res.last(),
+            Some(ParsedToken::Num(_))
+                | Some(ParsedToken::Var(_))
+                | Some(ParsedToken::Paren(Paren::Close))
+        );
+        match token {
+            ParsedToken::Op(op)
+                if is_operator_postfix(op, is_right_of_operand, tokens.get(idx + 1)) =>
+            {
+                let operand = res
+                    .drain(find_postfix_operand(&res)..)
+                    .collect::<ParsedTokenVec<'a, T>>();
+                res.push(ParsedToken::Op(Operator::make_unary(op.repr(), op.postfix()?)));
+                res.push(ParsedToken::Paren(Paren::Open));
+                res.extend(operand);
+                res.push(ParsedToken::Paren(Paren::Close));
+            }
+            _ => res.push(token.clone()),
+        }
+    }
+    Ok(res)
+}
+
+/// Replaces the variables `{0}`, `{1}`, ... in the tokens of a template by the parenthesized
+/// arguments and parenthesizes the result.
+fn substitute_args<'a, T: DataType>(
+    template: &[ParsedToken<'a, T>],
+    args: &[ParsedTokenVec<'a, T>],
+) -> ParsedTokenVec<'a, T> {
+    let mut res = ParsedTokenVec::new();
+    res.push(ParsedToken::Paren(Paren::Open));
+    for token in template {
+        match token {
+            ParsedToken::Var(name) => match name.parse::<usize>().ok().and_then(|i| args.get(i)) {
+                Some(arg) => {
+                    res.push(ParsedToken::Paren(Paren::Open));
+                    res.extend(arg.iter().cloned());
+                    res.push(ParsedToken::Paren(Paren::Close));
+                }
+                None => res.push(token.clone()),
+            },
+            _ => res.push(token.clone()),
+        }
+    }
+    res.push(ParsedToken::Paren(Paren::Close));
+    res
+}
+
+/// Rewrites calls of binary operators in function notation such as `contains(a, b)` into
+/// their infix notation `((a) contains (b))`. Calls with three arguments such as `if(c, a, b)`
+/// are replaced by the template of the operator, see
+/// [`Operator::with_ternary_call`](Operator::with_ternary_call), which is tokenized by `tokenize`.
+fn expand_bin_op_calls<'a, T, F>(
+    tokens: &[ParsedToken<'a, T>],
+    tokenize: &F,
+) -> ExResult<ParsedTokenVec<'a, T>>
+where
+    T: DataType,
+    F: Fn(&'a str) -> ExResult<ParsedTokenVec<'a, T>>,
+{
+    let mut res = ParsedTokenVec::new();
+    let mut idx = 0;
+    while idx < tokens.len() {
+        let is_prefix = idx == 0
+            || matches!(
+                tokens[idx - 1],
+                ParsedToken::Op(_) | ParsedToken::Paren(Paren::Open) | ParsedToken::Comma
+            );
+        let call = match (&tokens[idx], tokens.get(idx + 1)) {
+            (ParsedToken::Op(op), Some(ParsedToken::Paren(Paren::Open)))
+                if op.has_bin() && is_prefix =>
+            {
+                // invalid parentheses are reported by the precondition checks
+                match split_args(&tokens[idx + 2..]) {
+                    Ok((args, n_tokens)) if args.len() == 2 => Some((op, args, n_tokens)),
+                    Ok((args, n_tokens)) if args.len() == 3 && op.ternary_call().is_some() => {
+                        Some((op, args, n_tokens))
+                    }
+                    Ok((args, _)) if args.len() > 2 || !op.has_unary() => {
+                        return Err(format_exerr!(
+                            "binary operator '{}' expects 2 arguments but got {}",
+                            op.repr(),
+                            args.len()
+                        ));
+                    }
+                    _ => None,
+                }
+            }
+            _ => None,
+        };
+        match call {
+            Some((op, args, n_tokens)) => {
+                let args = args
+                    .iter()
+                    .map(|arg| expand_bin_op_calls(arg, tokenize))
+                    .collect::<ExResult<SmallVec<[_; 3]>>>()?;
+                let expanded = match (op.ternary_call(), args.len()) {
+                    (Some(template), 3) => substitute_args(&tokenize(template)?, &args),
+                    _ => infix(&args[0], op, &args[1]),
+                };
+                res.extend(expanded);
+                idx += n_tokens + 2;
+            }
+            None => {
+                res.push(tokens[idx].clone());
+                idx += 1;
+            }
+        }
+    }
+    Ok(res)
+}
+
+/// Returns the index of the first token of the left operand of the binary operator with
+/// priority `prio` that directly follows the tokens.
+fn find_left_operand<T: DataType>(tokens: &[ParsedToken<T>], prio: i64) -> usize {
+    let mut depth = 0;
+    let mut start = tokens.len();
+    while start > 0 {
+        let is_binary_op = |op: &Operator<T>| {
+            start > 1 && is_operator_binary(op, &tokens[start - 2]).unwrap_or(false)
+        };
+        match &tokens[start - 1] {
+            ParsedToken::Paren(Paren::Close) => depth += 1,
+            ParsedToken::Paren(Paren::Open) if depth == 0 => break,
+            ParsedToken::Paren(Paren::Open) => depth -= 1,
+            ParsedToken::Comma if depth == 0 => break,
+            ParsedToken::Op(op)
+                if depth == 0
+                    && is_binary_op(op)
+                    && op.bin().map(|bin_op| bin_op.prio <= prio).unwrap_or(true) =>
+            {
+                break
+            }
+            _ => (),
+        }
+        start -= 1;
+    }
+    start
+}
+
+/// Rewrites binary operators with tuples on the right-hand side for operators that opted into
+/// [`with_tuple_expansion`](Operator::with_tuple_expansion). For instance, `x in (1, 2, 5)` becomes
+/// `((x) == (1)) || ((x) == (2)) || ((x) == (5))` if `in` expands tuples with `==` and `||`.
+fn expand_in_tuples<'a, T: DataType>(
+    tokens: &[ParsedToken<'a, T>],
+    ops: &[Operator<'a, T>],
+) -> ExResult<ParsedTokenVec<'a, T>> {
+    let expansion_ops = |op: &Operator<'a, T>| {
+        let (compare, join) = op.tuple_expansion()?;
+        Some((find_bin_op(ops, compare)?.clone(), find_bin_op(ops, join)?.clone()))
+    };
+    if !ops.iter().any(|op| expansion_ops(op).is_some()) {
+        return Ok(tokens.iter().cloned().collect());
+    }
+    let mut res = ParsedTokenVec::new();
+    let mut idx = 0;
+    while idx < tokens.len() {
+        let tuple = match (&tokens[idx], tokens.get(idx + 1)) {
+            (ParsedToken::Op(op), Some(ParsedToken::Paren(Paren::Open)))
+                if idx > 0 && is_operator_binary(op, &tokens[idx - 1])? =>
+            {
+                match (expansion_ops(op), split_args(&tokens[idx + 2..])) {
+                    (Some(expansion_ops), Ok((elts, n_tokens))) if elts.len() > 1 => {
+                        Some((op.bin()?.prio, expansion_ops, elts, n_tokens))
+                    }
+                    _ => None,
+                }
+            }
+            _ => None,
+        };
+        match tuple {
+            Some((prio, (compare_op, join_op), elts, n_tokens)) => {
+                let lhs = res
+                    .drain(find_left_operand(&res, prio)..)
+                    .collect::<ParsedTokenVec<'a, T>>();
+                let mut membership = infix(&lhs, &compare_op, &expand_in_tuples(elts[0], ops)?);
+                for elt in &elts[1..] {
+                    let is_elt = infix(&lhs, &compare_op, &expand_in_tuples(elt, ops)?);
+                    membership = infix(&membership, &join_op, &is_elt);
+                }
+                res.extend(membership);
+                idx += n_tokens + 2;
+            }
+            None => {
+                res.push(tokens[idx].clone());
+                idx += 1;
+            }
+        }
+    }
+    Ok(res)
+}
+
+/// Returns the index after the last token of the operand of a unary operator with priority
+/// `prio` that starts at `start`. The operand extends over binary operators with a higher priority.
+fn find_right_operand_end<T: DataType>(
+    tokens: &[ParsedToken<T>],
+    start: usize,
+    prio: i64,
+) -> usize {
+    let mut depth = 0;
+    let mut end = start;
+    while end < tokens.len() {
+        let is_binary_op = |op: &Operator<T>| {
+            end > start && is_operator_binary(op, &tokens[end - 1]).unwrap_or(false)
+        };
+        match &tokens[end] {
+            ParsedToken::Paren(Paren::Open) => depth += 1,
+            ParsedToken::Paren(Paren::Close) if depth == 0 => break,
+            ParsedToken::Paren(Paren::Close) => depth -= 1,
+            ParsedToken::Comma if depth == 0 => break,
+            ParsedToken::Op(op)
+                if depth == 0
+                    && is_binary_op(op)
+                    && op.bin().map(|bin_op| bin_op.prio <= prio).unwrap_or(true) =>
+            {
+                break
+            }
+            _ => (),
+        }
+        end += 1;
+    }
+    end
+}
+
+/// Wraps operands of unary operators with a priority into parentheses, e.g., `-2^2` is rewritten
+/// into `-(2^2)` if `-` has a lower unary priority than `^`, see
+/// [`MathConvention`](crate::MathConvention).
+fn wrap_unary_operands<'a, T: DataType>(tokens: &[ParsedToken<'a, T>]) -> ParsedTokenVec<'a, T> {
+    let mut res = ParsedTokenVec::new();
+    let mut idx = 0;
+    while idx < tokens.len() {
+        let unary_prio = match &tokens[idx] {
+            ParsedToken::Op(op)
+                if idx == 0 || !is_operator_binary(op, &tokens[idx - 1]).unwrap_or(true) =>
+            {
+                op.unary_prio()
+            }
+            _ => None,
+        };
+        let end = unary_prio.map(|prio| find_right_operand_end(tokens, idx + 1, prio));
+        match end {
+            Some(end) if end > idx + 1 => {
+                res.push(tokens[idx].clone());
+                res.push(ParsedToken::Paren(Paren::Open));
+                res.extend(wrap_unary_operands(&tokens[idx + 1..end]));
+                res.push(ParsedToken::Paren(Paren::Close));
+                idx = end;
+            }
+            _ => {
+                res.push(tokens[idx].clone());
+                idx += 1;
+            }
+        }
+    }
+    res
+}
+
+/// Splits a program into its statements that are separated by `;`. Semicolons within
+/// parentheses, curly brackets, or double quotes do not separate statements.
+pub fn split_statements(text: &str) -> SmallVec<[&str; 8]> {
+    let mut statements = SmallVec::new();
+    let mut paren_depth = 0i32;
+    let mut is_in_curly = false;
+    let mut is_in_quotes = false;
+    let mut start = 0;
+    for (i, c) in text.char_indices() {
+        match c {
+            '"' if !is_in_curly => is_in_quotes = !is_in_quotes,
+            _ if is_in_quotes => (),
+            '{' => is_in_curly = true,
+            '}' => is_in_curly = false,
+            '(' if !is_in_curly => paren_depth += 1,
+            ')' if !is_in_curly => paren_depth -= 1,
+            ';' if !is_in_curly && paren_depth == 0 => {
+                statements.push(&text[start..i]);
+                start = i + 1;
+            }
+            _ => (),
+        }
+    }
+    statements.push(&text[start..]);
+    statements
+}
+
+/// Returns the rest of the text after `=` if it starts with `=` up to whitespace and `=` is
+/// not the beginning of the comparison `==`.
+fn strip_assignment_op(text: &str) -> Option<&str> {
+    let rest = text.trim_start().strip_prefix('=')?;
+    if rest.starts_with('=') {
+        None
+    } else {
+        Some(rest)
+    }
+}
+
+/// Returns the name of the local variable and the right hand side in case the
+/// statement is an assignment such as `r = sqrt(x^2+y^2)`. Names are matched by
+/// `is_variable` or written between curly brackets.
+pub fn split_assignment<'a, V>(statement: &'a str, is_variable: V) -> Option<(&'a str, &'a str)>
+where
+    V: Fn(&'a str) -> Option<&'a str>,
+{
+    let text = statement.trim_start();
+    let (name, rest) = match text.strip_prefix('{') {
+        Some(curly) => {
+            let end = curly.find('}')?;
+            (&curly[..end], &curly[end + 1..])
+        }
+        None => {
+            let name = is_variable(text).filter(|name| !name.is_empty())?;
+            (name, &text[name.len()..])
+        }
+    };
+    Some((name, strip_assignment_op(rest)?))
+}
+
+/// Returns the name, the parameters, and the body in case the statement defines a
+/// function such as `f(x, y) = x^2 + y`. Names are matched by `is_variable`.
+pub fn split_definition<'a, V>(
+    statement: &'a str,
+    is_variable: V,
+) -> Option<(&'a str, SmallVec<[&'a str; N_VARS_ON_STACK]>, &'a str)>
+where
+    V: Fn(&'a str) -> Option<&'a str>,
+{
+    let text = statement.trim_start();
+    let name = is_variable(text).filter(|name| !name.is_empty())?;
+    let rest = text[name.len()..].trim_start().strip_prefix('(')?;
+    let end = rest.find(['(', ')', '='])?;
+    if !rest[end..].starts_with(')') {
+        return None;
+    }
+    let params = &rest[..end];
+    let body = strip_assignment_op(&rest[end + 1..])?;
+    let params = if params.trim().is_empty() {
+        SmallVec::new()
+    } else {
+        params.split(',').map(|p| p.trim()).collect()
+    };
+    Some((name, params, body))
+}
+
+/// Checks names and parameters of a function definition. Parameters need to be matched
+/// completely by `is_variable`.
+pub fn check_definition<'a, V>(name: &str, params: &[&'a str], is_variable: V) -> ExResult<()>
+where
+    V: Fn(&'a str) -> Option<&'a str>,
+{
+    for (i, param) in params.iter().enumerate() {
+        if param.is_empty() || is_variable(param) != Some(*param) {
+            return Err(format_exerr!(
+                "invalid parameter '{}' in definition of function '{}'",
+                param,
+                name
+            ));
+        }
+        if params[..i].contains(param) {
+            return Err(format_exerr!(
+                "parameter '{}' occurs more than once in definition of function '{}'",
+                param,
+                name
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// User-defined function whose body has been tokenized
+struct Func<'a, T: DataType> {
+    name: &'a str,
+    params: SmallVec<[&'a str; N_VARS_ON_STACK]>,
+    body: ParsedTokenVec<'a, T>,
+}
+
+fn is_call<'a, T: DataType>(tokens: &[ParsedToken<'a, T>], idx: usize) -> Option<&'a str> {
+    match (&tokens[idx], tokens.get(idx + 1)) {
+        (ParsedToken::Var(name), Some(ParsedToken::Paren(Paren::Open))) => Some(name),
+        _ => None,
+    }
+}
+
+/// Splits the arguments of a function call at commas that are not nested in parentheses.
+/// The tokens are expected to start right after the opening parenthesis of the call. Returns
+/// the arguments and the number of tokens including the closing parenthesis of the call.
+#[allow(clippy::type_complexity)]
+fn split_args<'a, 'b, T: DataType>(
+    tokens: &'b [ParsedToken<'a, T>],
+) -> ExResult<(SmallVec<[&'b [ParsedToken<'a, T>]; N_VARS_ON_STACK]>, usize)> {
+    let mut args: SmallVec<[&'b [ParsedToken<'a, T>]; N_VARS_ON_STACK]> = SmallVec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    for (idx, token) in tokens.iter().enumerate() {
+        match token {
+            ParsedToken::Paren(Paren::Open) => depth += 1,
+            ParsedToken::Paren(Paren::Close) if depth > 0 => depth -= 1,
+            ParsedToken::Paren(Paren::Close) => {
+                if idx > start || !args.is_empty() {
+                    args.push(&tokens[start..idx]);
+                }
+                if args.iter().any(|arg| arg.is_empty()) {
+                    return Err(ExError::new("arguments of function calls cannot be empty"));
+                }
+                return Ok((args, idx + 1));
+            }
+            ParsedToken::Comma if depth == 0 => {
+                args.push(&tokens[start..idx]);
+                start = idx + 1;
+            }
+            _ => (),
+        }
+    }
+    Err(ExError::new("missing closing parenthesis of function call"))
+}
+
+/// Returns an error if local variables and calls of functions expand to more than
+/// `max_tokens` tokens. Since each expansion copies tokens, chains such as
+/// `a = x + x; b = a + a; c = b + b; ...` grow exponentially.
+fn check_expanded_len(n_tokens: usize, max_tokens: usize) -> ExResult<()> {
+    if n_tokens > max_tokens {
+        Err(format_exerr!(
+            "local variables and function calls expand to more than {} tokens",
+            max_tokens
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+/// Replaces each call of a user-defined function by the parenthesized body of the function,
+/// where each parameter is replaced by the parenthesized argument.
+fn expand_calls<'a, T: DataType>(
+    tokens: &[ParsedToken<'a, T>],
+    funcs: &[Func<'a, T>],
+    max_tokens: usize,
+) -> ExResult<ParsedTokenVec<'a, T>> {
+    let mut res = ParsedTokenVec::new();
+    let mut idx = 0;
+    while idx < tokens.len() {
+        let func = is_call(tokens, idx).and_then(|name| funcs.iter().rev().find(|f| f.name == name));
+        match func {
+            Some(func) => {
+                let (args, n_tokens) = split_args(&tokens[idx + 2..])?;
+                if args.len() != func.params.len() {
+                    return Err(format_exerr!(
+                        "function '{}' expects {} argument(s) but got {}",
+                        func.name,
+                        func.params.len(),
+                        args.len()
+                    ));
+                }
+                let args = args
+                    .iter()
+                    .map(|arg| expand_calls(arg, funcs, max_tokens))
+                    .collect::<ExResult<SmallVec<[_; N_VARS_ON_STACK]>>>()?;
+                res.push(ParsedToken::Paren(Paren::Open));
+                for token in &func.body {
+                    let arg = match token {
+                        ParsedToken::Var(name) => func.params.iter().position(|p| p == name),
+                        _ => None,
+                    };
+                    match arg {
+                        Some(arg_idx) => {
+                            check_expanded_len(res.len() + args[arg_idx].len() + 2, max_tokens)?;
+                            res.push(ParsedToken::Paren(Paren::Open));
+                            res.extend(args[arg_idx].iter().cloned());
+                            res.push(ParsedToken::Paren(Paren::Close));
+                        }
+                        None => res.push(token.clone()),
+                    }
+                }
+                res.push(ParsedToken::Paren(Paren::Close));
+                check_expanded_len(res.len(), max_tokens)?;
+                idx += n_tokens + 2;
+            }
+            None => {
+                res.push(tokens[idx].clone());
+                idx += 1;
+            }
         }
     }
     Ok(res)
 }
 
+/// Parses tokens of a text that might consist of multiple statements separated by `;`
+/// such as `r = sqrt(x^2+y^2); atan(y/x) * r`. All statements but the last one are
+/// assignments of local variables or definitions of functions such as `f(x) = x^2 + 1`
+/// that can be used in subsequent statements. The tokens of the last statement are returned,
+/// where each local variable is replaced by the parenthesized tokens of its definition and
+/// each function call by the parenthesized body of the function. Hence, neither local
+/// variables nor functions occur in the resulting expression.
+///
+/// # Arguments
+///
+/// * `text` - text to be parsed
+/// * `ops_in` - slice of operator-pairs
+/// * `is_numeric` - closure that decides whether the current rest of the text starts with a number
+/// * `is_variable` - closure that decides whether the current rest of the text starts with a
+///   variable name
+/// * `options` - options that are passed to [`tokenize_and_analyze`](tokenize_and_analyze)
+///   for each statement and that restrict the operators and variables
+///
+/// # Errors
+///
+/// An error is returned if a statement is invalid, if a statement that is not the last
+/// one is not an assignment or a definition, if the last statement is an assignment or
+/// a definition, if a function calls itself, or if an operator or a variable is not allowed
+/// by the options.
+///
+pub fn tokenize_statements<'a, T, F, V>(
+    text: &'a str,
+    ops_in: &[Operator<'a, T>],
+    is_numeric: F,
+    is_variable: V,
+    options: &ParseOptions,
+) -> ExResult<ParsedTokenVec<'a, T>>
+where
+    <T as std::str::FromStr>::Err: Debug,
+    T: DataType,
+    F: Fn(&'a str) -> Option<&'a str> + Copy,
+    V: Fn(&'a str) -> Option<&'a str> + Copy,
+{
+    let allowed_ops = options.filter_ops(ops_in)?;
+    let ops_in = &allowed_ops[..];
+    let statements = split_statements(text);
+    if statements.len() > 1 && !options.allow_statements {
+        return Err(format_exerr!(
+            "only a single expression is allowed, found {} statements",
+            statements.len()
+        ));
+    }
+    if statements.len() == 1 {
+        let tokens =
+            tokenize_and_analyze(text, ops_in, is_numeric, is_variable, options)?;
+        check_unknown_calls(&tokens, ops_in, &[])?;
+        options.check_vars(&tokens, ops_in)?;
+        return Ok(tokens);
+    }
+    let n_statements = statements.len();
+    let max_tokens = options.max_expanded_tokens;
+    let mut locals: SmallVec<[(&str, ParsedTokenVec<'a, T>); 8]> = SmallVec::new();
+    let mut funcs: SmallVec<[Func<'a, T>; 8]> = SmallVec::new();
+    // parameters shadow local variables and function calls are not replaced
+    let substitute_locals = |tokens: ParsedTokenVec<'a, T>,
+                             locals: &[(&str, ParsedTokenVec<'a, T>)],
+                             params: &[&str]| {
+        let mut res = ParsedTokenVec::new();
+        for idx in 0..tokens.len() {
+            let local = match &tokens[idx] {
+                ParsedToken::Var(name)
+                    if !params.contains(name) && is_call(&tokens, idx).is_none() =>
+                {
+                    locals.iter().rev().find(|(n, _)| n == name)
+                }
+                _ => None,
+            };
+            match local {
+                Some((_, local_tokens)) => {
+                    check_expanded_len(res.len() + local_tokens.len() + 2, max_tokens)?;
+                    res.push(ParsedToken::Paren(Paren::Open));
+                    res.extend(local_tokens.iter().cloned());
+                    res.push(ParsedToken::Paren(Paren::Close));
+                }
+                None => res.push(tokens[idx].clone()),
+            }
+        }
+        Ok::<_, ExError>(res)
+    };
+    let check_name = |name: &str| {
+        if ops_in.iter().any(|op| op.repr() == name) {
+            Err(format_exerr!(
+                "cannot assign to '{}' since it is an operator",
+                name
+            ))
+        } else {
+            Ok(())
+        }
+    };
+    for (i, statement) in statements.iter().enumerate() {
+        let is_last = i == n_statements - 1;
+        let definition = split_definition(statement, is_variable);
+        let assignment = if definition.is_none() {
+            split_assignment(statement, is_variable)
+        } else {
+            None
+        };
+        match (definition, assignment, is_last) {
+            (Some((name, params, body)), _, false) => {
+                check_name(name)?;
+                check_definition(name, &params, is_variable)?;
+                if let Some(param) = params.iter().find(|p| ops_in.iter().any(|op| op.repr() == **p)) {
+                    return Err(format_exerr!(
+                        "parameter '{}' of function '{}' is an operator",
+                        param,
+                        name
+                    ));
+                }
+                let tokens =
+                    tokenize_and_analyze(body, ops_in, is_numeric, is_variable, options)?;
+                let tokens = substitute_locals(tokens, &locals, &params)?;
+                let tokens = expand_calls(&tokens, &funcs, max_tokens)?;
+                if (0..tokens.len()).any(|idx| is_call(&tokens, idx) == Some(name)) {
+                    return Err(format_exerr!(
+                        "function '{}' calls itself, recursive definitions are not supported",
+                        name
+                    ));
+                }
+                check_unknown_calls(&tokens, ops_in, &funcs)
+                    .and_then(|_| check_parsed_token_preconditions(&tokens))
+                    .map_err(|e| format_exerr!("invalid definition of '{}', {}", name, e.msg()))?;
+                funcs.push(Func {
+                    name,
+                    params,
+                    body: tokens,
+                });
+            }
+            (None, Some((name, rhs)), false) => {
+                check_name(name)?;
+                let tokens =
+                    tokenize_and_analyze(rhs, ops_in, is_numeric, is_variable, options)?;
+                let tokens = substitute_locals(tokens, &locals, &[])?;
+                let tokens = expand_calls(&tokens, &funcs, max_tokens)?;
+                check_unknown_calls(&tokens, ops_in, &funcs)
+                    .and_then(|_| check_parsed_token_preconditions(&tokens))
+                    .map_err(|e| format_exerr!("invalid definition of '{}', {}", name, e.msg()))?;
+                locals.push((name, tokens));
+            }
+            (None, None, false) => {
+                return Err(format_exerr!(
+                    "all statements but the last one need to be assignments or definitions, violated by '{}'",
+                    statement.trim()
+                ))
+            }
+            (Some(_), _, true) | (_, Some(_), true) => {
+                return Err(format_exerr!(
+                    "the last statement must be an expression and not an assignment, violated by '{}'",
+                    statement.trim()
+                ))
+            }
+            (None, None, true) => {
+                let tokens =
+                    tokenize_and_analyze(statement, ops_in, is_numeric, is_variable, options)?;
+                let tokens = substitute_locals(tokens, &locals, &[])?;
+                let tokens = expand_calls(&tokens, &funcs, max_tokens)?;
+                check_unknown_calls(&tokens, ops_in, &funcs)?;
+                check_parsed_token_preconditions(&tokens)?;
+                options.check_vars(&tokens, ops_in)?;
+                return Ok(tokens);
+            }
+        }
+    }
+    unreachable!("the last statement always returns")
+}
+
+/// Calls of names that are neither operators of the operator factory nor user-defined functions
+/// are reported, e.g., `asin(x)` if `asin` is not available for the data type at hand. Similar
+/// names of unary operators and user-defined functions are suggested.
+fn check_unknown_calls<'a, T: DataType>(
+    tokens: &[ParsedToken<'a, T>],
+    ops: &[Operator<'a, T>],
+    funcs: &[Func<'a, T>],
+) -> ExResult<()> {
+    match (0..tokens.len()).find_map(|idx| is_call(tokens, idx)) {
+        Some(name) => {
+            let candidates = ops
+                .iter()
+                .filter(|op| op.has_unary())
+                .map(|op| op.repr())
+                .chain(funcs.iter().map(|func| func.name));
+            Err(format_exerr!(
+                "unknown function '{}'{}",
+                name,
+                did_you_mean(name, candidates)
+            ))
+        }
+        None => Ok(()),
+    }
+}
+
+/// Number of insertions, deletions, substitutions, and transpositions of adjacent characters
+/// that are needed to transform `a` into `b`, also known as optimal string alignment distance.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a = a.chars().collect::<SmallVec<[char; 32]>>();
+    let b = b.chars().collect::<SmallVec<[char; 32]>>();
+    let mut d = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for (i, row) in d.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, dist) in d[0].iter_mut().enumerate() {
+        *dist = j;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            d[i][j] = (d[i - 1][j] + 1)
+                .min(d[i][j - 1] + 1)
+                .min(d[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                d[i][j] = d[i][j].min(d[i - 2][j - 2] + 1);
+            }
+        }
+    }
+    d[a.len()][b.len()]
+}
+
+/// Returns a suggestion such as `, did you mean 'sin'?` if one of the candidates is similar to
+/// the name and an empty string otherwise. Names with less than 3 characters have no
+/// suggestions.
+fn did_you_mean<'b>(name: &str, candidates: impl Iterator<Item = &'b str>) -> String {
+    let max_distance = name.chars().count() / 3;
+    candidates
+        .filter(|candidate| *candidate != name)
+        .map(|candidate| (edit_distance(name, candidate), candidate))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| format!(", did you mean '{}'?", candidate))
+        .unwrap_or_default()
+}
+
 struct PairPreCondition<'a, T: DataType> {
     apply: fn(&ParsedToken<'a, T>, &ParsedToken<'a, T>) -> ExResult<()>,
 }
@@ -337,6 +1517,11 @@ where
     if parsed_tokens.is_empty() {
         return Err(ExError::new("cannot parse empty string"));
     };
+    if parsed_tokens.iter().any(|pt| matches!(pt, ParsedToken::Comma)) {
+        return Err(ExError::new(
+            "a comma can only separate arguments of function calls",
+        ));
+    }
 
     let pair_pre_conditions = make_pair_pre_conditions::<T>();
     (0..parsed_tokens.len() - 1)
@@ -368,6 +1553,11 @@ where
                     }
                     Ok(())
                 }
+                ParsedToken::Op(op) if !op.has_bin() && !op.has_unary() => Err(format_exerr!(
+                    "postfix operator {} at position {} needs an operand on its left",
+                    op.repr(),
+                    i
+                )),
                 _ => Ok(()),
             }
         })
@@ -393,6 +1583,13 @@ fn test_is_numeric() {
     assert_eq!(is_numeric_text("4.").unwrap(), "4.");
     assert_eq!(is_numeric_text(".4").unwrap(), ".4");
     assert_eq!(is_numeric_text("23.414").unwrap(), "23.414");
+    assert_eq!(is_numeric_text("1e-3*x").unwrap(), "1e-3");
+    assert_eq!(is_numeric_text("6.022E23").unwrap(), "6.022E23");
+    assert_eq!(is_numeric_text(".5e+2").unwrap(), ".5e+2");
+    assert_eq!(is_numeric_text("2e").unwrap(), "2");
+    assert_eq!(is_numeric_text("2e-x").unwrap(), "2");
+    assert_eq!(is_numeric_text("2E+").unwrap(), "2");
+    assert!(is_numeric_text("e3").is_none());
 }
 
 #[test]
@@ -412,7 +1609,13 @@ fn test_preconditions() {
         }
 
         let ops = FloatOpsFactory::<f32>::make();
-        let elts = tokenize_and_analyze(text, &ops, is_numeric_text);
+        let elts = tokenize_and_analyze(
+            text,
+            &ops,
+            is_numeric_text,
+            is_var_name_text,
+            &ParseOptions::default(),
+        );
         println!("{:?}", elts);
         match elts {
             Err(e) => check_err_msg::<Vec<ParsedToken<f32>>>(Err(e), msg_part),
@@ -455,3 +1658,78 @@ fn test_preconditions() {
         r"number/variable cannot be on the left of a unary operator",
     );
 }
+
+#[test]
+fn test_statements() -> ExResult<()> {
+    fn statements<'a>(text: &'a str, ops: &[Operator<'a, f64>]) -> ExResult<ParsedTokenVec<'a, f64>> {
+        tokenize_statements(text, ops, is_numeric_text, is_var_name_text, &ParseOptions::default())
+    }
+    fn analyze<'a>(text: &'a str, ops: &[Operator<'a, f64>]) -> ExResult<ParsedTokenVec<'a, f64>> {
+        tokenize_and_analyze(text, ops, is_numeric_text, is_var_name_text, &ParseOptions::default())
+    }
+    assert_eq!(split_statements("a=1; b").as_slice(), ["a=1", " b"]);
+    assert_eq!(split_statements("{a;b} = 1;(x;y)").as_slice(), ["{a;b} = 1", "(x;y)"]);
+    assert_eq!(split_statements(r#"a = "x;y"; a"#).as_slice(), [r#"a = "x;y""#, " a"]);
+    assert_eq!(split_assignment(" r = x^2", is_var_name_text), Some(("r", " x^2")));
+    assert_eq!(split_assignment("{r 1}= x", is_var_name_text), Some(("r 1", " x")));
+    assert_eq!(split_assignment("r == x", is_var_name_text), None);
+    assert_eq!(split_assignment("r <= x", is_var_name_text), None);
+    assert_eq!(split_assignment("2 = x", is_var_name_text), None);
+    fn is_dotted(text: &str) -> Option<&str> {
+        let n = text
+            .find(|c: char| !(c.is_alphanumeric() || c == '.'))
+            .unwrap_or(text.len());
+        Some(&text[..n])
+    }
+    assert_eq!(split_assignment("a.b = 1", is_dotted), Some(("a.b", " 1")));
+    assert_eq!(split_assignment("a.b = 1", is_var_name_text), None);
+
+    let ops = FloatOpsFactory::<f64>::make();
+    let tokens = statements("a = 2*x; b = a+1; b*a", &ops)?;
+    let tokens_ref = analyze("((2*x)+1)*(2*x)", &ops)?;
+    assert_eq!(tokens, tokens_ref);
+    assert!(statements("a = 2*x; a = 1", &ops).is_err());
+    assert!(statements("2*x; a", &ops).is_err());
+    assert!(statements("sin = 2*x; sin", &ops).is_err());
+    assert!(statements("a = ; a", &ops).is_err());
+    assert!(statements("a = x; ", &ops).is_err());
+
+    let (name, params, body) = split_definition(" f( x, y ) = x*y", is_var_name_text).unwrap();
+    assert_eq!((name, params.as_slice(), body), ("f", ["x", "y"].as_slice(), " x*y"));
+    let (name, params, body) = split_definition("g()=1", is_var_name_text).unwrap();
+    assert_eq!((name, params.as_slice(), body), ("g", [].as_slice(), "1"));
+    assert!(split_definition("f(x) == 1", is_var_name_text).is_none());
+    assert!(split_definition("f(g(x)) = 1", is_var_name_text).is_none());
+    assert!(split_definition("f(x+1) = 1", is_var_name_text).is_some());
+    assert!(check_definition("f", &["x+1"], is_var_name_text).is_err());
+    assert!(check_definition("f", &["x", "x"], is_var_name_text).is_err());
+    assert!(check_definition("f", &["x.y"], is_var_name_text).is_err());
+    assert!(check_definition("f", &["x.y"], is_dotted).is_ok());
+    let tokens = statements("f(a, b) = a*b; f(x+1, 2)", &ops)?;
+    let tokens_ref = analyze("((x+1)*(2))", &ops)?;
+    assert_eq!(tokens, tokens_ref);
+    assert!(statements("f(a) = f(a); f(1)", &ops).is_err());
+    assert!(statements("f(a) = a; f(1, 2)", &ops).is_err());
+    assert!(statements("f(a) = a; f(1", &ops).is_err());
+
+    let tokens = analyze("-(x, 2)*^(-(y), x)", &ops)?;
+    let tokens_ref = analyze("((x)-(2))*((-(y))^(x))", &ops)?;
+    assert_eq!(tokens, tokens_ref);
+    assert!(analyze("^(x)", &ops).is_err());
+    assert!(analyze("-(x, y, z)", &ops).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_did_you_mean() {
+    assert_eq!(edit_distance("tmep", "temp"), 1);
+    assert_eq!(edit_distance("sinn", "sin"), 1);
+    assert_eq!(edit_distance("", "cos"), 3);
+    assert_eq!(edit_distance("αβγ", "αγβ"), 1);
+    assert_eq!(edit_distance("kitten", "sitting"), 3);
+    let candidates = ["sin", "sinh", "cos"];
+    assert_eq!(did_you_mean("sinn", candidates.iter().copied()), ", did you mean 'sin'?");
+    assert_eq!(did_you_mean("cso", candidates.iter().copied()), ", did you mean 'cos'?");
+    assert_eq!(did_you_mean("tan", candidates.iter().copied()), "");
+    assert_eq!(did_you_mean("sn", candidates.iter().copied()), "");
+}
@@ -1,14 +1,13 @@
 use crate::{
     definitions::{N_BINOPS_OF_DEEPEX_ON_STACK, N_UNARYOPS_OF_DEEPEX_ON_STACK},
-    expression::flat::ExprIdxVec,
+    expression::flat::{detail::assoc_order, ExprIdxVec},
     format_exerr,
     operators::{BinOp, UnaryOp},
-    partial::{BinOpVec, DeepEx, DeepNode},
+    partial::{BinOpVec, DeepEx, DeepNode, DiffDataType},
     ExError, ExResult,
 };
 use std::{fmt::Debug, iter};
 
-use num::Float;
 use smallvec::SmallVec;
 
 #[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Debug)]
@@ -88,7 +87,10 @@ pub fn prioritized_indices<T: Clone + Debug>(
     indices.sort_by(|i1, i2| {
         let prio_i1 = prio_increase(*i1);
         let prio_i2 = prio_increase(*i2);
-        prio_i2.partial_cmp(&prio_i1).unwrap()
+        prio_i2.partial_cmp(&prio_i1).unwrap().then_with(|| {
+            assoc_order(*i1, bin_ops[*i1].is_right_assoc)
+                .cmp(&assoc_order(*i2, bin_ops[*i2].is_right_assoc))
+        })
     });
     indices
 }
@@ -161,7 +163,7 @@ pub fn operate_bin<'a, T: Clone + Debug>(
 
 pub fn is_num<T: Clone + Debug>(deepex: &DeepEx<T>, num: T) -> bool
 where
-    T: Float,
+    T: DiffDataType,
 {
     deepex.nodes().len() == 1
         && match &deepex.nodes()[0] {
@@ -336,6 +338,11 @@ where
                     break;
                 }
             },
+            ParsedToken::Comma => {
+                return Err(ExError::new(
+                    "a comma can only separate arguments of function calls",
+                ));
+            }
         }
     }
     Ok((
@@ -4,7 +4,7 @@ use std::{
     str::FromStr,
 };
 
-use num::Float;
+use num::NumCast;
 use smallvec::SmallVec;
 
 use crate::{
@@ -21,6 +21,26 @@ pub use details::{BinOpsWithReprs, UnaryOpWithReprs};
 use crate::parser;
 
 mod details;
+
+/// *`feature = "partial"`* - Data types that can be differentiated. Besides the operators
+/// of an expression, differentiation needs numeric constants such as `0`, `1`, or `ln(2)`
+/// that are created from `f64`s. The trait is implemented for all data types that can be cast
+/// from `f64` via [`NumCast`](num::NumCast). This covers all types that implement
+/// [`Float`](num::Float), which was required up to version 0.16, as well as complex numbers.
+/// For complex numbers, only holomorphic operators can be differentiated.
+pub trait DiffDataType: DataType + Copy + PartialEq {
+    fn from_f64(x: f64) -> Self;
+}
+
+impl<T> DiffDataType for T
+where
+    T: DataType + Copy + PartialEq + NumCast,
+{
+    fn from_f64(x: f64) -> Self {
+        <T as NumCast>::from(x).unwrap()
+    }
+}
+
 /// *`feature = "partial"`* - Trait for partial differentiation.  
 pub trait Differentiate<T: Clone>
 where
@@ -63,7 +83,7 @@ where
     ///
     fn partial(&self, var_idx: usize) -> ExResult<Self>
     where
-        T: DataType + Float,
+        T: DiffDataType,
         <T as FromStr>::Err: Debug,
     {
         self.partial_nth(var_idx, 1)
@@ -101,7 +121,7 @@ where
     ///
     fn partial_nth(&self, var_idx: usize, n: usize) -> ExResult<Self>
     where
-        T: DataType + Float,
+        T: DiffDataType,
         <T as FromStr>::Err: Debug,
     {
         self.partial_iter(iter::repeat(&var_idx).take(n))
@@ -140,7 +160,7 @@ where
     ///
     fn partial_iter<'a, I>(&self, var_idxs: I) -> ExResult<Self>
     where
-        T: DataType + Float,
+        T: DiffDataType,
         <T as FromStr>::Err: Debug,
         I: Iterator<Item = &'a usize> + Clone,
     {
@@ -162,7 +182,7 @@ where
     fn to_deepex<'a>(&'a self, ops: &[Operator<'a, T>]) -> ExResult<DeepEx<'a, T>>
     where
         Self: Sized,
-        T: DataType + Float,
+        T: DiffDataType,
         <T as FromStr>::Err: Debug;
 
     /// *`feature = "partial"`* - Every trait implementation needs to implement the conversion from
@@ -170,7 +190,7 @@ where
     fn from_deepex(deepex: DeepEx<T>, ops: &[Operator<T>]) -> ExResult<Self>
     where
         Self: Sized,
-        T: DataType + Float,
+        T: DiffDataType,
         <T as FromStr>::Err: Debug;
 }
 
@@ -217,13 +237,13 @@ pub enum DeepNode<'a, T: Clone + Debug> {
 }
 impl<'a, T: Debug> DeepNode<'a, T>
 where
-    T: Clone + Debug + Float,
+    T: Clone + Debug + DiffDataType,
 {
     fn zero() -> Self {
-        DeepNode::Num(T::from(0.0).unwrap())
+        DeepNode::Num(T::from_f64(0.0))
     }
     fn one() -> Self {
-        DeepNode::Num(T::from(1.0).unwrap())
+        DeepNode::Num(T::from_f64(1.0))
     }
     fn num(n: T) -> Self {
         DeepNode::Num(n)
@@ -369,21 +389,21 @@ where
 
     fn one() -> DeepEx<'a, T>
     where
-        T: Float,
+        T: DiffDataType,
     {
         DeepEx::from_node(DeepNode::one())
     }
 
     fn zero() -> DeepEx<'a, T>
     where
-        T: Float,
+        T: DiffDataType,
     {
         DeepEx::from_node(DeepNode::zero())
     }
 
     fn from_num(x: T) -> DeepEx<'a, T>
     where
-        T: Float,
+        T: DiffDataType,
     {
         DeepEx::from_node(DeepNode::num(x))
     }
@@ -414,23 +434,23 @@ where
 
     fn is_num(&self, num: T) -> bool
     where
-        T: Float,
+        T: DiffDataType,
     {
         details::is_num(self, num)
     }
 
     fn is_one(&self) -> bool
     where
-        T: Float,
+        T: DiffDataType,
     {
-        self.is_num(T::from(1.0).unwrap())
+        self.is_num(T::from_f64(1.0))
     }
 
     fn is_zero(&self) -> bool
     where
-        T: Float,
+        T: DiffDataType,
     {
-        self.is_num(T::from(0.0).unwrap())
+        self.is_num(T::from_f64(0.0))
     }
 
     pub fn reset_vars(&mut self, new_var_names: SmallVec<[&'a str; N_VARS_ON_STACK]>) {
@@ -550,7 +570,7 @@ fn make_op_missing_err(repr: &str) -> ExError {
     format_exerr!("operator {} needed for outer partial derivative", repr)
 }
 
-fn partial_derivative_outer<'a, T: Float + Debug>(
+fn partial_derivative_outer<'a, T: DiffDataType>(
     deepex: DeepEx<'a, T>,
     partial_derivative_ops: &[PartialDerivative<'a, T>],
     ops: &[Operator<'a, T>],
@@ -578,7 +598,7 @@ fn partial_derivative_outer<'a, T: Float + Debug>(
     })
 }
 
-fn partial_derivative_inner<'a, T: Float + Debug>(
+fn partial_derivative_inner<'a, T: DiffDataType>(
     var_idx: usize,
     deepex: DeepEx<'a, T>,
     partial_derivative_ops: &[PartialDerivative<'a, T>],
@@ -678,7 +698,7 @@ fn partial_derivative_inner<'a, T: Float + Debug>(
     Ok(res)
 }
 
-pub fn partial_deepex<'a, T: Float + Debug>(
+pub fn partial_deepex<'a, T: DiffDataType>(
     var_idx: usize,
     deepex: DeepEx<'a, T>,
     ops: &[Operator<'a, T>],
@@ -689,7 +709,7 @@ pub fn partial_deepex<'a, T: Float + Debug>(
     mul(inner, outer, mul_find(ops)?)
 }
 
-fn add<'a, T: Float + Debug>(
+fn add<'a, T: DiffDataType>(
     summand_1: DeepEx<'a, T>,
     summand_2: DeepEx<'a, T>,
     add_op: BinOpsWithReprs<'a, T>,
@@ -704,7 +724,7 @@ fn add<'a, T: Float + Debug>(
     })
 }
 
-fn sub<'a, T: Float + Debug>(
+fn sub<'a, T: DiffDataType>(
     sub_1: DeepEx<'a, T>,
     sub_2: DeepEx<'a, T>,
     sub_op: BinOpsWithReprs<'a, T>,
@@ -717,7 +737,7 @@ fn sub<'a, T: Float + Debug>(
     })
 }
 
-fn mul<'a, T: Float + Debug>(
+fn mul<'a, T: DiffDataType>(
     factor_1: DeepEx<'a, T>,
     factor_2: DeepEx<'a, T>,
     mul_op: BinOpsWithReprs<'a, T>,
@@ -736,7 +756,7 @@ fn mul<'a, T: Float + Debug>(
     })
 }
 
-fn div<'a, T: Float + Debug>(
+fn div<'a, T: DiffDataType>(
     numerator: DeepEx<'a, T>,
     denominator: DeepEx<'a, T>,
     div_op: BinOpsWithReprs<'a, T>,
@@ -753,7 +773,7 @@ fn div<'a, T: Float + Debug>(
     }
 }
 
-fn pow<'a, T: Float + Debug>(
+fn pow<'a, T: DiffDataType>(
     base: DeepEx<'a, T>,
     exponent: DeepEx<'a, T>,
     power_op: BinOpsWithReprs<'a, T>,
@@ -804,14 +824,14 @@ enum Base {
     Ten,
     Euler,
 }
-fn log_deri<'a, T: Float + Debug>(
+fn log_deri<'a, T: DiffDataType>(
     f: DeepEx<'a, T>,
     base: Base,
     ops: &[Operator<'a, T>],
 ) -> ExResult<DeepEx<'a, T>> {
     let div_op = div_find(ops)?;
     let lazy_mul_op = || mul_find(ops);
-    let ln_base = |base_float: f64| DeepEx::from_num(T::from(base_float).unwrap().ln());
+    let ln_base = |base_float: f64| DeepEx::from_num(T::from_f64(base_float.ln()));
     let x = f.with_new_latest_unary_op(UnaryOpWithReprs::new());
     let denominator = match base {
         Base::Ten => mul(x, ln_base(10.0), lazy_mul_op()?)?,
@@ -821,7 +841,7 @@ fn log_deri<'a, T: Float + Debug>(
     div(DeepEx::one(), denominator, div_op)
 }
 
-pub fn make_partial_derivative_ops<'a, T: Float + Debug>() -> Vec<PartialDerivative<'a, T>> {
+pub fn make_partial_derivative_ops<'a, T: DiffDataType>() -> Vec<PartialDerivative<'a, T>> {
     vec![
         PartialDerivative {
             repr: "^",
@@ -957,7 +977,7 @@ pub fn make_partial_derivative_ops<'a, T: Float + Debug>() -> Vec<PartialDerivat
                     let mul_op = mul_find(ops)?;
                     let div_op = div_find(ops)?;
                     let one = DeepEx::one();
-                    let two = DeepEx::from_num(T::from(2.0).unwrap());
+                    let two = DeepEx::from_num(T::from_f64(2.0));
                     div(one, mul(two, f, mul_op)?, div_op)
                 },
             ),
@@ -971,6 +991,15 @@ pub fn make_partial_derivative_ops<'a, T: Float + Debug>() -> Vec<PartialDerivat
                 },
             ),
         },
+        PartialDerivative {
+            repr: "log",
+            bin_op: None,
+            unary_outer_op: Some(
+                |f: DeepEx<'a, T>, ops: &[Operator<'a, T>]| -> ExResult<DeepEx<'a, T>> {
+                    log_deri(f, Base::Euler, ops)
+                },
+            ),
+        },
         PartialDerivative {
             repr: "log10",
             bin_op: None,
@@ -1026,7 +1055,7 @@ pub fn make_partial_derivative_ops<'a, T: Float + Debug>() -> Vec<PartialDerivat
                     let cos_op = find_as_unary_op_with_reprs("cos", ops)?;
                     let power_op = pow_find(ops)?;
                     let div_op = div_find(ops)?;
-                    let two = DeepEx::from_num(T::from(2.0).unwrap());
+                    let two = DeepEx::from_num(T::from_f64(2.0));
                     let cos_squared_ex = f
                         .clone()
                         .with_new_latest_unary_op(cos_op)
@@ -1046,7 +1075,7 @@ pub fn make_partial_derivative_ops<'a, T: Float + Debug>() -> Vec<PartialDerivat
                     let sub_op = sub_find(ops)?;
                     let div_op = div_find(ops)?;
 
-                    let two = DeepEx::from_num(T::from(2.0).unwrap());
+                    let two = DeepEx::from_num(T::from_f64(2.0));
                     let inner_squared = f
                         .with_new_latest_unary_op(UnaryOpWithReprs::new())
                         .operate_bin(two, power_op);
@@ -1068,7 +1097,7 @@ pub fn make_partial_derivative_ops<'a, T: Float + Debug>() -> Vec<PartialDerivat
                     let div_op = div_find(ops)?;
 
                     let one = DeepEx::one();
-                    let two = DeepEx::from_num(T::from(2.0).unwrap());
+                    let two = DeepEx::from_num(T::from_f64(2.0));
                     let inner_squared = f
                         .with_new_latest_unary_op(UnaryOpWithReprs::new())
                         .operate_bin(two, power_op);
@@ -1087,7 +1116,7 @@ pub fn make_partial_derivative_ops<'a, T: Float + Debug>() -> Vec<PartialDerivat
                     let add_op = add_find(ops)?;
                     let div_op = div_find(ops)?;
                     let one = DeepEx::one();
-                    let two = DeepEx::from_num(T::from(2.0).unwrap());
+                    let two = DeepEx::from_num(T::from_f64(2.0));
                     let inner_squared = pow(
                         f.with_new_latest_unary_op(UnaryOpWithReprs::new()),
                         two,
@@ -1126,7 +1155,7 @@ pub fn make_partial_derivative_ops<'a, T: Float + Debug>() -> Vec<PartialDerivat
                     let pow_op = pow_find(ops)?;
                     let tanh_op = find_as_unary_op_with_reprs("tanh", ops)?;
                     let sub_op = sub_find(ops)?;
-                    let two = DeepEx::from_num(T::from(2.0).unwrap());
+                    let two = DeepEx::from_num(T::from_f64(2.0));
                     sub(
                         one,
                         pow(f.with_new_latest_unary_op(tanh_op), two, pow_op)?,
@@ -1155,7 +1184,13 @@ where
     <T as FromStr>::Err: Debug,
     F: Fn(&'a str) -> Option<&'a str>,
 {
-    let parsed_tokens = parser::tokenize_and_analyze(text, ops, is_numeric)?;
+    let parsed_tokens = parser::tokenize_and_analyze(
+        text,
+        ops,
+        is_numeric,
+        parser::is_var_name_text,
+        &parser::ParseOptions::default(),
+    )?;
     parser::check_parsed_token_preconditions(&parsed_tokens)?;
     let parsed_vars = parser::find_parsed_vars(&parsed_tokens);
     let (expr, _) =
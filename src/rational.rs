@@ -0,0 +1,376 @@
+use std::{
+    fmt::{self, Debug, Display, Formatter},
+    marker::PhantomData,
+    str::FromStr,
+};
+
+use num::{
+    integer::Roots,
+    rational::Ratio,
+    traits::{CheckedAdd, CheckedDiv, CheckedMul, CheckedSub},
+    Integer, Signed, ToPrimitive, Zero,
+};
+
+use crate::{
+    format_exerr, BinOp, ExError, ExResult, FlatEx, MakeOperators, NumberMatcher, Operator,
+};
+
+/// Integer types that can be used as numerator and denominator of a [`Rational`](Rational),
+/// e.g., `i64` or [`BigInt`](https://docs.rs/num/latest/num/struct.BigInt.html).
+pub trait RationalInt:
+    Integer
+    + Signed
+    + Roots
+    + ToPrimitive
+    + CheckedAdd
+    + CheckedSub
+    + CheckedMul
+    + CheckedDiv
+    + Clone
+    + FromStr
+    + Debug
+    + Display
+{
+}
+impl<I> RationalInt for I where
+    I: Integer
+        + Signed
+        + Roots
+        + ToPrimitive
+        + CheckedAdd
+        + CheckedSub
+        + CheckedMul
+        + CheckedDiv
+        + Clone
+        + FromStr
+        + Debug
+        + Display
+{
+}
+
+/// Exact fraction of two integers of type `I`. With `I = i64` the fraction is based on
+/// [`Ratio<i64>`](https://docs.rs/num/latest/num/rational/struct.Ratio.html) and with
+/// `I = BigInt` on [`BigRational`](https://docs.rs/num/latest/num/type.BigRational.html).
+/// Literals such as `3` or `0.25` are converted into exact fractions such as `1/4`. Since operators
+/// cannot return errors, operations whose result cannot be represented exactly such as `sqrt(2)`,
+/// divisions by zero, and overflows result in the variant `Error`. Similar to [`Val`](crate::Val),
+/// the error can be transformed into a `Result` via [`to_result`](Rational::to_result).
+#[derive(Clone, Debug, PartialEq)]
+pub enum Rational<I: RationalInt = i64> {
+    Value(Ratio<I>),
+    Error(ExError),
+}
+
+impl<I: RationalInt> Rational<I> {
+    /// Creates the fraction `numer/denom`. Fails if the denominator is zero.
+    pub fn new(numer: I, denom: I) -> ExResult<Self> {
+        if denom.is_zero() {
+            Err(format_exerr!("denominator of {}/{} is zero", numer, denom))
+        } else {
+            Ok(Rational::Value(Ratio::new(numer, denom)))
+        }
+    }
+    /// Returns the fraction or the contained error.
+    pub fn to_result(self) -> ExResult<Ratio<I>> {
+        match self {
+            Rational::Value(r) => Ok(r),
+            Rational::Error(e) => Err(e),
+        }
+    }
+}
+
+impl<I: RationalInt> From<Ratio<I>> for Rational<I> {
+    fn from(r: Ratio<I>) -> Self {
+        Rational::Value(r)
+    }
+}
+
+impl<I: RationalInt> Display for Rational<I> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Rational::Value(r) => write!(f, "{}", r),
+            Rational::Error(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+/// Parses integers such as `3`, decimals such as `-0.25`, and fractions such as `1/3`. Decimals
+/// are converted into exact fractions, i.e., `0.25` becomes `1/4`.
+impl<I: RationalInt> FromStr for Rational<I> {
+    type Err = ExError;
+    fn from_str(s: &str) -> ExResult<Self> {
+        let s = s.trim();
+        let parse_int = |int_str: &str| {
+            int_str
+                .trim()
+                .parse::<I>()
+                .map_err(|_| format_exerr!("cannot parse '{}' as rational", s))
+        };
+        if let Some((numer, denom)) = s.split_once('/') {
+            return Rational::new(parse_int(numer)?, parse_int(denom)?);
+        }
+        let (int_part, frac_part) = s.split_once('.').unwrap_or((s, ""));
+        let numer = parse_int(&format!("{}{}", int_part, frac_part))?;
+        let denom = parse_int(&format!("1{}", "0".repeat(frac_part.len())))?;
+        Rational::new(numer, denom)
+    }
+}
+
+fn bin_op<I: RationalInt>(
+    a: Rational<I>,
+    b: Rational<I>,
+    op: &str,
+    f: fn(&Ratio<I>, &Ratio<I>) -> Option<Ratio<I>>,
+) -> Rational<I> {
+    match (a, b) {
+        (Rational::Value(a), Rational::Value(b)) => match f(&a, &b) {
+            Some(r) => Rational::Value(r),
+            None => Rational::Error(format_exerr!("overflow in {} {} {}", a, op, b)),
+        },
+        (Rational::Error(e), _) | (_, Rational::Error(e)) => Rational::Error(e),
+    }
+}
+
+fn div<I: RationalInt>(a: Rational<I>, b: Rational<I>, op: &str) -> Rational<I> {
+    match (a, b) {
+        (Rational::Value(a), Rational::Value(b)) if b.is_zero() => {
+            Rational::Error(format_exerr!("division by zero in {} {} {}", a, op, b))
+        }
+        (a, b) => {
+            if op == "/" {
+                bin_op(a, b, op, |a, b| a.checked_div(b))
+            } else {
+                bin_op(a, b, op, |a, b| {
+                    a.checked_div(b)?
+                        .trunc()
+                        .checked_mul(b)
+                        .and_then(|q| a.checked_sub(&q))
+                })
+            }
+        }
+    }
+}
+
+fn map<I: RationalInt>(a: Rational<I>, f: fn(Ratio<I>) -> ExResult<Ratio<I>>) -> Rational<I> {
+    match a {
+        Rational::Value(a) => f(a).map(Rational::Value).unwrap_or_else(Rational::Error),
+        Rational::Error(e) => Rational::Error(e),
+    }
+}
+
+fn neg<I: RationalInt>(a: Ratio<I>) -> ExResult<Ratio<I>> {
+    Ratio::zero()
+        .checked_sub(&a)
+        .ok_or_else(|| format_exerr!("overflow in -({})", a))
+}
+
+/// Computes the `n`-th root if it is rational.
+fn root<I: RationalInt>(a: &Ratio<I>, n: u32, repr: &str) -> ExResult<Ratio<I>> {
+    if a.is_negative() && n.is_even() {
+        return Err(format_exerr!("{} is not a real number", repr));
+    }
+    let exact_root = |x: &I| {
+        let r = x.nth_root(n);
+        match num::checked_pow(r.clone(), n as usize) {
+            Some(r_pow) if &r_pow == x => Some(r),
+            _ => None,
+        }
+    };
+    match (exact_root(a.numer()), exact_root(a.denom())) {
+        (Some(numer), Some(denom)) => Ok(Ratio::new(numer, denom)),
+        _ => Err(format_exerr!(
+            "{} is irrational and cannot be represented as rational number",
+            repr
+        )),
+    }
+}
+
+fn pow<I: RationalInt>(a: Rational<I>, b: Rational<I>) -> Rational<I> {
+    let (a, b) = match (a, b) {
+        (Rational::Value(a), Rational::Value(b)) => (a, b),
+        (Rational::Error(e), _) | (_, Rational::Error(e)) => return Rational::Error(e),
+    };
+    let repr = format!("{}^({})", a, b);
+    let res = || -> ExResult<Ratio<I>> {
+        let base = if b.is_integer() {
+            a.clone()
+        } else {
+            let n = b
+                .denom()
+                .to_u32()
+                .ok_or_else(|| format_exerr!("exponent of {} too large", repr))?;
+            root(&a, n, &repr)?
+        };
+        // the absolute value of the smallest integer such as `i64::MIN` overflows
+        let exponent = b
+            .numer()
+            .to_i128()
+            .and_then(|e| e.unsigned_abs().to_usize())
+            .ok_or_else(|| format_exerr!("exponent of {} too large", repr))?;
+        let base = if b.is_negative() {
+            if base.is_zero() {
+                return Err(format_exerr!("division by zero in {}", repr));
+            }
+            base.recip()
+        } else {
+            base
+        };
+        num::checked_pow(base, exponent).ok_or_else(|| format_exerr!("overflow in {}", repr))
+    };
+    res().map(Rational::Value).unwrap_or_else(Rational::Error)
+}
+
+/// Factory of default operators for exact computations with [`Rational`](Rational)s. Results
+/// that cannot be represented exactly such as the irrational `sqrt(2)` or `2^(1/2)` result in an error,
+/// whereas `sqrt(9/4)` or `8^(2/3)` are computed exactly. Operators that are not in the list
+/// such as `sin` are not available for rationals and their usage leads to an error during parsing.
+///
+/// |representation|description|
+/// |--------------|-----------|
+/// |`^`| power with a rational exponent if the result is rational |
+/// |`*`| product |
+/// |`/`| division |
+/// |`%`| remainder with the sign of the dividend |
+/// |`+`| addition as binary or identity as unary operator|
+/// |`-`| subtraction as binary or inverting the sign as unary operator |
+/// |`abs`| absolute value |
+/// |`signum`| signum |
+/// |`floor`| largest integer less than or equal to a number |
+/// |`ceil`| smallest integer greater than or equal to a number |
+/// |`round`| nearest integer, rounding half-way cases away from zero |
+/// |`trunc`| integer part of a number |
+/// |`fract`| fractional part of a number |
+/// |`recip`| reciprocal |
+/// |`numer`| numerator of the reduced fraction |
+/// |`denom`| denominator of the reduced fraction |
+/// |`sqrt`| square root if the result is rational |
+///
+/// ```rust
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// #
+/// use exmex::prelude::*;
+/// use exmex::{FlatExRational, Rational};
+/// let expr = FlatExRational::<i64>::from_str("1/3 + x")?;
+/// let res = expr.eval(&[Rational::from_str("1/6")?])?;
+/// assert_eq!(res, Rational::from_str("0.5")?);
+/// let expr = FlatExRational::<i64>::from_str("sqrt(2)")?;
+/// let err = expr.eval(&[])?.to_result().unwrap_err();
+/// assert!(err.msg().contains("irrational"));
+/// assert!(FlatExRational::<i64>::from_str("sin(1)").is_err());
+/// #
+/// #     Ok(())
+/// # }
+/// ```
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Debug)]
+pub struct RationalOpsFactory<I: RationalInt = i64> {
+    dummy: PhantomData<I>,
+}
+
+impl<I: RationalInt> MakeOperators<Rational<I>> for RationalOpsFactory<I> {
+    /// Returns the default operators.
+    fn make<'a>() -> Vec<Operator<'a, Rational<I>>> {
+        vec![
+            Operator::make_bin(
+                "^",
+                BinOp {
+                    apply: pow,
+                    prio: 4,
+                    is_commutative: false,
+                    is_right_assoc: true,
+                },
+            ),
+            Operator::make_bin(
+                "*",
+                BinOp {
+                    apply: |a, b| bin_op(a, b, "*", |a, b| a.checked_mul(b)),
+                    prio: 2,
+                    is_commutative: true,
+                    is_right_assoc: false,
+                },
+            ),
+            Operator::make_bin(
+                "/",
+                BinOp {
+                    apply: |a, b| div(a, b, "/"),
+                    prio: 3,
+                    is_commutative: false,
+                    is_right_assoc: false,
+                },
+            ),
+            Operator::make_bin(
+                "%",
+                BinOp {
+                    apply: |a, b| div(a, b, "%"),
+                    prio: 3,
+                    is_commutative: false,
+                    is_right_assoc: false,
+                },
+            ),
+            Operator::make_bin_unary(
+                "+",
+                BinOp {
+                    apply: |a, b| bin_op(a, b, "+", |a, b| a.checked_add(b)),
+                    prio: 0,
+                    is_commutative: true,
+                    is_right_assoc: false,
+                },
+                |a| a,
+            ),
+            Operator::make_bin_unary(
+                "-",
+                BinOp {
+                    apply: |a, b| bin_op(a, b, "-", |a, b| a.checked_sub(b)),
+                    prio: 1,
+                    is_commutative: false,
+                    is_right_assoc: false,
+                },
+                |a| map(a, neg),
+            ),
+            Operator::make_unary("abs", |a| {
+                map(a, |a| if a.is_negative() { neg(a) } else { Ok(a) })
+            }),
+            Operator::make_unary("signum", |a| map(a, |a| Ok(a.signum()))),
+            Operator::make_unary("floor", |a| map(a, |a| Ok(a.floor()))),
+            Operator::make_unary("ceil", |a| map(a, |a| Ok(a.ceil()))),
+            Operator::make_unary("round", |a| map(a, |a| Ok(a.round()))),
+            Operator::make_unary("trunc", |a| map(a, |a| Ok(a.trunc()))),
+            Operator::make_unary("fract", |a| map(a, |a| Ok(a.fract()))),
+            Operator::make_unary("recip", |a| {
+                map(a, |a| {
+                    if a.is_zero() {
+                        Err(ExError::new("division by zero in recip(0)"))
+                    } else {
+                        Ok(a.recip())
+                    }
+                })
+            }),
+            Operator::make_unary("numer", |a| {
+                map(a, |a| Ok(Ratio::from_integer(a.numer().clone())))
+            }),
+            Operator::make_unary("denom", |a| {
+                map(a, |a| Ok(Ratio::from_integer(a.denom().clone())))
+            }),
+            Operator::make_unary("sqrt", |a| map(a, |a| root(&a, 2, &format!("sqrt({})", a)))),
+        ]
+    }
+}
+
+/// Alias for [`FlatEx`](FlatEx) with [`Rational`](Rational) as data type and
+/// [`RationalOpsFactory`](RationalOpsFactory) as operator factory. Integer and decimal literals
+/// are matched by the default [`NumberMatcher`](NumberMatcher) and converted into exact fractions.
+pub type FlatExRational<I = i64> = FlatEx<Rational<I>, RationalOpsFactory<I>, NumberMatcher>;
+
+#[test]
+fn test_rational_from_str() {
+    let r = |n: i64, d: i64| Rational::Value(Ratio::new(n, d));
+    assert_eq!(Rational::from_str("0.25").unwrap(), r(1, 4));
+    assert_eq!(Rational::from_str(".5").unwrap(), r(1, 2));
+    assert_eq!(Rational::from_str("-1.50").unwrap(), r(-3, 2));
+    assert_eq!(Rational::from_str("42").unwrap(), r(42, 1));
+    assert_eq!(Rational::from_str("2/6").unwrap(), r(1, 3));
+    assert!(Rational::<i64>::from_str("1/0").is_err());
+    assert!(Rational::<i64>::from_str("1.2.3").is_err());
+    assert!(Rational::<i64>::from_str("0.12345678901234567890123").is_err());
+    assert!(Rational::<num::BigInt>::from_str("0.12345678901234567890123").is_ok());
+}
@@ -0,0 +1,610 @@
+use std::{
+    error::Error,
+    fmt::{self, Display, Formatter},
+    str::FromStr,
+};
+
+use crate::{
+    expression::MatchLiteral, format_exerr, literal_matcher_from_pattern, BinOp, ExError, ExResult,
+    FlatEx, MakeOperators, Operator,
+};
+
+const N_BASE_UNITS: usize = 7;
+const BASE_UNITS: [&str; N_BASE_UNITS] = ["m", "kg", "s", "A", "K", "mol", "cd"];
+
+/// *`feature = "units"`* - Exponents of the SI base units meter, kilogram, second, ampere,
+/// kelvin, mole, and candela. For instance, a velocity has the dimension `m*s^-1`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Dimension(pub [i8; N_BASE_UNITS]);
+
+impl Dimension {
+    pub fn is_dimensionless(&self) -> bool {
+        self.0.iter().all(|e| *e == 0)
+    }
+    fn combine(self, other: Self, f: fn(i8, i8) -> Option<i8>) -> Option<Self> {
+        let mut res = [0; N_BASE_UNITS];
+        for (i, r) in res.iter_mut().enumerate() {
+            *r = f(self.0[i], other.0[i])?;
+        }
+        Some(Dimension(res))
+    }
+    fn powf(self, exponent: f64) -> Option<Self> {
+        let mut res = [0; N_BASE_UNITS];
+        for (r, e) in res.iter_mut().zip(self.0.iter()) {
+            let new_e = *e as f64 * exponent;
+            if new_e.fract() != 0.0 || new_e.abs() > i8::MAX as f64 {
+                return None;
+            }
+            *r = new_e as i8;
+        }
+        Some(Dimension(res))
+    }
+}
+
+impl Display for Dimension {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        if self.is_dimensionless() {
+            return write!(f, "1");
+        }
+        let factors = BASE_UNITS
+            .iter()
+            .zip(self.0.iter())
+            .filter(|(_, e)| **e != 0)
+            .map(|(u, e)| {
+                if *e == 1 {
+                    u.to_string()
+                } else {
+                    format!("{}^{}", u, e)
+                }
+            })
+            .collect::<Vec<_>>();
+        write!(f, "{}", factors.join("*"))
+    }
+}
+
+/// *`feature = "units"`* - A named unit such as `km` that is a multiple of a
+/// combination of SI base units.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Unit {
+    pub name: &'static str,
+    /// factor to convert a value in this unit to SI base units
+    pub scale: f64,
+    pub dim: Dimension,
+}
+
+const fn unit(name: &'static str, scale: f64, dim: [i8; N_BASE_UNITS]) -> Unit {
+    Unit {
+        name,
+        scale,
+        dim: Dimension(dim),
+    }
+}
+
+const UNITS: [Unit; 25] = [
+    unit("m", 1.0, [1, 0, 0, 0, 0, 0, 0]),
+    unit("km", 1e3, [1, 0, 0, 0, 0, 0, 0]),
+    unit("cm", 1e-2, [1, 0, 0, 0, 0, 0, 0]),
+    unit("mm", 1e-3, [1, 0, 0, 0, 0, 0, 0]),
+    unit("kg", 1.0, [0, 1, 0, 0, 0, 0, 0]),
+    unit("g", 1e-3, [0, 1, 0, 0, 0, 0, 0]),
+    unit("mg", 1e-6, [0, 1, 0, 0, 0, 0, 0]),
+    unit("s", 1.0, [0, 0, 1, 0, 0, 0, 0]),
+    unit("ms", 1e-3, [0, 0, 1, 0, 0, 0, 0]),
+    unit("min", 60.0, [0, 0, 1, 0, 0, 0, 0]),
+    unit("h", 3600.0, [0, 0, 1, 0, 0, 0, 0]),
+    unit("A", 1.0, [0, 0, 0, 1, 0, 0, 0]),
+    unit("K", 1.0, [0, 0, 0, 0, 1, 0, 0]),
+    unit("mol", 1.0, [0, 0, 0, 0, 0, 1, 0]),
+    unit("cd", 1.0, [0, 0, 0, 0, 0, 0, 1]),
+    unit("Hz", 1.0, [0, 0, -1, 0, 0, 0, 0]),
+    unit("N", 1.0, [1, 1, -2, 0, 0, 0, 0]),
+    unit("Pa", 1.0, [-1, 1, -2, 0, 0, 0, 0]),
+    unit("J", 1.0, [2, 1, -2, 0, 0, 0, 0]),
+    unit("kJ", 1e3, [2, 1, -2, 0, 0, 0, 0]),
+    unit("W", 1.0, [2, 1, -3, 0, 0, 0, 0]),
+    unit("kW", 1e3, [2, 1, -3, 0, 0, 0, 0]),
+    unit("C", 1.0, [0, 0, 1, 1, 0, 0, 0]),
+    unit("V", 1.0, [2, 1, -3, -1, 0, 0, 0]),
+    unit("L", 1e-3, [3, 0, 0, 0, 0, 0, 0]),
+];
+
+fn find_unit(name: &str) -> Option<&'static Unit> {
+    UNITS.iter().find(|u| u.name == name)
+}
+
+/// *`feature = "units"`* - Error that results from operations on quantities with
+/// incompatible dimensions.
+#[derive(Clone, Debug, PartialEq)]
+pub enum DimensionError {
+    /// Both operands of operators such as `+` or `to` need the same dimension.
+    Incompatible {
+        op: &'static str,
+        left: Dimension,
+        right: Dimension,
+    },
+    /// Operands of functions such as `sin` and exponents need to be dimensionless.
+    NotDimensionless { op: &'static str, dim: Dimension },
+    /// Powers and roots need to result in integer exponents of the base units, e.g., `sqrt(2 m)` is invalid.
+    NonIntegerExponent { dim: Dimension, exponent: f64 },
+    /// Exponents of the base units need to fit into an `i8`, e.g., `1 m^100 * 1 m^100` is invalid.
+    ExponentOverflow {
+        op: &'static str,
+        left: Dimension,
+        right: Dimension,
+    },
+}
+
+impl Display for DimensionError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            DimensionError::Incompatible { op, left, right } => write!(
+                f,
+                "cannot apply '{}' to quantities of dimensions {} and {}",
+                op, left, right
+            ),
+            DimensionError::NotDimensionless { op, dim } => write!(
+                f,
+                "'{}' expects a dimensionless quantity but got dimension {}",
+                op, dim
+            ),
+            DimensionError::NonIntegerExponent { dim, exponent } => write!(
+                f,
+                "cannot raise dimension {} to the power of {}",
+                dim, exponent
+            ),
+            DimensionError::ExponentOverflow { op, left, right } => write!(
+                f,
+                "exponents overflow when applying '{}' to quantities of dimensions {} and {}",
+                op, left, right
+            ),
+        }
+    }
+}
+
+impl Error for DimensionError {}
+
+/// *`feature = "units"`* - Physical quantity consisting of a value in SI base units and its
+/// dimension. Literals such as `3 km`, `2 s`, `9.81 m*s^-2`, or `m^2` are parsed into quantities.
+/// Values are internally stored in SI base units. Quantities that are created from literals or
+/// converted with `to` additionally remember their unit for display. Since operators cannot return
+/// errors, operations on incompatible dimensions result in the variant `Error`. Similar to
+/// [`Val`](crate::Val), the error can be transformed into a `Result` via
+/// [`to_result`](Quantity::to_result).
+#[derive(Clone, Debug, PartialEq)]
+pub enum Quantity {
+    Value {
+        si_value: f64,
+        dim: Dimension,
+        unit: Option<Unit>,
+    },
+    Error(DimensionError),
+}
+
+impl Quantity {
+    /// Creates a dimensionless quantity.
+    pub fn dimensionless(value: f64) -> Self {
+        Quantity::Value {
+            si_value: value,
+            dim: Dimension::default(),
+            unit: None,
+        }
+    }
+    /// Creates a quantity with a value given in the unit with name `unit_name`, e.g., `km`.
+    pub fn new(value: f64, unit_name: &str) -> ExResult<Self> {
+        let unit =
+            find_unit(unit_name).ok_or_else(|| format_exerr!("unknown unit '{}'", unit_name))?;
+        Ok(Quantity::Value {
+            si_value: value * unit.scale,
+            dim: unit.dim,
+            unit: Some(*unit),
+        })
+    }
+    fn si(si_value: f64, dim: Dimension) -> Self {
+        Quantity::Value {
+            si_value,
+            dim,
+            unit: None,
+        }
+    }
+    /// Returns the quantity or the contained [`DimensionError`](DimensionError).
+    pub fn to_result(self) -> Result<Self, DimensionError> {
+        match self {
+            Quantity::Error(e) => Err(e),
+            _ => Ok(self),
+        }
+    }
+    /// Returns the value in the unit of the quantity or in SI base units if it has no unit.
+    pub fn value(&self) -> Result<f64, DimensionError> {
+        match self {
+            Quantity::Value {
+                si_value,
+                unit: Some(unit),
+                ..
+            } => Ok(si_value / unit.scale),
+            Quantity::Value { si_value, .. } => Ok(*si_value),
+            Quantity::Error(e) => Err(e.clone()),
+        }
+    }
+    pub fn dim(&self) -> Result<Dimension, DimensionError> {
+        match self {
+            Quantity::Value { dim, .. } => Ok(*dim),
+            Quantity::Error(e) => Err(e.clone()),
+        }
+    }
+}
+
+impl Display for Quantity {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Quantity::Value {
+                si_value,
+                unit: Some(unit),
+                ..
+            } => write!(f, "{} {}", si_value / unit.scale, unit.name),
+            Quantity::Value { si_value, dim, .. } if dim.is_dimensionless() => {
+                write!(f, "{}", si_value)
+            }
+            Quantity::Value { si_value, dim, .. } => write!(f, "{} {}", si_value, dim),
+            Quantity::Error(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+/// Parses a number followed by an optional unit term such as `3`, `3 km`, `km`, or `9.81 m*s^-2`.
+/// The factors of the unit term are separated by `*` and can have integer exponents.
+impl FromStr for Quantity {
+    type Err = ExError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let n_num_chars = s
+            .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+            .unwrap_or(s.len());
+        let (num, unit_term) = s.split_at(n_num_chars);
+        let value = if num.is_empty() {
+            1.0
+        } else {
+            num.parse::<f64>()
+                .map_err(|e| format_exerr!("cannot parse '{}' as quantity, {:?}", s, e))?
+        };
+        let unit_term = unit_term.trim();
+        if unit_term.is_empty() {
+            return Ok(Quantity::dimensionless(value));
+        }
+        let factors = unit_term
+            .split('*')
+            .map(|factor| {
+                let (name, exponent) = match factor.split_once('^') {
+                    Some((name, exponent)) => (
+                        name.trim(),
+                        exponent
+                            .trim()
+                            .parse::<i8>()
+                            .map_err(|e| format_exerr!("invalid exponent in '{}', {:?}", s, e))?,
+                    ),
+                    None => (factor.trim(), 1),
+                };
+                let unit =
+                    find_unit(name).ok_or_else(|| format_exerr!("unknown unit '{}'", name))?;
+                Ok((unit, exponent))
+            })
+            .collect::<ExResult<Vec<_>>>()?;
+        if let [(unit, 1)] = factors[..] {
+            // a single unit without exponent is kept for display
+            return Quantity::new(value, unit.name);
+        }
+        let mut si_value = value;
+        let mut dim = Dimension::default();
+        for (unit, exponent) in factors {
+            let factor_dim = unit
+                .dim
+                .powf(exponent as f64)
+                .ok_or_else(|| format_exerr!("exponent too large in '{}'", s))?;
+            si_value *= unit.scale.powi(exponent as i32);
+            dim = dim
+                .combine(factor_dim, |a, b| a.checked_add(b))
+                .ok_or_else(|| format_exerr!("exponent too large in '{}'", s))?;
+        }
+        Ok(Quantity::si(si_value, dim))
+    }
+}
+
+fn add_or_sub(a: Quantity, b: Quantity, op: &'static str, f: fn(f64, f64) -> f64) -> Quantity {
+    match (a, b) {
+        (Quantity::Error(e), _) | (_, Quantity::Error(e)) => Quantity::Error(e),
+        (
+            Quantity::Value {
+                si_value: a,
+                dim: dim_a,
+                unit: unit_a,
+            },
+            Quantity::Value {
+                si_value: b,
+                dim: dim_b,
+                unit: unit_b,
+            },
+        ) => {
+            if dim_a == dim_b {
+                Quantity::Value {
+                    si_value: f(a, b),
+                    dim: dim_a,
+                    unit: if unit_a == unit_b { unit_a } else { None },
+                }
+            } else {
+                Quantity::Error(DimensionError::Incompatible {
+                    op,
+                    left: dim_a,
+                    right: dim_b,
+                })
+            }
+        }
+    }
+}
+
+fn mul_or_div(
+    a: Quantity,
+    b: Quantity,
+    op: &'static str,
+    f: fn(f64, f64) -> f64,
+    g: fn(i8, i8) -> Option<i8>,
+) -> Quantity {
+    match (a, b) {
+        (Quantity::Error(e), _) | (_, Quantity::Error(e)) => Quantity::Error(e),
+        (
+            Quantity::Value {
+                si_value: a,
+                dim: dim_a,
+                ..
+            },
+            Quantity::Value {
+                si_value: b,
+                dim: dim_b,
+                ..
+            },
+        ) => match dim_a.combine(dim_b, g) {
+            Some(dim) => Quantity::si(f(a, b), dim),
+            None => Quantity::Error(DimensionError::ExponentOverflow {
+                op,
+                left: dim_a,
+                right: dim_b,
+            }),
+        },
+    }
+}
+
+fn pow(a: Quantity, b: Quantity) -> Quantity {
+    match (a, b) {
+        (Quantity::Error(e), _) | (_, Quantity::Error(e)) => Quantity::Error(e),
+        (
+            Quantity::Value {
+                si_value: a,
+                dim: dim_a,
+                ..
+            },
+            Quantity::Value {
+                si_value: b,
+                dim: dim_b,
+                ..
+            },
+        ) => {
+            if !dim_b.is_dimensionless() {
+                return Quantity::Error(DimensionError::NotDimensionless {
+                    op: "^",
+                    dim: dim_b,
+                });
+            }
+            match dim_a.powf(b) {
+                Some(dim) => Quantity::si(a.powf(b), dim),
+                None => Quantity::Error(DimensionError::NonIntegerExponent {
+                    dim: dim_a,
+                    exponent: b,
+                }),
+            }
+        }
+    }
+}
+
+fn to(a: Quantity, b: Quantity) -> Quantity {
+    match (a, b) {
+        (Quantity::Error(e), _) | (_, Quantity::Error(e)) => Quantity::Error(e),
+        (
+            Quantity::Value {
+                si_value,
+                dim: dim_a,
+                ..
+            },
+            Quantity::Value {
+                dim: dim_b, unit, ..
+            },
+        ) => {
+            if dim_a == dim_b {
+                Quantity::Value {
+                    si_value,
+                    dim: dim_a,
+                    unit,
+                }
+            } else {
+                Quantity::Error(DimensionError::Incompatible {
+                    op: "to",
+                    left: dim_a,
+                    right: dim_b,
+                })
+            }
+        }
+    }
+}
+
+fn map_dimensionless(a: Quantity, op: &'static str, f: fn(f64) -> f64) -> Quantity {
+    match a {
+        Quantity::Value { si_value, dim, .. } if dim.is_dimensionless() => {
+            Quantity::dimensionless(f(si_value))
+        }
+        Quantity::Value { dim, .. } => {
+            Quantity::Error(DimensionError::NotDimensionless { op, dim })
+        }
+        Quantity::Error(_) => a,
+    }
+}
+
+fn map_value(a: Quantity, f: fn(f64) -> f64) -> Quantity {
+    match a {
+        Quantity::Value {
+            si_value,
+            dim,
+            unit,
+        } => Quantity::Value {
+            si_value: f(si_value),
+            dim,
+            unit,
+        },
+        Quantity::Error(_) => a,
+    }
+}
+
+/// *`feature = "units"`* - Factory of operators for [`Quantity`](Quantity)s that check and propagate
+/// dimensions.
+///
+/// |representation|description|
+/// |--------------|-----------|
+/// |`to`| converts the left operand into the unit of the right one, e.g., `3000 m to km` or `to(3000 m, km)` |
+/// |`^`| power with a dimensionless exponent |
+/// |`*`| product |
+/// |`/`| division |
+/// |`+`| addition of quantities with the same dimension as binary or identity as unary operator|
+/// |`-`| subtraction of quantities with the same dimension as binary or inverting the sign as unary operator |
+/// |`abs`| absolute value |
+/// |`sqrt`| square root, e.g., `sqrt(4 m^2)` is `2 m` |
+/// |`sin`, `cos`, `tan`, `exp`, `ln`, `log10` | functions of dimensionless quantities |
+/// |`PI`| constant π |
+/// |`π`| second representation of constant π |
+///
+/// The supported units are the SI base units `m`, `kg`, `s`, `A`, `K`, `mol`, and `cd`, the multiples
+/// `km`, `cm`, `mm`, `g`, `mg`, `ms`, `min`, and `h`, as well as the derived units `Hz`, `N`, `Pa`, `J`,
+/// `kJ`, `W`, `kW`, `C`, `V`, and `L`. Since unit names are literals, they cannot be used as names of
+/// variables unless the variable names are wrapped in curly brackets such as `{s}`.
+/// ```rust
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// #
+/// use exmex::prelude::*;
+/// use exmex::{DimensionError, FlatExUnits, Quantity};
+/// let expr = FlatExUnits::from_str("(3 m/s * 2 s + x) to(km)")?;
+/// let res = expr.eval(&[Quantity::from_str("1 km")?])?;
+/// assert_eq!(res.to_string(), "1.006 km");
+/// let expr = FlatExUnits::from_str("3 m + 2 s")?;
+/// let err = expr.eval(&[])?.to_result().unwrap_err();
+/// assert!(matches!(err, DimensionError::Incompatible { op: "+", .. }));
+/// #
+/// #     Ok(())
+/// # }
+/// ```
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Debug)]
+pub struct UnitsOpsFactory;
+
+impl MakeOperators<Quantity> for UnitsOpsFactory {
+    /// Returns the default operators.
+    fn make<'a>() -> Vec<Operator<'a, Quantity>> {
+        vec![
+            Operator::make_bin(
+                "to",
+                BinOp {
+                    apply: to,
+                    prio: 0,
+                    is_commutative: false,
+                    is_right_assoc: false,
+                },
+            ),
+            Operator::make_bin(
+                "^",
+                BinOp {
+                    apply: pow,
+                    prio: 5,
+                    is_commutative: false,
+                    is_right_assoc: true,
+                },
+            ),
+            Operator::make_bin(
+                "*",
+                BinOp {
+                    apply: |a, b| mul_or_div(a, b, "*", |a, b| a * b, |a, b| a.checked_add(b)),
+                    prio: 3,
+                    is_commutative: true,
+                    is_right_assoc: false,
+                },
+            ),
+            Operator::make_bin(
+                "/",
+                BinOp {
+                    apply: |a, b| mul_or_div(a, b, "/", |a, b| a / b, |a, b| a.checked_sub(b)),
+                    prio: 4,
+                    is_commutative: false,
+                    is_right_assoc: false,
+                },
+            ),
+            Operator::make_bin_unary(
+                "+",
+                BinOp {
+                    apply: |a, b| add_or_sub(a, b, "+", |a, b| a + b),
+                    prio: 1,
+                    is_commutative: true,
+                    is_right_assoc: false,
+                },
+                |a| a,
+            ),
+            Operator::make_bin_unary(
+                "-",
+                BinOp {
+                    apply: |a, b| add_or_sub(a, b, "-", |a, b| a - b),
+                    prio: 2,
+                    is_commutative: false,
+                    is_right_assoc: false,
+                },
+                |a| map_value(a, |x| -x),
+            ),
+            Operator::make_unary("abs", |a| map_value(a, f64::abs)),
+            Operator::make_unary("sqrt", |a| pow(a, Quantity::dimensionless(0.5))),
+            Operator::make_unary("sin", |a| map_dimensionless(a, "sin", f64::sin)),
+            Operator::make_unary("cos", |a| map_dimensionless(a, "cos", f64::cos)),
+            Operator::make_unary("tan", |a| map_dimensionless(a, "tan", f64::tan)),
+            Operator::make_unary("exp", |a| map_dimensionless(a, "exp", f64::exp)),
+            Operator::make_unary("ln", |a| map_dimensionless(a, "ln", f64::ln)),
+            Operator::make_unary("log10", |a| map_dimensionless(a, "log10", f64::log10)),
+            Operator::make_constant("PI", Quantity::dimensionless(std::f64::consts::PI)),
+            Operator::make_constant("π", Quantity::dimensionless(std::f64::consts::PI)),
+        ]
+    }
+}
+
+fn make_pattern() -> String {
+    let mut names = UNITS.iter().map(|u| u.name).collect::<Vec<_>>();
+    // longer names first such that, e.g., mol is not matched as m
+    names.sort_by_key(|n| std::cmp::Reverse(n.len()));
+    // products of units such as m*s^-1 are products of quantities in expressions
+    let unit = format!(r"({})\b(\^-?[0-9]+)?", names.join("|"));
+    format!(r"^([0-9]+(\.[0-9]+)?(\s*{})?|{})", unit, unit)
+}
+
+literal_matcher_from_pattern!(UnitsMatcher, &make_pattern());
+
+/// *`feature = "units"`* - Alias for [`FlatEx`](FlatEx) with [`Quantity`](Quantity) as data type,
+/// [`UnitsOpsFactory`](UnitsOpsFactory) as operator factory, and [`UnitsMatcher`](UnitsMatcher)
+/// that matches numbers with optional units such as `3 km` or `2 m^2`.
+pub type FlatExUnits = FlatEx<Quantity, UnitsOpsFactory, UnitsMatcher>;
+
+#[test]
+fn test_units_literals() -> ExResult<()> {
+    assert_eq!(UnitsMatcher::is_literal("3 m/s"), Some("3 m"));
+    assert_eq!(UnitsMatcher::is_literal("3.5km*x"), Some("3.5km"));
+    assert_eq!(UnitsMatcher::is_literal("2 m^2+x"), Some("2 m^2"));
+    assert_eq!(UnitsMatcher::is_literal("s^-1"), Some("s^-1"));
+    assert_eq!(UnitsMatcher::is_literal("mol"), Some("mol"));
+    assert_eq!(UnitsMatcher::is_literal("3 * x"), Some("3"));
+    assert_eq!(UnitsMatcher::is_literal("sin(x)"), None);
+    assert_eq!(UnitsMatcher::is_literal("speed"), None);
+    assert_eq!(Quantity::from_str("3 km")?.value().unwrap(), 3.0);
+    let q = Quantity::from_str("9.81 m*s^-2")?;
+    assert_eq!(q.dim().unwrap(), Dimension([1, 0, -2, 0, 0, 0, 0]));
+    assert_eq!(q.to_string(), "9.81 m*s^-2");
+    assert_eq!(Quantity::from_str("2 km^2")?.value().unwrap(), 2e6);
+    assert!(Quantity::from_str("2 parsec").is_err());
+    Ok(())
+}
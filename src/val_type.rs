@@ -0,0 +1,744 @@
+use std::{
+    error::Error,
+    fmt::{self, Debug, Display, Formatter},
+    ops::Range,
+    str::FromStr,
+};
+
+use num::{Float, PrimInt, Signed};
+
+use crate::{
+    data_type::DataType,
+    parse_val,
+    parser::{self, Paren, ParsedToken},
+    ExError, ExResult, FlatExVal, MakeOperators, MatchLiteral, Operator, ParseOptions, Val,
+    ValMatcher, ValOpsFactory,
+};
+
+/// *`feature = "value"`* - Type of a [`Val`](Val) that is used to declare the types of variables
+/// and to report the inferred type of an expression, see [`check_val_types`](check_val_types).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ValType {
+    Int,
+    Float,
+    Bool,
+    Str,
+    Array,
+    /// Type that cannot be determined statically such as the type of an element of an array.
+    /// It is compatible with all other types.
+    Any,
+}
+
+impl Display for ValType {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+fn type_of<I, F>(val: &Val<I, F>) -> ValType
+where
+    I: DataType + PrimInt + Signed,
+    F: DataType + Float,
+{
+    match val {
+        Val::Int(_) => ValType::Int,
+        Val::Float(_) => ValType::Float,
+        Val::Bool(_) => ValType::Bool,
+        Val::Str(_) => ValType::Str,
+        Val::Array(_) => ValType::Array,
+        Val::Error(_) | Val::None => ValType::Any,
+    }
+}
+
+/// *`feature = "value"`* - Error of the static type check of an expression. The span is the
+/// byte range of the offending part of the expression string.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TypeError {
+    msg: String,
+    span: Range<usize>,
+}
+
+impl TypeError {
+    fn new(msg: String, span: Range<usize>) -> Self {
+        TypeError { msg, span }
+    }
+    pub fn msg(&self) -> &str {
+        &self.msg
+    }
+    pub fn span(&self) -> Range<usize> {
+        self.span.clone()
+    }
+}
+
+impl Display for TypeError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{} at {}..{}", self.msg, self.span.start, self.span.end)
+    }
+}
+
+impl Error for TypeError {}
+
+/// Static type of a sub-expression. `x if c` without `else` is only valid as left operand of `else`
+/// and `c ? x` only as left operand of `:`. The representation of the conditional operator is kept
+/// for error messages.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Ty {
+    Known(ValType),
+    IfWithoutElse(ValType, &'static str),
+}
+
+fn else_of(if_repr: &str) -> &'static str {
+    if if_repr == "?" {
+        ":"
+    } else {
+        "else"
+    }
+}
+
+#[derive(Clone)]
+struct Node {
+    ty: Ty,
+    span: Range<usize>,
+}
+
+fn join(t1: ValType, t2: ValType) -> ValType {
+    if t1 == t2 {
+        t1
+    } else {
+        ValType::Any
+    }
+}
+
+fn is_num(t: ValType) -> bool {
+    matches!(t, ValType::Int | ValType::Float | ValType::Any)
+}
+
+fn arith(a: ValType, b: ValType) -> Option<ValType> {
+    use ValType::*;
+    match (a, b) {
+        (Int, Int) => Some(Int),
+        (Float, Int) | (Int, Float) | (Float, Float) => Some(Float),
+        (Any, t) | (t, Any) if is_num(t) => Some(Any),
+        _ => None,
+    }
+}
+
+fn only(t: ValType, a: ValType, b: ValType, res: ValType) -> Option<ValType> {
+    if (a == t || a == ValType::Any) && (b == t || b == ValType::Any) {
+        Some(res)
+    } else {
+        None
+    }
+}
+
+/// Result type of binary operators of [`ValOpsFactory`](ValOpsFactory) or `None` if the
+/// operands cannot be combined.
+fn bin_type(repr: &str, a: ValType, b: ValType) -> Option<ValType> {
+    use ValType::*;
+    let is_broadcast = !matches!(
+        repr,
+        "==" | "!="
+            | "contains"
+            | "starts_with"
+            | "in"
+            | "between"
+            | "substr"
+            | "dot"
+            | "[]"
+            | "??"
+            | "coalesce"
+    );
+    if is_broadcast && (a == Array || b == Array) {
+        return Some(Array);
+    }
+    match repr {
+        "+" if a == Str || b == Str => match (a, b) {
+            (Str, Str) | (Str, Any) | (Any, Str) => Some(Str),
+            _ => None,
+        },
+        "+" | "-" | "*" | "/" => arith(a, b),
+        "^" => match (a, b) {
+            (Int, Int) => Some(Int),
+            (Float, Int) | (Float, Float) => Some(Float),
+            (Any, t) | (t, Any) if is_num(t) => Some(Any),
+            _ => None,
+        },
+        "%" | "|" | "&" | "XOR" | "<<" | ">>" => only(Int, a, b, Int),
+        "&&" | "||" | "xor" => only(Bool, a, b, Bool),
+        "<" | ">" | "<=" | ">=" => {
+            if (is_num(a) && is_num(b)) || only(Str, a, b, Bool).is_some() {
+                Some(Bool)
+            } else {
+                None
+            }
+        }
+        "==" | "!=" => Some(Bool),
+        "??" | "coalesce" => Some(join(a, b)),
+        "dot" => only(Array, a, b, Any),
+        "[]" => match (a, b) {
+            (Str, Int) | (Str, Any) => Some(Str),
+            (Array, Int) | (Array, Any) | (Any, Int) | (Any, Any) => Some(Any),
+            _ => None,
+        },
+        "contains" => match (a, b) {
+            (Array, _) | (Any, _) | (Str, Str) | (Str, Any) => Some(Bool),
+            _ => None,
+        },
+        "in" => bin_type("contains", b, a),
+        "between" => match (a, b) {
+            (t, Array) | (t, Any) if is_num(t) || t == Str => Some(Bool),
+            _ => None,
+        },
+        "starts_with" => only(Str, a, b, Bool),
+        "substr" => match (a, b) {
+            (Str, Int) | (Str, Any) | (Any, Int) | (Any, Any) => Some(Str),
+            _ => None,
+        },
+        _ => Some(Any),
+    }
+}
+
+const FLOAT_FUNCS: [&str; 21] = [
+    "sin", "cos", "tan", "asin", "acos", "atan", "sinh", "cosh", "tanh", "floor", "ceil", "trunc",
+    "fract", "exp", "sqrt", "cbrt", "round", "ln", "log10", "log2", "log",
+];
+
+/// Result type of unary operators of [`ValOpsFactory`](ValOpsFactory) or `None` if the
+/// operator cannot be applied.
+fn unary_type(repr: &str, a: ValType) -> Option<ValType> {
+    use ValType::*;
+    let is_float_func = FLOAT_FUNCS.contains(&repr);
+    match (repr, a) {
+        ("len", Str) | ("len", Array) | ("len", Any) => Some(Int),
+        ("len", _) => None,
+        ("is_none", _) => Some(Bool),
+        ("sum", Array)
+        | ("sum", Any)
+        | ("min", Array)
+        | ("min", Any)
+        | ("max", Array)
+        | ("max", Any) => Some(Any),
+        ("mean", Array) | ("mean", Any) => Some(Float),
+        ("sum", _) | ("min", _) | ("max", _) | ("mean", _) => None,
+        (_, Array) => Some(Array),
+        ("to_int", Int) | ("to_int", Float) | ("to_int", Bool) | ("to_int", Any) => Some(Int),
+        ("to_float", Int) | ("to_float", Float) | ("to_float", Bool) | ("to_float", Any) => {
+            Some(Float)
+        }
+        ("to_int", _) | ("to_float", _) => None,
+        (_, Float) | (_, Any) if is_float_func => Some(Float),
+        _ if is_float_func => None,
+        ("-", t) | ("abs", t) | ("signum", t) => match t {
+            Int | Float | Any => Some(t),
+            _ => None,
+        },
+        ("swap_bytes", t) | ("to_le", t) | ("to_be", t) | ("fact", t) => match t {
+            Int | Any => Some(Int),
+            _ => None,
+        },
+        ("!", t) | ("not", t) => match t {
+            Bool | Any => Some(Bool),
+            _ => None,
+        },
+        ("upper", t) | ("lower", t) => match t {
+            Str | Any => Some(Str),
+            _ => None,
+        },
+        _ => Some(Any),
+    }
+}
+
+/// Result type of postfix operators of [`ValOpsFactory`](ValOpsFactory) or `None` if the
+/// operator cannot be applied.
+fn postfix_type(repr: &str, a: ValType) -> Option<ValType> {
+    use ValType::*;
+    match (repr, a) {
+        (_, Array) => Some(Array),
+        ("!", Int) | ("!", Any) => Some(Int),
+        ("%", Int) | ("%", Float) | ("%", Any) => Some(Float),
+        ("!", _) | ("%", _) => None,
+        _ => Some(Any),
+    }
+}
+
+/// Precedence climbing parser that infers types instead of building an expression.
+struct Checker<'a, 'b, T: DataType> {
+    tokens: &'b [ParsedToken<'a, T>],
+    spans: &'b [Range<usize>],
+    offset: usize,
+    pos: usize,
+    var_types: &'b [(&'b str, ValType)],
+}
+
+impl<'a, 'b, I, F> Checker<'a, 'b, Val<I, F>>
+where
+    I: DataType + PrimInt + Signed,
+    F: DataType + Float,
+    <I as FromStr>::Err: Debug,
+    <F as FromStr>::Err: Debug,
+{
+    fn span(&self, pos: usize) -> Range<usize> {
+        match self.spans.get(pos) {
+            Some(span) => self.offset + span.start..self.offset + span.end,
+            None => {
+                let end = self.offset + self.spans.last().map(|s| s.end).unwrap_or(0);
+                end..end
+            }
+        }
+    }
+
+    fn next(&mut self) -> Option<&'b ParsedToken<'a, Val<I, F>>> {
+        let token = self.tokens.get(self.pos);
+        self.pos += 1;
+        token
+    }
+
+    fn expect_close(&mut self, open_span: &Range<usize>) -> Result<Range<usize>, TypeError> {
+        match self.next() {
+            Some(ParsedToken::Paren(Paren::Close)) => Ok(self.span(self.pos - 1)),
+            _ => Err(TypeError::new(
+                "missing closing parenthesis".to_string(),
+                open_span.clone(),
+            )),
+        }
+    }
+
+    fn known(&self, node: &Node) -> Result<ValType, TypeError> {
+        match node.ty {
+            Ty::Known(t) => Ok(t),
+            Ty::IfWithoutElse(_, if_repr) => Err(TypeError::new(
+                format!("'{}' without '{}'", if_repr, else_of(if_repr)),
+                node.span.clone(),
+            )),
+        }
+    }
+
+    fn apply_unary(
+        &self,
+        op: &Operator<Val<I, F>>,
+        op_span: Range<usize>,
+        operand: Node,
+    ) -> Result<Node, TypeError> {
+        let span = op_span.start..operand.span.end;
+        let t = self.known(&operand)?;
+        match unary_type(op.repr(), t) {
+            Some(t) => Ok(Node {
+                ty: Ty::Known(t),
+                span,
+            }),
+            None => Err(TypeError::new(
+                format!("cannot apply '{}' to {}", op.repr(), t),
+                span,
+            )),
+        }
+    }
+
+    /// Applies all postfix operators that directly follow the current position.
+    fn apply_postfixes(&mut self, mut operand: Node) -> Result<Node, TypeError> {
+        while let Some(ParsedToken::Op(op)) = self.tokens.get(self.pos) {
+            if !parser::is_operator_postfix(op, true, self.tokens.get(self.pos + 1)) {
+                break;
+            }
+            let span = operand.span.start..self.span(self.pos).end;
+            self.pos += 1;
+            let t = self.known(&operand)?;
+            operand = match postfix_type(op.repr(), t) {
+                Some(t) => Node {
+                    ty: Ty::Known(t),
+                    span,
+                },
+                None => {
+                    return Err(TypeError::new(
+                        format!("cannot apply postfix '{}' to {}", op.repr(), t),
+                        span,
+                    ))
+                }
+            };
+        }
+        Ok(operand)
+    }
+
+    fn apply_bin(
+        &self,
+        repr: &str,
+        a: Node,
+        b: Node,
+        span: Range<usize>,
+    ) -> Result<Node, TypeError> {
+        let ty = match (repr, a.ty) {
+            ("else", Ty::IfWithoutElse(t, "if")) | (":", Ty::IfWithoutElse(t, "?")) => {
+                Ty::Known(join(t, self.known(&b)?))
+            }
+            ("else", _) | (":", _) => {
+                let if_repr = if repr == ":" { "?" } else { "if" };
+                return Err(TypeError::new(
+                    format!("'{}' without '{}'", repr, if_repr),
+                    span,
+                ));
+            }
+            ("if", _) | ("?", _) => {
+                let (value, cond, if_repr) = if repr == "?" {
+                    (b, a, "?")
+                } else {
+                    (a, b, "if")
+                };
+                let t = self.known(&value)?;
+                match self.known(&cond)? {
+                    ValType::Bool | ValType::Any => Ty::IfWithoutElse(t, if_repr),
+                    cond_type => {
+                        return Err(TypeError::new(
+                            format!(
+                                "condition of '{}' needs to be Bool, got {}",
+                                if_repr, cond_type
+                            ),
+                            cond.span,
+                        ))
+                    }
+                }
+            }
+            _ => {
+                let (ta, tb) = (self.known(&a)?, self.known(&b)?);
+                match bin_type(repr, ta, tb) {
+                    Some(t) => Ty::Known(t),
+                    None => {
+                        return Err(TypeError::new(
+                            format!("cannot apply '{}' to {} and {}", repr, ta, tb),
+                            span,
+                        ))
+                    }
+                }
+            }
+        };
+        Ok(Node { ty, span })
+    }
+
+    fn atom(&mut self) -> Result<Node, TypeError> {
+        let node = self.primary()?;
+        self.apply_postfixes(node)
+    }
+
+    /// Operand without trailing postfix operators.
+    fn primary(&mut self) -> Result<Node, TypeError> {
+        let pos = self.pos;
+        let span = self.span(pos);
+        match self.next() {
+            Some(ParsedToken::Num(v)) => Ok(Node {
+                ty: Ty::Known(type_of(v)),
+                span,
+            }),
+            Some(ParsedToken::Var(name)) => {
+                if let Some(ParsedToken::Paren(Paren::Open)) = self.tokens.get(self.pos) {
+                    return Err(TypeError::new(format!("unknown function '{}'", name), span));
+                }
+                match self.var_types.iter().rev().find(|(n, _)| n == name) {
+                    Some((_, t)) => Ok(Node {
+                        ty: Ty::Known(*t),
+                        span,
+                    }),
+                    None => Err(TypeError::new(
+                        format!("type of variable '{}' is not declared", name),
+                        span,
+                    )),
+                }
+            }
+            Some(ParsedToken::Paren(Paren::Open)) => {
+                let node = self.expr(i64::MIN)?;
+                let close_span = self.expect_close(&span)?;
+                Ok(Node {
+                    ty: node.ty,
+                    span: span.start..close_span.end,
+                })
+            }
+            Some(ParsedToken::Op(op)) => {
+                let is_call = matches!(
+                    self.tokens.get(self.pos),
+                    Some(ParsedToken::Paren(Paren::Open))
+                );
+                if op.has_bin() && is_call {
+                    let open_span = self.span(self.pos);
+                    self.pos += 1;
+                    let first = self.expr(i64::MIN)?;
+                    if let Some(ParsedToken::Comma) = self.tokens.get(self.pos) {
+                        self.pos += 1;
+                        let second = self.expr(i64::MIN)?;
+                        let is_ternary = matches!(op.repr(), "if" | "between");
+                        if let (Some(ParsedToken::Comma), true) =
+                            (self.tokens.get(self.pos), is_ternary)
+                        {
+                            self.pos += 1;
+                            let third = self.expr(i64::MIN)?;
+                            let close_span = self.expect_close(&open_span)?;
+                            let span = span.start..close_span.end;
+                            return if op.repr() == "if" {
+                                // if(c, a, b) is equivalent to a if c else b
+                                let if_node = self.apply_bin("if", second, first, span.clone())?;
+                                self.apply_bin("else", if_node, third, span)
+                            } else {
+                                // between(x, lo, hi) is equivalent to x >= lo && x <= hi
+                                let lower = self.apply_bin(">=", first.clone(), second, span.clone())?;
+                                let upper = self.apply_bin("<=", first, third, span.clone())?;
+                                self.apply_bin("&&", lower, upper, span)
+                            };
+                        }
+                        let close_span = self.expect_close(&open_span)?;
+                        return self.apply_bin(
+                            op.repr(),
+                            first,
+                            second,
+                            span.start..close_span.end,
+                        );
+                    } else if op.has_unary() {
+                        let close_span = self.expect_close(&open_span)?;
+                        let operand = Node {
+                            ty: first.ty,
+                            span: open_span.start..close_span.end,
+                        };
+                        return self.apply_unary(op, span, operand);
+                    } else {
+                        return Err(TypeError::new(
+                            format!("binary operator '{}' expects 2 arguments", op.repr()),
+                            span,
+                        ));
+                    }
+                }
+                if op.has_unary() {
+                    let operand = self.atom()?;
+                    self.apply_unary(op, span, operand)
+                } else {
+                    Err(TypeError::new(
+                        format!("binary operator '{}' misses its left operand", op.repr()),
+                        span,
+                    ))
+                }
+            }
+            Some(_) => Err(TypeError::new("unexpected token".to_string(), span)),
+            None => Err(TypeError::new(
+                "unexpected end of expression".to_string(),
+                span,
+            )),
+        }
+    }
+
+    fn expr(&mut self, min_prio: i64) -> Result<Node, TypeError> {
+        let mut lhs = self.atom()?;
+        while let Some(ParsedToken::Op(op)) = self.tokens.get(self.pos) {
+            let bin_op = match op.bin() {
+                Ok(bin_op) if bin_op.prio >= min_prio => bin_op,
+                Ok(_) => break,
+                Err(_) => {
+                    return Err(TypeError::new(
+                        format!("unary operator '{}' cannot follow an operand", op.repr()),
+                        self.span(self.pos),
+                    ))
+                }
+            };
+            self.pos += 1;
+            if op.repr() == "in" && self.is_tuple() {
+                lhs = self.in_tuple(lhs)?;
+                continue;
+            }
+            let rhs_min_prio = if bin_op.is_right_assoc {
+                bin_op.prio
+            } else {
+                bin_op.prio + 1
+            };
+            if op.is_index() {
+                // postfix operators after an index apply to the element, e.g., v[1]!
+                let rhs = self.primary()?;
+                let span = lhs.span.start..rhs.span.end;
+                lhs = self.apply_bin(op.repr(), lhs, rhs, span)?;
+                lhs = self.apply_postfixes(lhs)?;
+                continue;
+            }
+            let rhs = self.expr(rhs_min_prio)?;
+            let span = lhs.span.start..rhs.span.end;
+            lhs = self.apply_bin(op.repr(), lhs, rhs, span)?;
+        }
+        Ok(lhs)
+    }
+
+    /// Whether the next tokens are a tuple such as `(1, 2, 5)` with at least 2 elements.
+    fn is_tuple(&self) -> bool {
+        if !matches!(self.tokens.get(self.pos), Some(ParsedToken::Paren(Paren::Open))) {
+            return false;
+        }
+        let mut depth = 0;
+        for token in &self.tokens[self.pos..] {
+            match token {
+                ParsedToken::Paren(Paren::Open) => depth += 1,
+                ParsedToken::Paren(Paren::Close) if depth == 1 => return false,
+                ParsedToken::Paren(Paren::Close) => depth -= 1,
+                ParsedToken::Comma if depth == 1 => return true,
+                _ => (),
+            }
+        }
+        false
+    }
+
+    /// Checks `x in (a, b, c)` which is equivalent to `x == a || x == b || x == c`.
+    fn in_tuple(&mut self, lhs: Node) -> Result<Node, TypeError> {
+        let open_span = self.span(self.pos);
+        self.pos += 1;
+        loop {
+            let elt = self.expr(i64::MIN)?;
+            self.apply_bin("==", lhs.clone(), elt, lhs.span.clone())?;
+            match self.tokens.get(self.pos) {
+                Some(ParsedToken::Comma) => self.pos += 1,
+                _ => break,
+            }
+        }
+        let close_span = self.expect_close(&open_span)?;
+        Ok(Node {
+            ty: Ty::Known(ValType::Bool),
+            span: lhs.span.start..close_span.end,
+        })
+    }
+}
+
+fn check_expression<'a, I, F>(
+    text: &'a str,
+    offset: usize,
+    ops: &[Operator<'a, Val<I, F>>],
+    var_types: &[(&str, ValType)],
+) -> Result<ValType, TypeError>
+where
+    I: DataType + PrimInt + Signed,
+    F: DataType + Float,
+    <I as FromStr>::Err: Debug,
+    <F as FromStr>::Err: Debug,
+{
+    let (tokens, spans) =
+        parser::tokenize_with_spans(
+            text,
+            ops,
+            ValMatcher::is_literal,
+            parser::is_var_name_text,
+            &ParseOptions::default(),
+        )
+        .map_err(|e| TypeError::new(e.msg().to_string(), offset..offset + text.len()))?;
+    let mut checker = Checker {
+        tokens: &tokens,
+        spans: &spans,
+        offset,
+        pos: 0,
+        var_types,
+    };
+    let node = checker.expr(i64::MIN)?;
+    if checker.pos < tokens.len() {
+        return Err(TypeError::new(
+            "unexpected token".to_string(),
+            checker.span(checker.pos),
+        ));
+    }
+    checker.known(&node)
+}
+
+/// *`feature = "value"`* - Checks the types of an expression that is parsed into a
+/// [`FlatExVal`](FlatExVal) without evaluating it. The types of all variables need to be
+/// declared. The inferred type of the result is returned. Type mismatches that otherwise
+/// only surface as [`Val::Error`](Val::Error) during evaluation for particular inputs are
+/// reported with the byte range of the offending sub-expression, e.g., arithmetic with booleans,
+/// functions such as `fact` applied to floats, or `if` without `else`. Element types of arrays
+/// are not known statically and result in [`ValType::Any`](ValType::Any). Local variables
+/// are supported, user-defined functions are not. Priorities and associativities of the
+/// operators are taken from [`ValOpsFactory`](ValOpsFactory). Since declared variables are never
+/// [`Val::None`](Val::None), the inferred type holds for [`NoneIsError`](crate::NoneIsError) and
+/// [`NoneIsNull`](crate::NoneIsNull) alike.
+/// ```rust
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// #
+/// use exmex::{check_val_types, ValType};
+/// let var_types = [("x", ValType::Float), ("flag", ValType::Bool)];
+/// let t = check_val_types::<i32, f64>("x * 2 if flag else 0.5", &var_types)?;
+/// assert_eq!(t, ValType::Float);
+/// let err = check_val_types::<i32, f64>("1 + (x + flag)", &var_types).unwrap_err();
+/// assert_eq!(err.msg(), "cannot apply '+' to Float and Bool");
+/// assert_eq!(err.span(), 5..13);
+/// #
+/// #     Ok(())
+/// # }
+/// ```
+pub fn check_val_types<I, F>(
+    text: &str,
+    var_types: &[(&str, ValType)],
+) -> Result<ValType, TypeError>
+where
+    I: DataType + PrimInt + Signed,
+    F: DataType + Float,
+    <I as FromStr>::Err: Debug,
+    <F as FromStr>::Err: Debug,
+{
+    let ops = ValOpsFactory::<I, F>::make();
+    let offset_of = |sub: &str| sub.as_ptr() as usize - text.as_ptr() as usize;
+    let mut var_types = var_types.to_vec();
+    let statements = parser::split_statements(text);
+    let n_statements = statements.len();
+    for (i, statement) in statements.into_iter().enumerate() {
+        let span = offset_of(statement)..offset_of(statement) + statement.len();
+        if parser::split_definition(statement, parser::is_var_name_text).is_some() {
+            return Err(TypeError::new(
+                "the type check does not support function definitions".to_string(),
+                span,
+            ));
+        }
+        match (
+            parser::split_assignment(statement, parser::is_var_name_text),
+            i + 1 == n_statements,
+        ) {
+            (Some((name, rhs)), false) => {
+                let t = check_expression(rhs, offset_of(rhs), &ops, &var_types)?;
+                var_types.push((name, t));
+            }
+            (None, true) => {
+                return check_expression(statement, span.start, &ops, &var_types);
+            }
+            (Some(_), true) => {
+                return Err(TypeError::new(
+                    "the last statement must be an expression and not an assignment".to_string(),
+                    span,
+                ))
+            }
+            (None, false) => {
+                return Err(TypeError::new(
+                    "all statements but the last one need to be assignments".to_string(),
+                    span,
+                ))
+            }
+        }
+    }
+    unreachable!("the last statement always returns")
+}
+
+/// *`feature = "value"`* - Parses a string into an expression of type [`FlatExVal`](FlatExVal)
+/// like [`parse_val`](parse_val) and additionally checks its types with
+/// [`check_val_types`](check_val_types). Returns the expression and the inferred type of its result.
+/// ```rust
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// #
+/// use exmex::{parse_val_typed, Express, Val, ValType};
+/// let (expr, t) = parse_val_typed::<i32, f64>("fact(n) / 2", &[("n", ValType::Int)])?;
+/// assert_eq!(t, ValType::Int);
+/// assert_eq!(expr.eval(&[Val::Int(4)])?.to_int()?, 12);
+/// let err = parse_val_typed::<i32, f64>("fact(n) / 2", &[("n", ValType::Float)]).unwrap_err();
+/// assert_eq!(err.msg(), "cannot apply 'fact' to Float at 0..7");
+/// #
+/// #     Ok(())
+/// # }
+/// ```
+pub fn parse_val_typed<I, F>(
+    text: &str,
+    var_types: &[(&str, ValType)],
+) -> ExResult<(FlatExVal<I, F>, ValType)>
+where
+    I: DataType + PrimInt + Signed,
+    F: DataType + Float,
+    <I as FromStr>::Err: Debug,
+    <F as FromStr>::Err: Debug,
+{
+    let expr = parse_val::<I, F>(text)?;
+    let val_type =
+        check_val_types::<I, F>(text, var_types).map_err(|e| ExError::new(&e.to_string()))?;
+    Ok((expr, val_type))
+}
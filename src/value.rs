@@ -4,7 +4,7 @@ use num::{Float, PrimInt, Signed};
 
 use crate::{
     data_type::DataType, expression::MatchLiteral, format_exerr, literal_matcher_from_pattern,
-    BinOp, ExError, ExResult, FlatEx, MakeOperators, Operator,
+    BinOp, ExError, ExResult, FlatEx, MakeOperators, Operand, Operator, Selection, ShortCircuit,
 };
 
 macro_rules! to_type {
@@ -23,7 +23,7 @@ macro_rules! to_type {
 }
 
 /// *`feature = "value"`* -
-/// The value type [`Val`](Val) can contain an integer, float, bool, none, or error.
+/// The value type [`Val`](Val) can contain an integer, float, bool, string, array, none, or error.
 /// To use the value type, there are the is a parse function [`parse_val`](`parse_val`).
 /// In the following example, the ternary Python-style `a if condition else b` is used.
 /// This is equivalent to `if condition {a} else {b}` in Rust or `condition ? a : b` in C.
@@ -89,9 +89,17 @@ where
     I: DataType + PrimInt + Signed,
     F: DataType + Float,
 {
+    /// Integers are written in decimal, hexadecimal, octal, or binary notation such as `255`,
+    /// `0xFF`, `0o377`, or `0b1111_1111`. Digits can be separated by underscores.
     Int(I),
+    /// Floats are written with a decimal point or an exponent such as `2.5`, `1e-3`, or
+    /// `6.022E23`.
     Float(F),
     Bool(bool),
+    /// Strings are written in double quotes such as `"admin"`.
+    Str(String),
+    /// Arrays are written in brackets such as `[1, 2.5, -3]`.
+    Array(Vec<Val<I, F>>),
     /// Since the trait `Try` is experimental, we keep track of an error in an additional variant.
     Error(ExError),
     /// Sometimes, `Val` does not contain a value
@@ -106,12 +114,37 @@ where
     to_type!(to_int, I, Int);
     to_type!(to_float, F, Float);
     to_type!(to_bool, bool, Bool);
+    to_type!(to_str, String, Str);
+    to_type!(to_array, Vec<Val<I, F>>, Array);
 }
 
 fn map_parse_err<E: Debug>(e: E) -> ExError {
     format_exerr!("{:?}", e)
 }
 
+/// Splits the content of an array literal at commas that are neither nested in brackets
+/// nor in quotes.
+fn split_array_literal(s: &str) -> Vec<&str> {
+    let mut elts = vec![];
+    let mut depth = 0;
+    let mut is_in_quotes = false;
+    let mut start = 0;
+    for (i, c) in s.char_indices() {
+        match c {
+            '"' => is_in_quotes = !is_in_quotes,
+            '[' if !is_in_quotes => depth += 1,
+            ']' if !is_in_quotes => depth -= 1,
+            ',' if !is_in_quotes && depth == 0 => {
+                elts.push(&s[start..i]);
+                start = i + 1;
+            }
+            _ => (),
+        }
+    }
+    elts.push(&s[start..]);
+    elts
+}
+
 impl<I, F> FromStr for Val<I, F>
 where
     I: DataType + PrimInt + Signed,
@@ -122,10 +155,41 @@ where
     type Err = ExError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let res = Ok(if s.contains('.') {
-            Val::Float(s.parse::<F>().map_err(map_parse_err)?)
+        if let Some(inner) = s.strip_prefix('"').and_then(|s| s.strip_suffix('"')) {
+            return Ok(Val::Str(inner.to_string()));
+        }
+        if let Some(inner) = s.strip_prefix('[').and_then(|s| s.strip_suffix(']')) {
+            return if inner.trim().is_empty() {
+                Ok(Val::Array(vec![]))
+            } else {
+                split_array_literal(inner)
+                    .iter()
+                    .map(|elt| elt.trim().parse::<Val<I, F>>())
+                    .collect::<ExResult<Vec<_>>>()
+                    .map(Val::Array)
+            };
+        }
+        let s_wo_seps = s.replace('_', "");
+        let s = s_wo_seps.as_str();
+        let (sign, unsigned) = match s.strip_prefix('-') {
+            Some(unsigned) => ("-", unsigned),
+            None => ("", s),
+        };
+        let radix = match unsigned.get(0..2) {
+            Some("0x") | Some("0X") => Some(16),
+            Some("0o") | Some("0O") => Some(8),
+            Some("0b") | Some("0B") => Some(2),
+            _ => None,
+        };
+        let res = Ok(if let Some(radix) = radix {
+            let digits = format!("{}{}", sign, &unsigned[2..]);
+            Val::Int(I::from_str_radix(&digits, radix).map_err(|_| {
+                format_exerr!("{} does not fit into an int with radix {}", digits, radix)
+            })?)
         } else if s == "false" || s == "true" {
             Val::Bool(s.parse::<bool>().map_err(map_parse_err)?)
+        } else if s.contains(['.', 'e', 'E']) {
+            Val::Float(s.parse::<F>().map_err(map_parse_err)?)
         } else {
             Val::Int(s.parse::<I>().map_err(map_parse_err)?)
         });
@@ -148,6 +212,8 @@ where
             (Val::Float(x), Val::Float(y)) => x == y,
             (Val::Int(x), Val::Int(y)) => x == y,
             (Val::Bool(x), Val::Bool(y)) => x == y,
+            (Val::Str(x), Val::Str(y)) => x == y,
+            (Val::Array(x), Val::Array(y)) => x == y,
             (Val::Float(x), Val::Int(y)) => *x == F::from(*y).unwrap(),
             (Val::Int(x), Val::Float(y)) => F::from(*x).unwrap() == *y,
             _ => false,
@@ -166,6 +232,7 @@ where
         match (self, other) {
             (Val::Float(x), Val::Float(y)) => x.partial_cmp(y),
             (Val::Int(x), Val::Int(y)) => x.partial_cmp(y),
+            (Val::Str(x), Val::Str(y)) => x.partial_cmp(y),
             (Val::Float(x), Val::Int(y)) => x.partial_cmp(&F::from(*y).unwrap()),
             (Val::Int(x), Val::Float(y)) => F::from(*x).unwrap().partial_cmp(y),
             _ => None,
@@ -173,6 +240,42 @@ where
     }
 }
 
+type BinFn<I, F> = fn(Val<I, F>, Val<I, F>) -> Val<I, F>;
+
+/// Applies a binary operator element-wise if at least one of the operands is an array.
+/// Scalars are combined with each element of an array.
+fn broadcast<I, F>(a: Val<I, F>, b: Val<I, F>, f: BinFn<I, F>) -> Val<I, F>
+where
+    I: DataType + PrimInt + Signed,
+    F: DataType + Float,
+{
+    match (a, b) {
+        (Val::Array(x), Val::Array(y)) => {
+            if x.len() == y.len() {
+                Val::Array(
+                    x.into_iter()
+                        .zip(y)
+                        .map(|(a, b)| broadcast(a, b, f))
+                        .collect(),
+                )
+            } else {
+                Val::Error(format_exerr!(
+                    "cannot broadcast arrays of lengths {} and {}",
+                    x.len(),
+                    y.len()
+                ))
+            }
+        }
+        (Val::Array(x), b) => {
+            Val::Array(x.into_iter().map(|a| broadcast(a, b.clone(), f)).collect())
+        }
+        (a, Val::Array(y)) => {
+            Val::Array(y.into_iter().map(|b| broadcast(a.clone(), b, f)).collect())
+        }
+        (a, b) => f(a, b),
+    }
+}
+
 fn pow<I, F>(a: Val<I, F>, b: Val<I, F>) -> Val<I, F>
 where
     I: DataType + PrimInt + Signed,
@@ -274,6 +377,88 @@ single_type_arith!(left_shift, Int, |a: I, b: I| -> Val<I, F> {
 
 single_type_arith!(or, Bool, |a, b| Val::Bool(a || b));
 single_type_arith!(and, Bool, |a, b| Val::Bool(a && b));
+single_type_arith!(xor, Bool, |a, b| Val::Bool(a ^ b));
+
+fn contains<I, F>(a: Val<I, F>, b: Val<I, F>) -> Val<I, F>
+where
+    I: DataType + PrimInt + Signed,
+    F: DataType + Float,
+    <I as FromStr>::Err: Debug,
+    <F as FromStr>::Err: Debug,
+{
+    match (a, b) {
+        (Val::Str(x), Val::Str(y)) => Val::Bool(x.contains(y.as_str())),
+        (Val::Array(x), y) => Val::Bool(x.contains(&y)),
+        (a, b) => Val::Error(format_exerr!("cannot check if {:?} contains {:?}", a, b)),
+    }
+}
+single_type_arith!(starts_with, Str, |a: String, b: String| Val::Bool(
+    a.starts_with(b.as_str())
+));
+
+/// Returns whether `x` is between the first and the second element of the array `bounds`,
+/// both inclusive.
+fn between<I, F>(x: Val<I, F>, bounds: Val<I, F>) -> Val<I, F>
+where
+    I: DataType + PrimInt + Signed,
+    F: DataType + Float,
+    <I as FromStr>::Err: Debug,
+    <F as FromStr>::Err: Debug,
+{
+    match bounds {
+        Val::Array(bounds) if bounds.len() == 2 => Val::Bool(bounds[0] <= x && x <= bounds[1]),
+        _ => Val::Error(format_exerr!(
+            "between expects an array of 2 bounds, got {:?}",
+            bounds
+        )),
+    }
+}
+
+fn add_or_concat<I, F>(a: Val<I, F>, b: Val<I, F>) -> Val<I, F>
+where
+    I: DataType + PrimInt + Signed,
+    F: DataType + Float,
+{
+    match (a, b) {
+        (Val::Str(x), Val::Str(y)) => Val::Str(x + &y),
+        (a, b) => add(a, b),
+    }
+}
+
+/// Returns the characters of a string starting at the given index. Negative indices
+/// count from the end.
+fn substr<I, F>(s: Val<I, F>, start: Val<I, F>) -> Val<I, F>
+where
+    I: DataType + PrimInt + Signed,
+    F: DataType + Float,
+{
+    match (s, start) {
+        (Val::Str(s), Val::Int(start)) => {
+            let n_chars = s.chars().count();
+            let start_idx = if start < I::zero() {
+                start
+                    .abs()
+                    .to_usize()
+                    .and_then(|offset| n_chars.checked_sub(offset))
+            } else {
+                start.to_usize().filter(|idx| *idx <= n_chars)
+            };
+            match start_idx {
+                Some(idx) => Val::Str(s.chars().skip(idx).collect()),
+                None => Val::Error(format_exerr!(
+                    "start index {:?} is out of bounds for '{}'",
+                    start,
+                    s
+                )),
+            }
+        }
+        (s, start) => Val::Error(format_exerr!(
+            "substr expects a string and an int, got {:?} and {:?}",
+            s,
+            start
+        )),
+    }
+}
 
 macro_rules! unary_match_name {
     ($name:ident, $scalar:ident, $(($unused_ops:expr, $variants:ident)),+) => {
@@ -300,7 +485,10 @@ macro_rules! unary_match {
             I: DataType + PrimInt + Signed,
             F: DataType + Float,
         {
-            $matcher!($name, val, $(($ops, $variants)),+)
+            match val {
+                Val::Array(a) => Val::Array(a.into_iter().map($name).collect()),
+                val => $matcher!($name, val, $(($ops, $variants)),+)
+            }
         }
     };
 }
@@ -353,13 +541,8 @@ unary_op!(
                 Some(x) => x,
                 None => return Val::Error(format_exerr!("cannot compute factorial of {:?}", a)),
             };
-            let res =
-                (1usize..(a_usize_unpacked + 1usize))
-                    .map(I::from)
-                    .fold(Some(I::one()), |a, b| match (a, b) {
-                        (Some(a_), Some(b_)) => Some(a_ * b_),
-                        _ => None,
-                    });
+            let res = (1usize..(a_usize_unpacked + 1usize))
+                .try_fold(I::one(), |a, b| a.checked_mul(&I::from(b)?));
             match res {
                 Some(i) => Val::Int(i),
                 None => Val::Error(format_exerr!("cannot compute factorial of {:?}", a)),
@@ -369,6 +552,39 @@ unary_op!(
     )
 );
 
+fn len<I, F>(val: Val<I, F>) -> Val<I, F>
+where
+    I: DataType + PrimInt + Signed,
+    F: DataType + Float,
+{
+    let n = match &val {
+        Val::Str(s) => s.chars().count(),
+        Val::Array(a) => a.len(),
+        _ => return Val::Error(format_exerr!("cannot compute length of {:?}", val)),
+    };
+    match I::from(n) {
+        Some(n) => Val::Int(n),
+        None => Val::Error(format_exerr!(
+            "length of {:?} does not fit into an int",
+            val
+        )),
+    }
+}
+unary_op!(not, (|a: bool| Val::Bool(!a), Bool));
+unary_op!(
+    percent,
+    (
+        |a: I| match F::from(a) {
+            Some(a) => Val::Float(a / F::from(100).unwrap()),
+            None => Val::Error(format_exerr!("cannot convert {:?} to float", a)),
+        },
+        Int
+    ),
+    (|a: F| Val::Float(a / F::from(100).unwrap()), Float)
+);
+unary_op!(upper, (|s: String| Val::Str(s.to_uppercase()), Str));
+unary_op!(lower, (|s: String| Val::Str(s.to_lowercase()), Str));
+
 unary_op!(
     minus,
     (|a: I| Val::Int(-a), Int),
@@ -385,6 +601,7 @@ macro_rules! cast {
             <F as FromStr>::Err: Debug,
         {
             match v {
+                Val::Array(a) => Val::Array(a.into_iter().map($name).collect()),
                 Val::$variant(x) => Val::$variant(x),
                 Val::$other_variant(x) => Val::$variant($T::from(x).unwrap()),
                 Val::Bool(x) => Val::$variant(if x { $T::one() } else { $T::zero() }),
@@ -397,41 +614,455 @@ macro_rules! cast {
 cast!(cast_to_float, Float, Int, F);
 cast!(cast_to_int, Int, Float, I);
 
+fn to_array_or_err<I, F>(val: Val<I, F>, name: &str) -> ExResult<Vec<Val<I, F>>>
+where
+    I: DataType + PrimInt + Signed,
+    F: DataType + Float,
+{
+    match val {
+        Val::Array(a) => Ok(a),
+        Val::Error(e) => Err(e),
+        _ => Err(format_exerr!("{} expects an array, got {:?}", name, val)),
+    }
+}
+
+fn sum<I, F>(val: Val<I, F>) -> Val<I, F>
+where
+    I: DataType + PrimInt + Signed,
+    F: DataType + Float,
+{
+    match to_array_or_err(val, "sum") {
+        Ok(a) => a.into_iter().fold(Val::Int(I::zero()), add),
+        Err(e) => Val::Error(e),
+    }
+}
+
+fn mean<I, F>(val: Val<I, F>) -> Val<I, F>
+where
+    I: DataType + PrimInt + Signed,
+    F: DataType + Float,
+    <I as FromStr>::Err: Debug,
+    <F as FromStr>::Err: Debug,
+{
+    match to_array_or_err(val, "mean") {
+        Ok(a) if a.is_empty() => Val::Error(ExError::new("cannot compute mean of empty array")),
+        Ok(a) => {
+            let n = F::from(a.len()).unwrap();
+            div(cast_to_float(sum(Val::Array(a))), Val::Float(n))
+        }
+        Err(e) => Val::Error(e),
+    }
+}
+
+macro_rules! extremum {
+    ($name:ident, $ordering:expr) => {
+        fn $name<I, F>(val: Val<I, F>) -> Val<I, F>
+        where
+            I: DataType + PrimInt + Signed,
+            F: DataType + Float,
+            <I as FromStr>::Err: Debug,
+            <F as FromStr>::Err: Debug,
+        {
+            let mut elts = match to_array_or_err(val, stringify!($name)) {
+                Ok(a) => a.into_iter(),
+                Err(e) => return Val::Error(e),
+            };
+            let first = match elts.next() {
+                Some(first) => first,
+                None => {
+                    return Val::Error(format_exerr!(
+                        "cannot compute {} of empty array",
+                        stringify!($name)
+                    ))
+                }
+            };
+            elts.try_fold(first, |res, elt| match elt.partial_cmp(&res) {
+                Some(ordering) if ordering == $ordering => Ok(elt),
+                Some(_) => Ok(res),
+                None => Err(format_exerr!("cannot compare {:?} and {:?}", res, elt)),
+            })
+            .unwrap_or_else(Val::Error)
+        }
+    };
+}
+
+extremum!(min, Ordering::Less);
+extremum!(max, Ordering::Greater);
+
+fn dot<I, F>(a: Val<I, F>, b: Val<I, F>) -> Val<I, F>
+where
+    I: DataType + PrimInt + Signed,
+    F: DataType + Float,
+{
+    match (a, b) {
+        (Val::Array(x), Val::Array(y)) if x.len() == y.len() => {
+            sum(broadcast(Val::Array(x), Val::Array(y), mul))
+        }
+        (a, b) => Val::Error(format_exerr!(
+            "dot expects two arrays of the same length, got {:?} and {:?}",
+            a,
+            b
+        )),
+    }
+}
+
+/// Returns an element of an array or a character of a string. Negative indices count
+/// from the end.
+fn index<I, F>(a: Val<I, F>, idx: Val<I, F>) -> Val<I, F>
+where
+    I: DataType + PrimInt + Signed,
+    F: DataType + Float,
+{
+    let n = match &a {
+        Val::Array(x) => x.len(),
+        Val::Str(s) => s.chars().count(),
+        _ => return Val::Error(format_exerr!("cannot index {:?}", a)),
+    };
+    let i = match idx {
+        Val::Int(i) if i < I::zero() => i.abs().to_usize().and_then(|offset| n.checked_sub(offset)),
+        Val::Int(i) => i.to_usize().filter(|i| *i < n),
+        _ => return Val::Error(format_exerr!("index needs to be an int, got {:?}", idx)),
+    };
+    match (a, i) {
+        (Val::Array(mut x), Some(i)) => x.swap_remove(i),
+        (Val::Str(s), Some(i)) => Val::Str(s.chars().skip(i).take(1).collect()),
+        (a, _) => Val::Error(format_exerr!(
+            "index {:?} is out of bounds for {:?}",
+            idx,
+            a
+        )),
+    }
+}
+
+/// Returns the first operand if the condition is true and `Val::None` if it is false.
+fn select_if<I, F>(v: Val<I, F>, cond: Val<I, F>) -> Val<I, F>
+where
+    I: DataType + PrimInt + Signed,
+    F: DataType + Float,
+{
+    match cond.to_bool() {
+        Ok(true) => v,
+        Ok(false) => Val::None,
+        Err(e) => Val::Error(e),
+    }
+}
+
+/// Decides the result of `if` without the operand that is returned if the condition is true.
+fn short_circuit_if<I, F>(cond: &Val<I, F>) -> Option<Val<I, F>>
+where
+    I: DataType + PrimInt + Signed,
+    F: DataType + Float,
+{
+    match cond {
+        Val::Bool(true) => None,
+        Val::Bool(false) => Some(Val::None),
+        _ => Some(select_if(Val::None, cond.clone())),
+    }
+}
+
+fn select_else<I, F>(res_of_if: Val<I, F>, v: Val<I, F>) -> Val<I, F>
+where
+    I: DataType + PrimInt + Signed,
+    F: DataType + Float,
+{
+    match res_of_if {
+        Val::None => v,
+        _ => res_of_if,
+    }
+}
+
+fn coalesce<I, F>(a: Val<I, F>, b: Val<I, F>) -> Val<I, F>
+where
+    I: DataType + PrimInt + Signed,
+    F: DataType + Float,
+{
+    match a {
+        Val::None => b,
+        _ => a,
+    }
+}
+
+fn short_circuit_coalesce<I, F>(a: &Val<I, F>) -> Option<Val<I, F>>
+where
+    I: DataType + PrimInt + Signed,
+    F: DataType + Float,
+{
+    match a {
+        Val::None => None,
+        _ => Some(a.clone()),
+    }
+}
+
+/// *`feature = "value"`* - Semantics of `Val::None` operands of the operators of a
+/// [`ValOpsFactory`](ValOpsFactory). Apart from `if`, `else`, `?`, `:`, `??`, `coalesce`, and
+/// `is_none`, all operators are applied via the methods of this trait.
+pub trait NoneMode: Clone + Debug {
+    /// Applies the binary operator `f` to `a` and `b`.
+    fn bin<I, F>(a: Val<I, F>, b: Val<I, F>, f: BinFn<I, F>) -> Val<I, F>
+    where
+        I: DataType + PrimInt + Signed,
+        F: DataType + Float;
+    /// Applies the boolean operator `f` to `a` and `b`. The result is `dominant` if one of the
+    /// operands is `dominant`, i.e., `false` for `&&` and `true` for `||`.
+    fn logic<I, F>(a: Val<I, F>, b: Val<I, F>, dominant: bool, f: BinFn<I, F>) -> Val<I, F>
+    where
+        I: DataType + PrimInt + Signed,
+        F: DataType + Float;
+    /// Applies the unary operator `f` to `a`.
+    fn unary<I, F>(a: Val<I, F>, f: fn(Val<I, F>) -> Val<I, F>) -> Val<I, F>
+    where
+        I: DataType + PrimInt + Signed,
+        F: DataType + Float;
+    /// Maps the condition of `if` and `?` to the value that is checked.
+    fn cond<I, F>(c: Val<I, F>) -> Val<I, F>
+    where
+        I: DataType + PrimInt + Signed,
+        F: DataType + Float;
+}
+
+/// `Val::None` is treated like any other value and operators result in `Val::Error` for
+/// operands they cannot handle, e.g., `1 + x` is an error if `x` is `Val::None`. Comparisons
+/// with `Val::None` are false.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Debug)]
+pub struct NoneIsError;
+impl NoneMode for NoneIsError {
+    fn bin<I, F>(a: Val<I, F>, b: Val<I, F>, f: BinFn<I, F>) -> Val<I, F>
+    where
+        I: DataType + PrimInt + Signed,
+        F: DataType + Float,
+    {
+        f(a, b)
+    }
+    fn logic<I, F>(a: Val<I, F>, b: Val<I, F>, _dominant: bool, f: BinFn<I, F>) -> Val<I, F>
+    where
+        I: DataType + PrimInt + Signed,
+        F: DataType + Float,
+    {
+        f(a, b)
+    }
+    fn unary<I, F>(a: Val<I, F>, f: fn(Val<I, F>) -> Val<I, F>) -> Val<I, F>
+    where
+        I: DataType + PrimInt + Signed,
+        F: DataType + Float,
+    {
+        f(a)
+    }
+    fn cond<I, F>(c: Val<I, F>) -> Val<I, F>
+    where
+        I: DataType + PrimInt + Signed,
+        F: DataType + Float,
+    {
+        c
+    }
+}
+
+/// `Val::None` is a missing value with SQL-like three-valued semantics. Arithmetic with
+/// `Val::None` and comparisons with `Val::None` result in `Val::None`, e.g., `1 + x` and
+/// `x == x` are `Val::None` if `x` is `Val::None`. Boolean operators only result in `Val::None`
+/// if the other operand does not decide the result, e.g., `x && false` is `false`. Conditions
+/// that are `Val::None` are treated as `false`.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Debug)]
+pub struct NoneIsNull;
+impl NoneMode for NoneIsNull {
+    fn bin<I, F>(a: Val<I, F>, b: Val<I, F>, f: BinFn<I, F>) -> Val<I, F>
+    where
+        I: DataType + PrimInt + Signed,
+        F: DataType + Float,
+    {
+        match (a, b) {
+            (Val::None, _) | (_, Val::None) => Val::None,
+            (a, b) => f(a, b),
+        }
+    }
+    fn logic<I, F>(a: Val<I, F>, b: Val<I, F>, dominant: bool, f: BinFn<I, F>) -> Val<I, F>
+    where
+        I: DataType + PrimInt + Signed,
+        F: DataType + Float,
+    {
+        let is_dominant = |v: &Val<I, F>| matches!(v, Val::Bool(x) if *x == dominant);
+        if is_dominant(&a) || is_dominant(&b) {
+            Val::Bool(dominant)
+        } else {
+            Self::bin(a, b, f)
+        }
+    }
+    fn unary<I, F>(a: Val<I, F>, f: fn(Val<I, F>) -> Val<I, F>) -> Val<I, F>
+    where
+        I: DataType + PrimInt + Signed,
+        F: DataType + Float,
+    {
+        match a {
+            Val::None => Val::None,
+            a => f(a),
+        }
+    }
+    fn cond<I, F>(c: Val<I, F>) -> Val<I, F>
+    where
+        I: DataType + PrimInt + Signed,
+        F: DataType + Float,
+    {
+        match c {
+            Val::None => Val::Bool(false),
+            c => c,
+        }
+    }
+}
+
 /// *`feature = "value"`* - Factory of default operators for the data type [`Val`](Val).
 ///
 /// Operators available in addition to those from [`FloatOpsFactory`](crate::FloatOpsFactory) are:
 ///
 /// |representation|description|
 /// |--------------|-----------|
-/// | `%` | reminder of integers |
+/// | `+` | addition of numbers and concatenation of strings |
+/// | `%` | reminder of integers, as postfix operator division by 100 such as `50%` |
 /// | <code>&#124;</code> | bitwise or of integers |
 /// | `&` | bitwise and of integers |
 /// | `XOR` | bitwise exclusive or of integers |
+/// | `!`, `not` | negation of booleans, `!` as postfix operator is the factorial of integers such as `5!` |
 /// | `<<` | left shift of integers |
 /// | `>>` | right shift of integers |
 /// | <code>&#124;&#124;</code> | or for booleans |
 /// | `&&` | and for booleans |
+/// | `xor` | exclusive or for booleans |
 /// | `if` | returns first operand if second is true, else `Val::None`, to make `x if condition else y` possible |
-/// | `else` | returns second operand if first is `Val::None`, else first, to make `x if condition else y` possible |
-/// | `==`, `!=`, `<`, `>`, `<=`, `>=`| comparison operators between numbers, e.g., `1 == 1.0` is true. Comparing booleans to none-booleans is false, e.g., `1 == true` is false. Comparisons with `Val::None` or `Val::Error` always results in `false`, e.g., `(5 if false) == (5 if false)` is false.|
+/// | `else` | returns second operand if first has not been selected by `if`, else first, to make `x if condition else y` possible |
+/// | `?` | returns second operand if first is true, else `Val::None`, to make `condition ? x : y` possible |
+/// | `:` | same as `else`, to make `condition ? x : y` possible |
+/// | `??`, `coalesce` | returns first operand if it is not `Val::None`, else second operand |
+/// | `is_none` | whether the operand is `Val::None` |
+/// | `contains` | whether the first string contains the second one or whether an array contains an element |
+/// | `starts_with` | whether the first string starts with the second one |
+/// | `in` | whether the first operand is contained in the second array or string, `x in (1, 2, 5)` is equivalent to <code>x == 1 &#124;&#124; x == 2 &#124;&#124; x == 5</code> |
+/// | `between` | whether the first operand is between the 2 elements of the second array, both inclusive. `between(x, lo, hi)` is equivalent to `x >= lo && x <= hi` |
+/// | `substr` | characters of a string starting at an integer index, negative indices count from the end |
+/// | `dot` | dot product of two arrays of the same length |
+/// | `[]` | element of an array or character of a string at an integer index, negative indices count from the end, used as `v[i]` |
+/// | `==`, `!=`, `<`, `>`, `<=`, `>=`| comparison operators between numbers or between strings, e.g., `1 == 1.0` is true. Comparing booleans to none-booleans is false, e.g., `1 == true` is false. Comparisons with `Val::None` or `Val::Error` always results in `false`, e.g., `(5 if false) == (5 if false)` is false, unless the factory uses [`NoneIsNull`](NoneIsNull).|
 /// | `fact` | factorial of integers |
 /// | `to_float` | convert integer, float, or bool to float |
 /// | `to_int` | convert integer, float, or bool to integer |
+/// | `len` | number of characters of a string or number of elements of an array |
+/// | `upper` | string in upper case |
+/// | `lower` | string in lower case |
+/// | `sum`, `mean`, `min`, `max` | reductions of arrays |
+///
+/// The binary operators are listed from the weakest to the strongest binding in the following.
+/// Unary operators bind stronger than all binary operators, e.g., `!a && b` is `(!a) && b`.
+/// Postfix operators bind stronger than unary operators, e.g., `-3!` is `-(3!)`. The operator `%`
+/// is postfix if it is not followed by an operand, i.e., `50% * x` is half of `x`, whereas
+/// `50 % x` is the remainder.
+/// Operators of the same priority are applied from left to right apart from `^`, `else`, and `:`,
+/// which are right-associative.
+///
+/// 1. `else`, `:`
+/// 1. `if`, `?`
+/// 1. <code>&#124;&#124;</code>, `??`, `coalesce`
+/// 1. `&&`, `xor`
+/// 1. `==`, `!=`, `<`, `>`, `<=`, `>=`, `in`, `between`, `contains`, `starts_with`
+/// 1. <code>&#124;</code>, `&`, `XOR`, `<<`, `>>`
+/// 1. `+`, `-`
+/// 1. `*`, `dot`
+/// 1. `/`, `%`, `substr`
+/// 1. `^`
+/// 1. `[]`
+///
+/// Unary operators apart from `len` and the reductions are applied element-wise to arrays. The
+/// same holds for binary operators apart from `==`, `!=`, `if`, `else`, `?`, `:`, `??`,
+/// `coalesce`, `contains`, `starts_with`, `in`, `between`, `substr`, `dot`, and `[]`, where
+/// scalars are broadcast to the length of the array.
+/// ```rust
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// #
+/// use exmex::{Express, Val};
+/// let expr = exmex::parse_val::<i32, f64>("sum(w * x) + max(x) - x[-1]")?;
+/// let x = Val::Array(vec![Val::Float(0.5), Val::Int(3), Val::Float(2.0)]);
+/// let w = Val::Array(vec![Val::Int(2), Val::Int(1), Val::Int(0)]);
+/// assert_eq!(expr.eval(&[w, x])?.to_float()?, 5.0);
+/// let expr = exmex::parse_val::<i32, f64>("[1, 2, 3] * 2 == [2, 4, 6]")?;
+/// assert!(expr.eval(&[])?.to_bool()?);
+/// #
+/// #     Ok(())
+/// # }
+/// ```
+///
+/// The operators `if`, `else`, `?`, `:`, `&&`, and `||` are evaluated lazily, i.e., operands
+/// that do not influence the result are not evaluated. For instance, the logarithm in
+/// `ln(x) if x > 0 else 0` is only computed for positive `x` and the right operand of `&&` only if
+/// the left one is true. Besides `x if condition else y` and `condition ? x : y`, conditionals
+/// can be written as `if(condition, x, y)`. Conditionals can be chained without parentheses,
+/// e.g., `a ? x : b ? y : z` is `a ? x : (b ? y : z)` and `x if a else y if b else z` is
+/// `x if a else (y if b else z)`. Conditionals in the middle need parentheses, e.g.,
+/// `a ? (b ? x : y) : z`.
+/// ```rust
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// #
+/// use exmex::{Express, Val};
+/// let expr = exmex::parse_val::<i32, f64>("x > 0 ? ln(x) : if(x == 0, 0, fact(x))")?;
+/// assert_eq!(expr.eval(&[Val::Float(1.0)])?.to_float()?, 0.0);
+/// assert_eq!(expr.eval(&[Val::Int(0)])?.to_int()?, 0);
+/// #
+/// #     Ok(())
+/// # }
+/// ```
+///
+/// The type parameter `N` determines the semantics of `Val::None` operands, e.g., missing values
+/// of a data set. With the default [`NoneIsError`](NoneIsError), most operators result in an error
+/// for `Val::None` operands. With [`NoneIsNull`](NoneIsNull), `Val::None` propagates through
+/// arithmetic and comparisons like `NULL` in SQL. In both cases, `x ?? default` and
+/// `coalesce(x, default)` replace missing values.
+/// ```rust
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// #
+/// use exmex::prelude::*;
+/// use exmex::{FlatExVal, NoneIsNull, Val};
+/// let expr = FlatExVal::<i32, f64, NoneIsNull>::from_str("(price * 2 > 10) ?? false")?;
+/// assert!(!expr.eval(&[Val::None])?.to_bool()?);
+/// let expr = FlatExVal::<i32, f64, NoneIsNull>::from_str("is_none(price * 2)")?;
+/// assert!(expr.eval(&[Val::None])?.to_bool()?);
+/// let expr = exmex::parse_val::<i32, f64>("coalesce(price, 0) * 2")?;
+/// assert_eq!(expr.eval(&[Val::None])?.to_int()?, 0);
+/// #
+/// #     Ok(())
+/// # }
+/// ```
+///
+/// Binary operators can also be written in function notation, e.g., `contains(name, "adm")`
+/// is equivalent to `name contains "adm"`.
+/// ```rust
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// #
+/// use exmex::{Express, Val};
+/// let expr = exmex::parse_val::<i32, f64>(r#"(len(name) > 3) && starts_with(upper(name), "AD")"#)?;
+/// assert!(expr.eval(&[Val::Str("admin".to_string())])?.to_bool()?);
+/// let expr = exmex::parse_val::<i32, f64>(r#"substr(name, -3) + "!""#)?;
+/// assert_eq!(expr.eval(&[Val::Str("admin".to_string())])?.to_str()?, "min!");
+/// #
+/// #     Ok(())
+/// # }
+/// ```
 ///
 #[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Debug)]
-pub struct ValOpsFactory<I = i32, F = f64>
+pub struct ValOpsFactory<I = i32, F = f64, N = NoneIsError>
 where
     I: DataType + PrimInt + Signed,
     F: DataType + Float,
+    N: NoneMode,
 {
     dummy_i: PhantomData<I>,
     dummy_f: PhantomData<F>,
+    dummy_n: PhantomData<N>,
 }
 
-impl<I, F> MakeOperators<Val<I, F>> for ValOpsFactory<I, F>
+impl<I, F, N> MakeOperators<Val<I, F>> for ValOpsFactory<I, F, N>
 where
     I: DataType + PrimInt + Signed,
     F: DataType + Float,
+    N: NoneMode,
     <I as FromStr>::Err: Debug,
     <F as FromStr>::Err: Debug,
 {
@@ -441,219 +1072,399 @@ where
             Operator::make_bin(
                 "^",
                 BinOp {
-                    apply: pow,
-                    prio: 6,
+                    apply: |a, b| broadcast(a, b, |a, b| N::bin(a, b, pow)),
+                    prio: 9,
                     is_commutative: false,
+                    is_right_assoc: true,
                 },
             ),
             Operator::make_bin(
                 "+",
                 BinOp {
-                    apply: add,
-                    prio: 3,
+                    apply: |a, b| broadcast(a, b, |a, b| N::bin(a, b, add_or_concat)),
+                    prio: 6,
                     is_commutative: true,
+                    is_right_assoc: false,
                 },
             ),
             Operator::make_bin_unary(
                 "-",
                 BinOp {
-                    apply: sub,
-                    prio: 3,
+                    apply: |a, b| broadcast(a, b, |a, b| N::bin(a, b, sub)),
+                    prio: 6,
                     is_commutative: false,
+                    is_right_assoc: false,
                 },
-                minus,
+                |a| N::unary(a, minus),
             ),
             Operator::make_bin(
                 "*",
                 BinOp {
-                    apply: mul,
-                    prio: 4,
+                    apply: |a, b| broadcast(a, b, |a, b| N::bin(a, b, mul)),
+                    prio: 7,
                     is_commutative: true,
+                    is_right_assoc: false,
                 },
             ),
             Operator::make_bin(
                 "/",
                 BinOp {
-                    apply: |a, b| match b {
-                        Val::Int(x) if x == I::zero() => {
-                            Val::Error(ExError::new("int division by zero"))
-                        }
-                        _ => div(a, b),
+                    apply: |a, b| {
+                        broadcast(a, b, |a, b| {
+                            N::bin(a, b, |a, b| match b {
+                                Val::Int(x) if x == I::zero() => {
+                                    Val::Error(ExError::new("int division by zero"))
+                                }
+                                _ => div(a, b),
+                            })
+                        })
                     },
-                    prio: 5,
+                    prio: 8,
                     is_commutative: false,
+                    is_right_assoc: false,
                 },
             ),
-            Operator::make_bin(
+            Operator::make_bin_postfix(
                 "%",
                 BinOp {
-                    apply: rem,
-                    prio: 5,
+                    apply: |a, b| broadcast(a, b, |a, b| N::bin(a, b, rem)),
+                    prio: 8,
                     is_commutative: false,
+                    is_right_assoc: false,
                 },
+                |a| N::unary(a, percent),
             ),
             Operator::make_bin(
                 "|",
                 BinOp {
-                    apply: bitwise_or,
-                    prio: 2,
+                    apply: |a, b| broadcast(a, b, |a, b| N::bin(a, b, bitwise_or)),
+                    prio: 5,
                     is_commutative: true,
+                    is_right_assoc: false,
                 },
             ),
             Operator::make_bin(
                 "&",
                 BinOp {
-                    apply: bitwise_and,
-                    prio: 2,
+                    apply: |a, b| broadcast(a, b, |a, b| N::bin(a, b, bitwise_and)),
+                    prio: 5,
                     is_commutative: true,
+                    is_right_assoc: false,
                 },
             ),
             Operator::make_bin(
                 "XOR",
                 BinOp {
-                    apply: bitwise_xor,
-                    prio: 2,
+                    apply: |a, b| broadcast(a, b, |a, b| N::bin(a, b, bitwise_xor)),
+                    prio: 5,
                     is_commutative: true,
+                    is_right_assoc: false,
                 },
             ),
             Operator::make_bin(
                 ">>",
                 BinOp {
-                    apply: right_shift,
-                    prio: 2,
+                    apply: |a, b| broadcast(a, b, |a, b| N::bin(a, b, right_shift)),
+                    prio: 5,
                     is_commutative: false,
+                    is_right_assoc: false,
                 },
             ),
             Operator::make_bin(
                 "<<",
                 BinOp {
-                    apply: left_shift,
-                    prio: 2,
+                    apply: |a, b| broadcast(a, b, |a, b| N::bin(a, b, left_shift)),
+                    prio: 5,
                     is_commutative: false,
+                    is_right_assoc: false,
                 },
             ),
-            Operator::make_bin(
+            Operator::make_bin_short_circuit(
                 "&&",
                 BinOp {
-                    apply: and,
-                    prio: 2,
+                    apply: |a, b| broadcast(a, b, |a, b| N::logic(a, b, false, and)),
+                    prio: 3,
                     is_commutative: true,
+                    is_right_assoc: false,
+                },
+                ShortCircuit {
+                    first: Operand::Left,
+                    apply: |a| match a {
+                        Val::Bool(false) => Some(Val::Bool(false)),
+                        _ => None,
+                    },
+                    selection: None,
                 },
             ),
-            Operator::make_bin(
+            Operator::make_bin_short_circuit(
                 "||",
                 BinOp {
-                    apply: or,
+                    apply: |a, b| broadcast(a, b, |a, b| N::logic(a, b, true, or)),
                     prio: 2,
                     is_commutative: true,
+                    is_right_assoc: false,
+                },
+                ShortCircuit {
+                    first: Operand::Left,
+                    apply: |a| match a {
+                        Val::Bool(true) => Some(Val::Bool(true)),
+                        _ => None,
+                    },
+                    selection: None,
                 },
             ),
             Operator::make_bin(
                 "==",
                 BinOp {
-                    apply: |a, b| Val::Bool(a == b),
-                    prio: 1,
+                    apply: |a, b| N::bin(a, b, |a, b| Val::Bool(a == b)),
+                    prio: 4,
                     is_commutative: true,
+                    is_right_assoc: false,
                 },
             ),
             Operator::make_bin(
                 ">=",
                 BinOp {
-                    apply: |a, b| Val::Bool(a >= b),
-                    prio: 1,
-                    is_commutative: true,
+                    apply: |a, b| broadcast(a, b, |a, b| N::bin(a, b, |a, b| Val::Bool(a >= b))),
+                    prio: 4,
+                    is_commutative: false,
+                    is_right_assoc: false,
                 },
             ),
             Operator::make_bin(
                 ">",
                 BinOp {
-                    apply: |a, b| Val::Bool(a > b),
-                    prio: 1,
-                    is_commutative: true,
+                    apply: |a, b| broadcast(a, b, |a, b| N::bin(a, b, |a, b| Val::Bool(a > b))),
+                    prio: 4,
+                    is_commutative: false,
+                    is_right_assoc: false,
                 },
             ),
             Operator::make_bin(
                 "<=",
                 BinOp {
-                    apply: |a, b| Val::Bool(a <= b),
-                    prio: 1,
-                    is_commutative: true,
+                    apply: |a, b| broadcast(a, b, |a, b| N::bin(a, b, |a, b| Val::Bool(a <= b))),
+                    prio: 4,
+                    is_commutative: false,
+                    is_right_assoc: false,
                 },
             ),
             Operator::make_bin(
                 "<",
                 BinOp {
-                    apply: |a, b| Val::Bool(a < b),
-                    prio: 1,
-                    is_commutative: true,
+                    apply: |a, b| broadcast(a, b, |a, b| N::bin(a, b, |a, b| Val::Bool(a < b))),
+                    prio: 4,
+                    is_commutative: false,
+                    is_right_assoc: false,
                 },
             ),
             Operator::make_bin(
                 "!=",
                 BinOp {
-                    apply: |a, b| Val::Bool(a != b),
-                    prio: 1,
+                    apply: |a, b| N::bin(a, b, |a, b| Val::Bool(a != b)),
+                    prio: 4,
                     is_commutative: true,
+                    is_right_assoc: false,
                 },
             ),
             Operator::make_bin(
-                "if",
+                "dot",
                 BinOp {
-                    apply: |v, cond| {
-                        let condition = match cond.to_bool() {
-                            Ok(b) => b,
-                            Err(e) => return Val::Error(e),
-                        };
-                        if condition {
-                            v
-                        } else {
-                            Val::None
-                        }
-                    },
-                    prio: 0,
+                    apply: |a, b| N::bin(a, b, dot),
+                    prio: 7,
+                    is_commutative: true,
+                    is_right_assoc: false,
+                },
+            ),
+            Operator::make_bin_index(
+                "[]",
+                BinOp {
+                    apply: |a, b| N::bin(a, b, index),
+                    prio: 10,
+                    is_commutative: false,
+                    is_right_assoc: false,
+                },
+            ),
+            Operator::make_bin(
+                "contains",
+                BinOp {
+                    apply: |a, b| N::bin(a, b, contains),
+                    prio: 4,
+                    is_commutative: false,
+                    is_right_assoc: false,
+                },
+            ),
+            Operator::make_bin(
+                "starts_with",
+                BinOp {
+                    apply: |a, b| N::bin(a, b, starts_with),
+                    prio: 4,
+                    is_commutative: false,
+                    is_right_assoc: false,
+                },
+            ),
+            Operator::make_bin(
+                "in",
+                BinOp {
+                    apply: |a, b| N::bin(b, a, contains),
+                    prio: 4,
+                    is_commutative: false,
+                    is_right_assoc: false,
+                },
+            )
+            .with_tuple_expansion("==", "||"),
+            Operator::make_bin(
+                "between",
+                BinOp {
+                    apply: |a, b| N::bin(a, b, between),
+                    prio: 4,
                     is_commutative: false,
+                    is_right_assoc: false,
+                },
+            )
+            .with_ternary_call("{0} >= {1} && {0} <= {2}"),
+            Operator::make_bin(
+                "xor",
+                BinOp {
+                    apply: |a, b| broadcast(a, b, |a, b| N::bin(a, b, xor)),
+                    prio: 3,
+                    is_commutative: true,
+                    is_right_assoc: false,
                 },
             ),
             Operator::make_bin(
+                "substr",
+                BinOp {
+                    apply: |a, b| N::bin(a, b, substr),
+                    prio: 8,
+                    is_commutative: false,
+                    is_right_assoc: false,
+                },
+            ),
+            Operator::make_bin_short_circuit(
+                "if",
+                BinOp {
+                    apply: |v, cond| select_if(v, N::cond(cond)),
+                    prio: 1,
+                    is_commutative: false,
+                    is_right_assoc: false,
+                },
+                ShortCircuit {
+                    first: Operand::Right,
+                    apply: |cond| short_circuit_if(&N::cond(cond.clone())),
+                    selection: Some(Selection::Condition),
+                },
+            )
+            .with_ternary_call("{1} if {0} else {2}"),
+            Operator::make_bin_short_circuit(
                 "else",
                 BinOp {
-                    apply: |res_of_if, v| match res_of_if {
-                        Val::None => v,
-                        _ => res_of_if,
-                    },
+                    apply: select_else,
+                    prio: 0,
+                    is_commutative: false,
+                    is_right_assoc: true,
+                },
+                ShortCircuit {
+                    first: Operand::Left,
+                    apply: |res_of_if| Some(res_of_if.clone()),
+                    selection: Some(Selection::Alternative),
+                },
+            ),
+            Operator::make_bin_short_circuit(
+                "?",
+                BinOp {
+                    apply: |cond, v| select_if(v, N::cond(cond)),
+                    prio: 1,
+                    is_commutative: false,
+                    is_right_assoc: false,
+                },
+                ShortCircuit {
+                    first: Operand::Left,
+                    apply: |cond| short_circuit_if(&N::cond(cond.clone())),
+                    selection: Some(Selection::Condition),
+                },
+            ),
+            Operator::make_bin_short_circuit(
+                ":",
+                BinOp {
+                    apply: select_else,
                     prio: 0,
                     is_commutative: false,
+                    is_right_assoc: true,
+                },
+                ShortCircuit {
+                    first: Operand::Left,
+                    apply: |res_of_if| Some(res_of_if.clone()),
+                    selection: Some(Selection::Alternative),
+                },
+            ),
+            Operator::make_bin_short_circuit(
+                "??",
+                BinOp {
+                    apply: coalesce,
+                    prio: 2,
+                    is_commutative: false,
+                    is_right_assoc: false,
+                },
+                ShortCircuit {
+                    first: Operand::Left,
+                    apply: short_circuit_coalesce,
+                    selection: None,
                 },
             ),
-            Operator::make_unary("signum", signum),
-            Operator::make_unary("abs", abs),
-            Operator::make_unary("sin", sin),
-            Operator::make_unary("cos", cos),
-            Operator::make_unary("tan", tan),
-            Operator::make_unary("asin", asin),
-            Operator::make_unary("acos", acos),
-            Operator::make_unary("atan", atan),
-            Operator::make_unary("sinh", sinh),
-            Operator::make_unary("cosh", cosh),
-            Operator::make_unary("tanh", tanh),
-            Operator::make_unary("floor", floor),
-            Operator::make_unary("ceil", ceil),
-            Operator::make_unary("trunc", trunc),
-            Operator::make_unary("fract", fract),
-            Operator::make_unary("exp", exp),
-            Operator::make_unary("sqrt", sqrt),
-            Operator::make_unary("cbrt", cbrt),
-            Operator::make_unary("round", round),
-            Operator::make_unary("ln", ln),
-            Operator::make_unary("log10", log10),
-            Operator::make_unary("log2", log2),
-            Operator::make_unary("log", ln),
-            Operator::make_unary("swap_bytes", swap_bytes),
-            Operator::make_unary("to_le", to_le),
-            Operator::make_unary("to_be", to_be),
-            Operator::make_unary("fact", fact),
-            Operator::make_unary("to_int", cast_to_int),
-            Operator::make_unary("to_float", cast_to_float),
+            Operator::make_bin_short_circuit(
+                "coalesce",
+                BinOp {
+                    apply: coalesce,
+                    prio: 2,
+                    is_commutative: false,
+                    is_right_assoc: false,
+                },
+                ShortCircuit {
+                    first: Operand::Left,
+                    apply: short_circuit_coalesce,
+                    selection: None,
+                },
+            ),
+            Operator::make_unary("is_none", |a| Val::Bool(matches!(a, Val::None))),
+            Operator::make_unary("signum", |a| N::unary(a, signum)),
+            Operator::make_unary("abs", |a| N::unary(a, abs)),
+            Operator::make_unary("sin", |a| N::unary(a, sin)),
+            Operator::make_unary("cos", |a| N::unary(a, cos)),
+            Operator::make_unary("tan", |a| N::unary(a, tan)),
+            Operator::make_unary("asin", |a| N::unary(a, asin)),
+            Operator::make_unary("acos", |a| N::unary(a, acos)),
+            Operator::make_unary("atan", |a| N::unary(a, atan)),
+            Operator::make_unary("sinh", |a| N::unary(a, sinh)),
+            Operator::make_unary("cosh", |a| N::unary(a, cosh)),
+            Operator::make_unary("tanh", |a| N::unary(a, tanh)),
+            Operator::make_unary("floor", |a| N::unary(a, floor)),
+            Operator::make_unary("ceil", |a| N::unary(a, ceil)),
+            Operator::make_unary("trunc", |a| N::unary(a, trunc)),
+            Operator::make_unary("fract", |a| N::unary(a, fract)),
+            Operator::make_unary("exp", |a| N::unary(a, exp)),
+            Operator::make_unary("sqrt", |a| N::unary(a, sqrt)),
+            Operator::make_unary("cbrt", |a| N::unary(a, cbrt)),
+            Operator::make_unary("round", |a| N::unary(a, round)),
+            Operator::make_unary("ln", |a| N::unary(a, ln)),
+            Operator::make_unary("log10", |a| N::unary(a, log10)),
+            Operator::make_unary("log2", |a| N::unary(a, log2)),
+            Operator::make_unary("log", |a| N::unary(a, ln)),
+            Operator::make_unary("swap_bytes", |a| N::unary(a, swap_bytes)),
+            Operator::make_unary("to_le", |a| N::unary(a, to_le)),
+            Operator::make_unary("to_be", |a| N::unary(a, to_be)),
+            Operator::make_unary("fact", |a| N::unary(a, fact)),
+            Operator::make_unary("to_int", |a| N::unary(a, cast_to_int)),
+            Operator::make_unary("to_float", |a| N::unary(a, cast_to_float)),
+            Operator::make_unary("len", |a| N::unary(a, len)),
+            Operator::make_unary_postfix("!", |a| N::unary(a, not), |a| N::unary(a, fact)),
+            Operator::make_unary("not", |a| N::unary(a, not)),
+            Operator::make_unary("upper", |a| N::unary(a, upper)),
+            Operator::make_unary("lower", |a| N::unary(a, lower)),
+            Operator::make_unary("sum", |a| N::unary(a, sum)),
+            Operator::make_unary("mean", |a| N::unary(a, mean)),
+            Operator::make_unary("min", |a| N::unary(a, min)),
+            Operator::make_unary("max", |a| N::unary(a, max)),
             Operator::make_constant("PI", Val::Float(F::from(std::f64::consts::PI).unwrap())),
             Operator::make_constant("π", Val::Float(F::from(std::f64::consts::PI).unwrap())),
             Operator::make_constant("E", Val::Float(F::from(std::f64::consts::E).unwrap())),
@@ -662,12 +1473,27 @@ where
         ]
     }
 }
-const PATTERN: &str = r"^([0-9]+(\.[0-9]+)?|true|false|\[\s*(\-?.?[0-9]+(\.[0-9]+)?|true|false)(\s*,\s*-?\.?[0-9]+(\.[0-9]+)?|true|false)*\s*\])";
+/// Hexadecimal, octal, and binary integers as well as decimal numbers with optional fraction
+/// and exponent, digits can be separated by underscores such as in `1_000_000`.
+macro_rules! number_pattern {
+    () => {
+        r"(0[xX][0-9a-fA-F]+(_[0-9a-fA-F]+)*|0[oO][0-7]+(_[0-7]+)*|0[bB][01]+(_[01]+)*|[0-9]+(_[0-9]+)*(\.[0-9]+(_[0-9]+)*)?([eE][+-]?[0-9]+)?)"
+    };
+}
+const PATTERN: &str = concat!(
+    r#"^("[^"]*"|"#,
+    number_pattern!(),
+    r#"|true|false|\[\s*((-?"#,
+    number_pattern!(),
+    r#"|true|false|"[^"]*")(\s*,\s*(-?"#,
+    number_pattern!(),
+    r#"|true|false|"[^"]*"))*)?\s*\])"#
+);
 literal_matcher_from_pattern!(ValMatcher, PATTERN);
 
 /// *`feature = "value"`* - Alias for [`FlatEx`](FlatEx) with [`Val`](Val) as data type and [`ValOpsFactory`](ValOpsFactory)
 /// as operator factory.
-pub type FlatExVal<I, F> = FlatEx<Val<I, F>, ValOpsFactory<I, F>, ValMatcher>;
+pub type FlatExVal<I, F, N = NoneIsError> = FlatEx<Val<I, F>, ValOpsFactory<I, F, N>, ValMatcher>;
 
 /// *`feature = "value"`* - Parses a string into an expression of type
 /// [`FlatExVal`](FlatExVal) with datatype [`Val`](Val).
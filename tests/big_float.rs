@@ -0,0 +1,34 @@
+#[cfg(feature = "bigfloat")]
+use {
+    exmex::{BigFloat, ExResult, Express, FlatExBigFloat},
+    std::str::FromStr,
+};
+
+#[cfg(feature = "bigfloat")]
+#[test]
+fn test_big_float() -> ExResult<()> {
+    type FlatExBf = FlatExBigFloat<512>;
+    fn assert_close(res: &BigFloat<512>, reference: &str) {
+        let reference = BigFloat::<512>::from_str(reference).unwrap();
+        let tol = BigFloat::<512>::from_str("1e-140").unwrap();
+        let diff = res
+            .0
+            .sub(&reference.0, 512, astro_float::RoundingMode::ToEven);
+        assert!(diff.abs() < tol.0, "{} != {}", res, reference);
+    }
+    // 1/3 with far more digits than f64 can hold
+    let expr = FlatExBf::from_str("1 / x")?;
+    assert_close(
+        &expr.eval(&[BigFloat::from_str("3")?])?,
+        "0.33333333333333333333333333333333333333333333333333333333333333333333333333333333333333333333333333333333333333333333333333333333333333333333333333",
+    );
+    let expr = FlatExBf::from_str("sqrt(2)^2 + 1.5e-150 - 2")?;
+    assert_close(&expr.eval(&[])?, "0");
+    let expr = FlatExBf::from_str("sin(PI/6) * exp(ln(x)) - cbrt(8) + log2(1024) + abs(-1)")?;
+    assert_close(&expr.eval(&[BigFloat::from_str("4")?])?, "11");
+    let expr = FlatExBf::from_str("floor(2.5) + ceil(2.5) + trunc(-2.5) + fract(2.25)")?;
+    assert_close(&expr.eval(&[])?, "3.25");
+    assert!(FlatExBf::from_str("sqrt(-1)")?.eval(&[])?.is_nan());
+    assert!(FlatExBf::from_str("signum(x)").is_err());
+    Ok(())
+}
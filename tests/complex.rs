@@ -0,0 +1,68 @@
+#[cfg(feature = "partial")]
+use exmex::Differentiate;
+use exmex::{ExResult, Express, FlatExComplex};
+use num::Complex;
+use std::str::FromStr;
+
+fn assert_complex_eq(c1: Complex<f64>, c2: Complex<f64>) {
+    if (c1 - c2).norm() > 1e-12 {
+        println!(
+            "Complex numbers not almost equal.\nc1: {}\nc2: {}\n",
+            c1, c2
+        );
+        unreachable!();
+    }
+}
+
+#[test]
+fn test_complex() -> ExResult<()> {
+    fn test(s: &str, vars: &[Complex<f64>], reference: Complex<f64>) -> ExResult<()> {
+        println!("testing {}", s);
+        let expr = FlatExComplex::<f64>::from_str(s)?;
+        assert_complex_eq(expr.eval(vars)?, reference);
+        Ok(())
+    }
+    let c = Complex::new;
+    test("2i", &[], c(0.0, 2.0))?;
+    test("i*i", &[], c(-1.0, 0.0))?;
+    test("j^2", &[], c(-1.0, 0.0))?;
+    test("1.5 + 0.5j", &[], c(1.5, 0.5))?;
+    test("(1+2i)*(3-i)", &[], c(5.0, 5.0))?;
+    test("(1+2i)/(3-i)", &[], c(0.1, 0.7))?;
+    test("-3i + z", &[c(1.0, 1.0)], c(1.0, -2.0))?;
+    test("abs(3+4i)", &[], c(5.0, 0.0))?;
+    test("arg(2i)", &[], c(std::f64::consts::FRAC_PI_2, 0.0))?;
+    test("conj(z)", &[c(1.0, 2.0)], c(1.0, -2.0))?;
+    test("re(z) + im(z)", &[c(1.0, 2.0)], c(3.0, 0.0))?;
+    test("exp(i*PI)", &[], c(-1.0, 0.0))?;
+    test("ln(2i)", &[], c(2.0f64.ln(), std::f64::consts::FRAC_PI_2))?;
+    test("ln(-1)", &[], c(0.0, std::f64::consts::PI))?;
+    test("sqrt(-4)", &[], c(0.0, 2.0))?;
+    test("sin(z)^2 + cos(z)^2", &[c(0.3, -1.2)], c(1.0, 0.0))?;
+    test("sinh(i)", &[], c(0.0, 1.0f64.sin()))?;
+    test("im(z) * 2i", &[c(1.0, 3.0)], c(0.0, 6.0))?;
+    assert!(FlatExComplex::<f64>::from_str("2ii").is_err());
+    Ok(())
+}
+
+#[cfg(feature = "partial")]
+#[test]
+fn test_complex_partial() -> ExResult<()> {
+    let expr = FlatExComplex::<f64>::from_str("z^2 * exp(i*z) + ln(z)")?;
+    let dexpr = expr.partial(0)?;
+    let z = Complex::new(0.7, -0.4);
+    let i = Complex::<f64>::i();
+    let reference = 2.0 * z * (i * z).exp() + i * z * z * (i * z).exp() + 1.0 / z;
+    assert_complex_eq(dexpr.eval(&[z])?, reference);
+
+    let expr = FlatExComplex::<f64>::from_str("sin(z*w)")?;
+    let dexpr = expr.partial(1)?;
+    let w = Complex::new(-1.3, 0.2);
+    assert_complex_eq(dexpr.eval(&[w, z])?, w * (z * w).cos());
+
+    for s in ["conj(z)", "abs(z)", "re(z)", "im(z)", "arg(z)"] {
+        let expr = FlatExComplex::<f64>::from_str(s)?;
+        assert!(expr.partial(0).is_err());
+    }
+    Ok(())
+}
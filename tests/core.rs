@@ -5,7 +5,9 @@ use exmex::format_exerr;
 use exmex::{
     literal_matcher_from_pattern, ops_factory, parse,
     prelude::*,
-    ExError, ExResult, MatchLiteral, {BinOp, FloatOpsFactory, MakeOperators, Operator},
+    variable_matcher_from_pattern, ExError, ExResult, FuncEnv, MatchLiteral, MatchVariable,
+    MathConvention, NumberMatcher, ParseOptions, Whitespace,
+    {BinOp, FloatOpsFactory, MakeOperators, Operator},
 };
 use std::iter::repeat;
 #[cfg(test)]
@@ -121,7 +123,8 @@ fn test_readme() -> ExResult<()> {
                 BinOp {
                     apply: |a, b| a | b,
                     prio: 0,
-                    is_commutative: true
+                    is_commutative: true,
+                    is_right_assoc: false
                 }
             ),
             Operator::make_unary("!", |a| !a)
@@ -223,6 +226,7 @@ fn test_variables_non_ascii() -> ExResult<()> {
                 apply: |a, b| a | b,
                 prio: 0,
                 is_commutative: true,
+                is_right_assoc: false,
             }
         ),
         Operator::make_bin(
@@ -231,6 +235,7 @@ fn test_variables_non_ascii() -> ExResult<()> {
                 apply: |a, b| a & b,
                 prio: 0,
                 is_commutative: true,
+                is_right_assoc: false,
             }
         ),
         Operator::make_constant("γ", Thumbs { val: false })
@@ -253,6 +258,33 @@ fn test_variables_non_ascii() -> ExResult<()> {
     Ok(())
 }
 
+#[test]
+fn test_variable_matcher() -> ExResult<()> {
+    variable_matcher_from_pattern!(
+        DottedMatcher,
+        r"^\$?[\p{L}_][\p{L}\p{N}_]*(\.[\p{L}_][\p{L}\p{N}_]*)*"
+    );
+    type FlatExDotted = FlatEx<f64, FloatOpsFactory<f64>, NumberMatcher, DottedMatcher>;
+    let expr = FlatExDotted::from_str("sensor.temp * 2 + sin(sensor.temp) - $price")?;
+    assert_eq!(expr.var_names(), ["$price", "sensor.temp"]);
+    utils::assert_float_eq_f64(expr.eval(&[1.0, 0.0])?, -1.0);
+    let expr = FlatExDotted::from_str("Ж^2 + 日本 + {x y} + sinus")?;
+    assert_eq!(expr.var_names(), ["sinus", "x y", "Ж", "日本"]);
+    utils::assert_float_eq_f64(expr.eval(&[1.0, 2.0, 3.0, 4.0])?, 16.0);
+    let expr = FlatExDotted::from_str("a = x.y^2; a + 1")?;
+    assert_eq!(expr.var_names(), ["x.y"]);
+    let expr = FlatExDotted::from_str("sensor.avg = x + 1; sensor.avg * 2")?;
+    assert_eq!(expr.var_names(), ["x"]);
+    utils::assert_float_eq_f64(expr.eval(&[2.0])?, 6.0);
+    let expr = FlatExDotted::from_str("$p = x + 1; $p * 2")?;
+    utils::assert_float_eq_f64(expr.eval(&[2.0])?, 6.0);
+    let expr = FlatExDotted::from_str("scale.by(a.b, $c) = a.b * $c; scale.by(x, 3)")?;
+    utils::assert_float_eq_f64(expr.eval(&[2.0])?, 6.0);
+    assert!(FlatEx::<f64>::from_str("sensor.temp").is_err());
+    assert!(FlatEx::<f64>::from_str("日本").is_err());
+    Ok(())
+}
+
 #[test]
 fn test_variables() -> ExResult<()> {
     let sut = "sin  ({x})+(((cos({y})   ^  (sin({z})))*ln(cos({y})))*cos({z}))";
@@ -362,6 +394,120 @@ fn test_variables() -> ExResult<()> {
     Ok(())
 }
 
+#[test]
+fn test_statements() -> ExResult<()> {
+    fn test(sut: &str, vars: &[f64], reference: f64, var_names: &[&str]) -> ExResult<()> {
+        println!("testing {}...", sut);
+        let flatex = FlatEx::<f64>::from_str(sut)?;
+        assert_eq!(flatex.var_names(), var_names);
+        utils::assert_float_eq_f64(flatex.eval(vars)?, reference);
+        assert_eq!(flatex.unparse(), sut);
+        Ok(())
+    }
+    let (x, y) = (1.5f64, 0.5f64);
+    test(
+        "r = sqrt(x^2+y^2); atan(y/x) * r",
+        &[x, y],
+        (y / x).atan() * (x * x + y * y).sqrt(),
+        &["x", "y"],
+    )?;
+    // unary operators have higher priority than binary ones, i.e., b = (-2)^2
+    test("a = 2; b = -a^2; -b + a", &[], -2.0, &[])?;
+    test("a = x; a = a + 1; sin a", &[x], (x + 1.0).sin(), &["x"])?;
+    test("{my local} = x*z; {my local}/z", &[x, y], x, &["x", "z"])?;
+    assert!(FlatEx::<f64>::from_str("a = x;").is_err());
+    assert!(FlatEx::<f64>::from_str("a = x; b").is_ok());
+    assert!(FlatEx::<f64>::from_str("x; a").is_err());
+    assert!(FlatEx::<f64>::from_str("a = (x; a").is_err());
+
+    // each local doubles the number of tokens
+    let chain = |n: usize| {
+        let locals = (1..n)
+            .map(|i| format!("a{} = a{} + a{}; ", i, i - 1, i - 1))
+            .collect::<String>();
+        format!("a0 = x + x; {}a{}", locals, n - 1)
+    };
+    test(&chain(10), &[1.0], 1024.0, &["x"])?;
+    assert!(FlatEx::<f64>::from_str(&chain(40)).is_err());
+    let calls = (1..40)
+        .map(|i| format!("f{}(a) = f{}(a) + f{}(a); ", i, i - 1, i - 1))
+        .collect::<String>();
+    assert!(FlatEx::<f64>::from_str(&format!("f0(a) = a; {}f39(x)", calls)).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_user_defined_functions() -> ExResult<()> {
+    fn test(sut: &str, vars: &[f64], reference: f64, var_names: &[&str]) -> ExResult<()> {
+        println!("testing {}...", sut);
+        let flatex = FlatEx::<f64>::from_str(sut)?;
+        assert_eq!(flatex.var_names(), var_names);
+        utils::assert_float_eq_f64(flatex.eval(vars)?, reference);
+        Ok(())
+    }
+    let (x, y) = (1.5f64, 0.5f64);
+    test("f(x) = x^2 + 1; f(3)", &[], 10.0, &[])?;
+    test("f(a, b) = a*b; f(x, y) + f(y, 2)", &[x, y], x * y + 1.0, &["x", "y"])?;
+    test("f(a) = sin(a); g(a) = f(a)*2; g(f(x))", &[x], x.sin().sin() * 2.0, &["x"])?;
+    test("c = 2; f(x) = c*x; f(y)", &[y], 2.0 * y, &["y"])?;
+    // parameters shadow locals
+    test("x = 2; f(x) = x^2; f(y)", &[y], y * y, &["y"])?;
+    test("z() = 3.5; z() - x", &[x], 3.5 - x, &["x"])?;
+    test("f(a) = -a; f(x)^2", &[x], x * x, &["x"])?;
+
+    fn test_err(sut: &str, msg_part: &str) {
+        match FlatEx::<f64>::from_str(sut) {
+            Ok(_) => panic!("'{}' should not be parsable", sut),
+            Err(e) => {
+                println!("msg '{}' should contain '{}'", e.msg(), msg_part);
+                assert!(e.msg().contains(msg_part));
+            }
+        }
+    }
+    test_err("f(n) = n*f(n-1); f(3)", "recursive");
+    test_err("f(x) = x; f(1, 2)", "expects 1 argument(s) but got 2");
+    test_err("f(x, y) = x; f(1, )", "cannot be empty");
+    test_err("f(x) = g(x); f(2)", "unknown function 'g'");
+    test_err("f(x) = x; g(2)", "unknown function 'g'");
+    test_err("sin(x) = x; sin(2)", "operator");
+    test_err("f(cos) = cos; f(2)", "operator");
+    test_err("f(x) = x; f(x) = 2", "last statement");
+    test_err("a = 1, 2; a", "comma");
+
+    let mut env = FuncEnv::new();
+    env.define("sq(x) = x^2")?;
+    env.define("norm(x, y) = sqrt(sq(x) + sq(y))")?;
+    let flatex = FlatEx::<f64>::from_str_with_env("norm(a, 4) / 5", &env)?;
+    assert_eq!(flatex.var_names(), ["a"]);
+    utils::assert_float_eq_f64(flatex.eval(&[3.0])?, 1.0);
+    let reparsed = FlatEx::<f64>::from_str(flatex.unparse())?;
+    utils::assert_float_eq_f64(reparsed.eval(&[3.0])?, 1.0);
+    let flatex = FlatEx::<f64>::from_str_with_env("sq(a)", &env)?;
+    assert_eq!(flatex.unparse(), "sq(x) = x^2; sq(a)");
+    let flatex = FlatEx::<f64>::from_str_with_env("a*2", &env)?;
+    assert_eq!(flatex.unparse(), "a*2");
+    assert!(FlatEx::<f64>::from_str_with_env("unknown(a)", &env).is_err());
+    // functions can call functions that are defined later
+    env.define("outer(x) = inner(x) + 1")?;
+    env.define("inner(x) = sq(x) * 2")?;
+    let flatex = FlatEx::<f64>::from_str_with_env("outer(a)", &env)?;
+    utils::assert_float_eq_f64(flatex.eval(&[3.0])?, 19.0);
+    assert!(env.define("sq2(x) = outer(x)^2 + sq2(x)").is_err());
+    assert!(env.define("twice(x) = 2 * outer(twice(x))").is_err());
+
+    // names of functions and parameters are matched by the variable matcher of the environment
+    variable_matcher_from_pattern!(DottedMatcher, r"^[a-z_]+(\.[a-z_]+)*");
+    type FlatExDotted = FlatEx<f64, FloatOpsFactory<f64>, NumberMatcher, DottedMatcher>;
+    let mut env = FuncEnv::<DottedMatcher>::default();
+    env.define("vec.norm(v.x, v.y) = sqrt(v.x^2 + v.y^2)")?;
+    let flatex = FlatExDotted::from_str_with_env("vec.norm(p.x, 4)", &env)?;
+    assert_eq!(flatex.var_names(), ["p.x"]);
+    utils::assert_float_eq_f64(flatex.eval(&[3.0])?, 5.0);
+    assert!(env.define("vec.twice(v.x) = 2 * vec.twice(v.x)").is_err());
+    assert!(FuncEnv::new().define("vec.norm(v.x) = v.x").is_err());
+    Ok(())
+}
+
 #[test]
 fn test_custom_ops_invert() -> ExResult<()> {
     #[derive(Clone)]
@@ -392,6 +538,7 @@ fn test_custom_ops() -> ExResult<()> {
                         apply: |a: f32, b| a.powf(b),
                         prio: 2,
                         is_commutative: false,
+                        is_right_assoc: false,
                     },
                 ),
                 Operator::make_bin(
@@ -400,6 +547,7 @@ fn test_custom_ops() -> ExResult<()> {
                         apply: |a, b| a * b,
                         prio: 1,
                         is_commutative: true,
+                        is_right_assoc: false,
                     },
                 ),
                 Operator::make_unary("invert", |a: f32| 1.0 / a),
@@ -420,6 +568,7 @@ fn test_custom_ops() -> ExResult<()> {
                     apply: |_: f32, _| 0.0,
                     prio: 2,
                     is_commutative: true,
+                    is_right_assoc: false,
                 },
                 |_| 0.0,
             );
@@ -433,6 +582,246 @@ fn test_custom_ops() -> ExResult<()> {
     let expr = FlatEx::<f32, ExtendedF32Operators>::from_str("2^2*1/(berti) + zer0(4)")?;
     let val = expr.eval(&[4.0])?;
     utils::assert_float_eq::<f32>(val, 1.0, 1e-6, 0.0, "");
+
+    // names of operators do not imply a meaning, calls with three arguments need a template
+    ops_factory!(
+        NamesOpsFactory,
+        f64,
+        Operator::make_bin("if", BinOp::new(|a, b| a * b, 1, true)),
+        Operator::make_bin("else", BinOp::new(|a, b| a + b, 0, true)),
+        Operator::make_bin("between", BinOp::new(|a, b| a - b, 0, false))
+            .with_ternary_call("{0} between {1} between {2}")
+    );
+    let expr = FlatEx::<f64, NamesOpsFactory>::from_str("if(2, 3) else 1")?;
+    utils::assert_float_eq_f64(expr.eval(&[])?, 7.0);
+    assert!(FlatEx::<f64, NamesOpsFactory>::from_str("if(x, 1, 2)").is_err());
+    let expr = FlatEx::<f64, NamesOpsFactory>::from_str("between(x, 1, 2)")?;
+    utils::assert_float_eq_f64(expr.eval(&[5.0])?, 2.0);
+
+    // tuples are only expanded for operators that opt in
+    ops_factory!(
+        TupleOpsFactory,
+        f64,
+        Operator::make_bin("==", BinOp::new(|a, b| if a == b { 1.0 } else { 0.0 }, 1, true)),
+        Operator::make_bin("||", BinOp::new(f64::max, 0, true)),
+        Operator::make_bin("in", BinOp::new(|a, b| a + b, 2, false)),
+        Operator::make_bin("oneof", BinOp::new(|a, b| a + b, 2, false))
+            .with_tuple_expansion("==", "||")
+    );
+    assert!(FlatEx::<f64, TupleOpsFactory>::from_str("x in (1, 2)").is_err());
+    let expr = FlatEx::<f64, TupleOpsFactory>::from_str("x oneof (1, 2)")?;
+    utils::assert_float_eq_f64(expr.eval(&[2.0])?, 1.0);
+    utils::assert_float_eq_f64(expr.eval(&[3.0])?, 0.0);
+
+    // brackets index only for index operators
+    ops_factory!(
+        IndexOpsFactory,
+        f64,
+        Operator::make_bin("+", BinOp::new(|a, b| a + b, 0, true)),
+        Operator::make_bin_index("at", BinOp::new(|a, b| 10.0 * a + b, 10, false))
+    );
+    let expr = FlatEx::<f64, IndexOpsFactory>::from_str("x[2] + x at 3")?;
+    utils::assert_float_eq_f64(expr.eval(&[1.0])?, 25.0);
+    ops_factory!(
+        BracketsOpsFactory,
+        f64,
+        Operator::make_bin("[]", BinOp::new(|a, b| 10.0 * a + b, 10, false))
+    );
+    assert!(FlatEx::<f64, BracketsOpsFactory>::from_str("x[2]").is_err());
+    Ok(())
+}
+
+#[test]
+fn test_associativity() -> ExResult<()> {
+    fn test<OF: MakeOperators<f64>>(sut: &str, vars: &[f64], reference: f64) -> ExResult<()> {
+        println!(" === testing {}", sut);
+        let expr = FlatEx::<f64, OF>::from_str(sut)?;
+        utils::assert_float_eq_f64(expr.eval(vars)?, reference);
+        let expr = FlatEx::<f64, OF>::from_str_wo_compile(sut)?;
+        utils::assert_float_eq_f64(expr.eval(vars)?, reference);
+        Ok(())
+    }
+    type Math = MathConvention<FloatOpsFactory<f64>>;
+    test::<FloatOpsFactory<f64>>("2^3^2", &[], 512.0)?;
+    test::<FloatOpsFactory<f64>>("(2^3)^2", &[], 64.0)?;
+    test::<FloatOpsFactory<f64>>("2^3^2*2-8/4/2", &[], 1023.0)?;
+    test::<FloatOpsFactory<f64>>("x^y^z", &[2.0, 3.0, 2.0], 512.0)?;
+    test::<FloatOpsFactory<f64>>("x^3^y/2", &[2.0, 2.0], 256.0)?;
+    test::<FloatOpsFactory<f64>>("-(x^3^2)", &[2.0], -512.0)?;
+    test::<FloatOpsFactory<f64>>("-x^2", &[3.0], 9.0)?;
+    test::<FloatOpsFactory<f64>>("2^-1^2", &[], 2.0)?;
+    test::<Math>("-x^2", &[3.0], -9.0)?;
+    test::<Math>("-2^2 + 2^3^2", &[], 508.0)?;
+    test::<Math>("(-2)^2", &[], 4.0)?;
+    test::<Math>("-(2)^2", &[], -4.0)?;
+    test::<Math>("1 - -2^2", &[], 5.0)?;
+    test::<Math>("- -2^2", &[], 4.0)?;
+    test::<Math>("2^-x^2", &[1.0], 0.5)?;
+    test::<Math>("-2*3+1", &[], -5.0)?;
+    test::<Math>("sin(-x^2)+-x", &[0.0], 0.0)?;
+    test::<Math>("-sin(x)^2", &[1.0], -(1f64.sin().powi(2)))?;
+    assert!(FlatEx::<f64, Math>::from_str("2-").is_err());
+    Ok(())
+}
+
+#[test]
+fn test_implicit_mul() -> ExResult<()> {
+    fn test(sut: &str, vars: &[f64], reference: f64) -> ExResult<()> {
+        println!(" === testing {}", sut);
+        let expr = FlatEx::<f64>::from_str_with_implicit_mul(sut)?;
+        utils::assert_float_eq_f64(expr.eval(vars)?, reference);
+        Ok(())
+    }
+    test("2x", &[3.0], 6.0)?;
+    test("2 3", &[], 6.0)?;
+    test("3(x+1)", &[1.0], 6.0)?;
+    test("(a+b)(a-b)", &[3.0, 2.0], 5.0)?;
+    test("2sin(x)", &[0.5], 2.0 * 0.5f64.sin())?;
+    test("x sin(x)cos(x)", &[0.5], 0.5 * 0.5f64.sin() * 0.5f64.cos())?;
+    test("2x^2 - x", &[3.0], 15.0)?;
+    test("-2x", &[3.0], -6.0)?;
+    test("1/2x", &[4.0], 2.0)?;
+    test("x y", &[2.0, 3.0], 6.0)?;
+    test("xy", &[2.0], 2.0)?;
+    test("2x3", &[2.0], 4.0)?;
+    test("2{x y}", &[2.0], 4.0)?;
+    test("x (y+1)", &[2.0, 3.0], 8.0)?;
+    test("f(x) = x + 1; 2f(x)(x)", &[2.0], 12.0)?;
+    test("x-y", &[2.0, 3.0], -1.0)?;
+    test("2PI", &[], 2.0 * std::f64::consts::PI)?;
+    assert!(FlatEx::<f64>::from_str_with_implicit_mul("x(y+1)").is_err());
+    assert!(FlatEx::<f64>::from_str("2x").is_err());
+    Ok(())
+}
+
+#[test]
+fn test_parse_options() -> ExResult<()> {
+    fn parse(text: &str, options: &ParseOptions) -> ExResult<FlatEx<f64>> {
+        FlatEx::<f64>::from_str_with_options(text, options)
+    }
+    let default = ParseOptions::default();
+    let expr = parse("sin(x) + 2", &default)?;
+    assert_eq!(expr, FlatEx::<f64>::from_str("sin(x) + 2")?);
+    utils::assert_float_eq_f64(expr.eval(&[0.0])?, 2.0);
+
+    let wo_compile = ParseOptions {
+        compile: false,
+        ..ParseOptions::default()
+    };
+    let expr = parse("2 * 3 + x", &wo_compile)?;
+    assert_eq!(expr, FlatEx::<f64>::from_str_wo_compile("2 * 3 + x")?);
+    utils::assert_float_eq_f64(expr.eval(&[1.0])?, 7.0);
+
+    assert!(parse("x\t+ 1", &default).is_err());
+    let any_whitespace = ParseOptions {
+        whitespace: Whitespace::Any,
+        ..ParseOptions::default()
+    };
+    let expr = parse("x\t+\n1 + {a b}", &any_whitespace)?;
+    assert_eq!(expr.var_names(), ["a b", "x"]);
+    utils::assert_float_eq_f64(expr.eval(&[1.0, 2.0])?, 4.0);
+    let no_whitespace = ParseOptions {
+        whitespace: Whitespace::Rejected,
+        ..ParseOptions::default()
+    };
+    assert!(parse("x+{a b}", &no_whitespace).is_ok());
+    assert!(parse("x + 1", &no_whitespace).is_err());
+
+    let case_insensitive = ParseOptions {
+        case_insensitive_ops: true,
+        ..ParseOptions::default()
+    };
+    let expr = parse("SIN(x) + Cos(x) * PI", &case_insensitive)?;
+    utils::assert_float_eq_f64(expr.eval(&[0.0])?, std::f64::consts::PI);
+    assert!(parse("SIN(x)", &default).is_err());
+
+    let limited = ParseOptions {
+        max_len: Some(10),
+        max_depth: Some(2),
+        ..ParseOptions::default()
+    };
+    assert!(parse("((x))", &limited).is_ok());
+    assert!(parse("(((x)))", &limited).is_err());
+    assert!(parse("x + y + z + w", &limited).is_err());
+    let single = ParseOptions {
+        allow_statements: false,
+        ..ParseOptions::default()
+    };
+    assert!(parse("a = x; a", &single).is_err());
+    assert!(parse("f(a) = a; f(x)", &single).is_err());
+    assert!(parse("(x)", &single).is_ok());
+    let few_tokens = ParseOptions {
+        max_expanded_tokens: 20,
+        ..ParseOptions::default()
+    };
+    assert!(parse("a = x + x; a * a", &few_tokens).is_ok());
+    assert!(parse("a = x + x; b = a * a; b * b", &few_tokens).is_err());
+    assert!(parse("f(a) = a * a; f(f(f(x)))", &few_tokens).is_err());
+
+    let allowed = ParseOptions {
+        allowed_vars: Some(&["temp", "x"]),
+        ..ParseOptions::default()
+    };
+    assert_eq!(parse("temp * x", &allowed)?.var_names(), ["temp", "x"]);
+    assert!(parse("tmep * x", &allowed).is_err());
+    let no_vars = ParseOptions {
+        unknown_as_vars: false,
+        ..ParseOptions::default()
+    };
+    utils::assert_float_eq_f64(parse("a = 2; a * 3", &no_vars)?.eval(&[])?, 6.0);
+    assert!(parse("a * 3", &no_vars).is_err());
+
+    let localized = ParseOptions {
+        implicit_mul: true,
+        decimal_separator: exmex::DecimalSeparator::Comma,
+        ..ParseOptions::default()
+    };
+    utils::assert_float_eq_f64(parse("2,5x", &localized)?.eval(&[2.0])?, 5.0);
+    Ok(())
+}
+
+#[test]
+fn test_allowed_idents() -> ExResult<()> {
+    let strict = ParseOptions {
+        allowed_vars: Some(&["temp", "x"]),
+        allowed_ops: Some(&["sin", "+", "-", "*", "/"]),
+        ..ParseOptions::default()
+    };
+    let parse = |text| FlatEx::<f64>::from_str_with_options(text, &strict);
+    let expr = parse("sin(x) * temp - x / 2")?;
+    utils::assert_float_eq_f64(expr.eval(&[2.0, 0.0])?, -0.0);
+    let msg = |text| parse(text).unwrap_err().msg().to_string();
+    assert_eq!(msg("sinn(x)"), "unknown function 'sinn', did you mean 'sin'?");
+    assert_eq!(msg("tmep * x"), "unknown identifier 'tmep', did you mean 'temp'?");
+    assert_eq!(msg("y * x"), "unknown identifier 'y'");
+    assert_eq!(msg("cos(x)"), "unknown function 'cos'");
+    assert!(parse("x^2").is_err());
+    assert!(parse("x + PI").is_err());
+    // functions and local variables are no variables
+    let expr = parse("f(a) = a * 2; b = sin(x); f(temp) + b")?;
+    assert_eq!(expr.var_names(), ["temp", "x"]);
+    assert_eq!(
+        parse("f(a) = a * 2; f(tmep)").unwrap_err().msg(),
+        "unknown identifier 'tmep', did you mean 'temp'?"
+    );
+    assert_eq!(
+        parse("fun(a) = a * 2; funn(x)").unwrap_err().msg(),
+        "unknown function 'funn', did you mean 'fun'?"
+    );
+
+    let unknown_op = ParseOptions {
+        allowed_ops: Some(&["sin", "sqr"]),
+        ..ParseOptions::default()
+    };
+    let err = FlatEx::<f64>::from_str_with_options("sin(x)", &unknown_op).unwrap_err();
+    assert_eq!(
+        err.msg(),
+        "operator 'sqr' is not provided by the operator factory, did you mean 'sqrt'?"
+    );
+
+    // without allowed variables, typos are reported for calls only
+    let err = FlatEx::<f64>::from_str("sinn(x) + tmep").unwrap_err();
+    assert_eq!(err.msg(), "unknown function 'sinn', did you mean 'sin'?");
     Ok(())
 }
 
@@ -446,6 +835,10 @@ fn test_eval_str() -> ExResult<()> {
         Ok(())
     }
     test("0/0", f64::NAN)?;
+    test("1e-3*2", 0.002)?;
+    test("6.022E23/1e23", 6.022)?;
+    test("2.5e+2-.5e1", 245.0)?;
+    test("2e0*E", 2.0 * std::f64::consts::E)?;
     test("abs(  -22/2)", 11.0)?;
     test("signum(-22/2)", -1.0)?;
     test("cbrt(8)", 2.0)?;
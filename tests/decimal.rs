@@ -0,0 +1,67 @@
+#[cfg(feature = "decimal")]
+use {
+    exmex::{CheckedDecimal, Decimal, ExResult, Express, FlatExDecimal},
+    std::str::FromStr,
+};
+
+#[cfg(feature = "decimal")]
+#[test]
+fn test_decimal() -> ExResult<()> {
+    fn test(s: &str, vars: &[&str], reference: &str) -> ExResult<()> {
+        println!("testing {}", s);
+        let expr = FlatExDecimal::from_str(s)?;
+        let vars = vars
+            .iter()
+            .map(|v| CheckedDecimal::from_str(v).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            expr.eval(&vars)?.to_result()?,
+            Decimal::from_str(reference).unwrap()
+        );
+        Ok(())
+    }
+    test("0.1 + 0.2", &[], "0.3")?;
+    test("0.1 + 0.2 - 0.3", &[], "0")?;
+    test("x * 3", &["0.1"], "0.3")?;
+    test("1 / 4 - x", &["0.05"], "0.2")?;
+    test("-2.5 % 2", &[], "-0.5")?;
+    test("2^10", &[], "1024")?;
+    test(
+        "abs(-1.25) + floor(2.7) + ceil(0.1) + trunc(-1.9)",
+        &[],
+        "3.25",
+    )?;
+    test("round(2.5) + fract(3.75) + signum(-4)", &[], "1.75")?;
+    test("sqrt(2.25)", &[], "1.5")?;
+    let res = FlatExDecimal::from_str("sin(PI/2) + ln(e)")?
+        .eval(&[])?
+        .to_result()?;
+    assert!((res - Decimal::from(2)).abs() < Decimal::from_str("1e-20").unwrap());
+
+    for s in ["asin(0.5)", "log2(x)", "cbrt(8) + 1"] {
+        let err = FlatExDecimal::from_str(s).unwrap_err();
+        assert!(err.msg().starts_with("unknown function"), "{}", err.msg());
+    }
+
+    fn test_error(s: &str, msg: &str) -> ExResult<()> {
+        println!("testing {}", s);
+        let err = FlatExDecimal::from_str(s)?
+            .eval(&[])?
+            .to_result()
+            .unwrap_err();
+        assert!(err.msg().contains(msg), "{}", err.msg());
+        Ok(())
+    }
+    test_error("1/0", "division by zero")?;
+    test_error("1%0", "division by zero")?;
+    test_error("79228162514264337593543950335 + 1", "overflow")?;
+    test_error("-79228162514264337593543950335 - 1", "overflow")?;
+    test_error("79228162514264337593543950335 * 2", "overflow")?;
+    test_error("10^30", "overflow")?;
+    test_error("sqrt(-1)", "sqrt")?;
+    test_error("ln(0)", "ln")?;
+    test_error("ln(-1)", "ln")?;
+    test_error("exp(100)", "exp")?;
+    test_error("abs(1/0) + 1", "division by zero")?;
+    Ok(())
+}
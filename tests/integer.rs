@@ -0,0 +1,87 @@
+use exmex::{CheckedInt, Euclidean, ExResult, Express, FlatExInt, Floored, IntType};
+use std::{fmt::Debug, str::FromStr};
+
+fn eval<I: IntType, D: exmex::DivisionMode>(s: &str, vars: &[I]) -> ExResult<ExResult<I>>
+where
+    FlatExInt<I, D>: Express<CheckedInt<I>>,
+{
+    println!("testing {}", s);
+    let expr = FlatExInt::<I, D>::from_str(s)?;
+    let vars = vars
+        .iter()
+        .map(|v| CheckedInt::Value(*v))
+        .collect::<Vec<_>>();
+    Ok(expr.eval(&vars)?.to_result())
+}
+
+fn test<I: IntType + Debug, D: exmex::DivisionMode>(
+    s: &str,
+    vars: &[I],
+    reference: I,
+) -> ExResult<()>
+where
+    FlatExInt<I, D>: Express<CheckedInt<I>>,
+{
+    assert_eq!(eval::<I, D>(s, vars)??, reference);
+    Ok(())
+}
+
+fn test_err<I: IntType, D: exmex::DivisionMode>(s: &str, vars: &[I], msg: &str) -> ExResult<()>
+where
+    FlatExInt<I, D>: Express<CheckedInt<I>>,
+{
+    let err = eval::<I, D>(s, vars)?.unwrap_err();
+    assert_eq!(err.msg(), msg);
+    Ok(())
+}
+
+#[test]
+fn test_int() -> ExResult<()> {
+    type T = exmex::Truncated;
+    test::<i64, T>("1 + 2 * 3 - 4", &[], 3)?;
+    test::<i64, T>("2^10 - x", &[24], 1000)?;
+    test::<i64, T>("-7 / 2", &[], -3)?;
+    test::<i64, T>("-7 % 2", &[], -1)?;
+    test::<i64, Floored>("-7 / 2", &[], -4)?;
+    test::<i64, Floored>("-7 % 2", &[], 1)?;
+    test::<i64, Floored>("7 % -2", &[], -1)?;
+    test::<i64, Euclidean>("7 / -2", &[], -3)?;
+    test::<i64, Euclidean>("-7 % -2", &[], 1)?;
+    test::<i64, T>("12 & 10", &[], 8)?;
+    test::<i64, T>("12 | 10", &[], 14)?;
+    test::<i64, T>("12 XOR 10", &[], 6)?;
+    test::<i64, T>("1 << 10", &[], 1024)?;
+    test::<i64, T>("-16 >> 2", &[], -4)?;
+    test::<i64, T>("abs(x) * signum(x)", &[-5], -5)?;
+    test::<i64, T>("MAX + MIN", &[], -1)?;
+    test::<u8, T>("x * 2 + 1", &[100], 201)?;
+    test::<u8, T>("MAX - x", &[5], 250)?;
+    test::<u32, Floored>("x / 4", &[7], 1)?;
+    test::<i32, T>("0 - 0", &[], 0)?;
+    test::<u16, T>("-0", &[], 0)?;
+    assert!(FlatExInt::<i64>::from_str("2.5 * x").is_err());
+    Ok(())
+}
+
+#[test]
+fn test_int_errors() -> ExResult<()> {
+    type T = exmex::Truncated;
+    test_err::<i64, T>("x + 1", &[i64::MAX], "overflow in 9223372036854775807 + 1")?;
+    test_err::<i64, T>("MIN - 1", &[], "overflow in -9223372036854775808 - 1")?;
+    test_err::<i64, T>("-x", &[i64::MIN], "overflow in -(-9223372036854775808)")?;
+    test_err::<i64, T>("abs(MIN)", &[], "overflow in abs(-9223372036854775808)")?;
+    test_err::<i64, T>("MIN / -1", &[], "overflow in -9223372036854775808 / -1")?;
+    test_err::<i64, Floored>("MIN % -1", &[], "overflow in -9223372036854775808 % -1")?;
+    test_err::<i64, T>("x / 0", &[3], "division by zero in 3 / 0")?;
+    test_err::<i64, Euclidean>("x % (1 - 1)", &[3], "division by zero in 3 % 0")?;
+    test_err::<i64, T>("2 ^ -1", &[], "overflow in 2 ^ -1")?;
+    test_err::<i64, T>("3 ^ 40", &[], "overflow in 3 ^ 40")?;
+    test_err::<i64, T>("1 << 64", &[], "overflow in 1 << 64")?;
+    test_err::<i64, T>("1 >> -1", &[], "overflow in 1 >> -1")?;
+    test_err::<u8, T>("x * 3", &[100], "overflow in 100 * 3")?;
+    test_err::<u8, T>("3 - x", &[4], "overflow in 3 - 4")?;
+    test_err::<u32, T>("-x", &[1], "overflow in -(1)")?;
+    // errors are propagated
+    test_err::<i64, T>("(x / 0) * 2 + 1", &[3], "division by zero in 3 / 0")?;
+    Ok(())
+}
@@ -0,0 +1,73 @@
+use exmex::{ExResult, Express, FlatEx, FlatExInterval, Interval};
+use rand::{thread_rng, Rng};
+use std::str::FromStr;
+
+#[test]
+fn test_interval_enclosure() -> ExResult<()> {
+    // evaluations with floats at random points of the box must be contained in the interval result
+    fn test(s: &str, intervals: &[Interval]) -> ExResult<()> {
+        println!("testing {}", s);
+        let expr = FlatExInterval::from_str(s)?;
+        let res = expr.eval(intervals)?;
+        let expr_f64 = FlatEx::<f64>::from_str(s)?;
+        let mut rng = thread_rng();
+        for _ in 0..1000 {
+            let vars = intervals
+                .iter()
+                .map(|i| rng.gen_range(i.lo..=i.hi))
+                .collect::<Vec<_>>();
+            let x = expr_f64.eval(&vars)?;
+            assert!(res.contains(x), "{} not in {} for {:?}", x, res, vars);
+        }
+        for corner in [true, false] {
+            let vars = intervals
+                .iter()
+                .map(|i| if corner { i.lo } else { i.hi })
+                .collect::<Vec<_>>();
+            let x = expr_f64.eval(&vars)?;
+            assert!(res.contains(x), "{} not in {} for {:?}", x, res, vars);
+        }
+        Ok(())
+    }
+    let i = Interval::new;
+    test("x^2 + sin(y)", &[i(-1.0, 2.0), i(0.0, 3.2)])?;
+    test("x*y - x/y", &[i(-1.5, 2.0), i(0.5, 3.0)])?;
+    test("x^3 - x^2", &[i(-2.0, 1.0)])?;
+    test("x^(-2)", &[i(-2.0, -0.5)])?;
+    test("x^y", &[i(0.5, 2.0), i(-1.5, 2.5)])?;
+    test("cos(x) * tan(y)", &[i(-7.0, 2.0), i(-1.0, 1.0)])?;
+    test("sqrt(x) + ln(x) + exp(-x)", &[i(0.1, 4.0)])?;
+    test("abs(x) + cosh(x) + tanh(x) + atan(x)", &[i(-3.0, 2.0)])?;
+    test("asin(x) + acos(x) + cbrt(x)", &[i(-0.3, 1.0)])?;
+    test("0.1 + 0.2 - x", &[i(0.3, 0.3)])?;
+    Ok(())
+}
+
+#[test]
+fn test_interval_special_cases() -> ExResult<()> {
+    let eval = |s: &str, vars: &[Interval]| FlatExInterval::from_str(s)?.eval(vars);
+    let i = Interval::new;
+    // non-monotone functions
+    let res = eval("x^2", &[i(-2.0, 1.0)])?;
+    assert_eq!(res.lo, 0.0);
+    assert!(res.hi >= 4.0 && res.hi < 4.0 + 1e-12);
+    let res = eval("sin(x)", &[i(0.0, 4.0)])?;
+    assert_eq!(res.hi, 1.0);
+    assert!(res.lo <= 4f64.sin() && res.lo > 4f64.sin() - 1e-12);
+    assert_eq!(eval("cos(x)", &[i(-10.0, 10.0)])?, i(-1.0, 1.0));
+    // division across zero
+    assert_eq!(eval("1/x", &[i(-1.0, 1.0)])?, Interval::entire());
+    assert!(eval("1/x", &[i(0.0, 0.0)])?.is_empty());
+    assert_eq!(eval("tan(x)", &[i(1.0, 2.0)])?, Interval::entire());
+    // domains
+    assert!(eval("sqrt(x)", &[i(-2.0, -1.0)])?.is_empty());
+    let res = eval("sqrt(x)", &[i(-2.0, 4.0)])?;
+    assert!(res.lo == 0.0 && res.hi >= 2.0);
+    // outward rounding
+    let res = eval("0.1 + 0.2", &[])?;
+    assert!(res.lo < 0.3 && 0.3 < res.hi);
+    let res = eval("[1, 2] * x + [0, 0.5]", &[i(-1.0, 1.0)])?;
+    assert!(res.lo <= -2.0 && res.hi >= 2.5);
+    assert_eq!(Interval::from_str("[1, 2]")?, i(1.0, 2.0));
+    Ok(())
+}
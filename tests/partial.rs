@@ -1,5 +1,5 @@
 #[cfg(feature = "partial")]
-use exmex::{parse, Differentiate, ExResult, Express, FlatEx};
+use exmex::{parse, Differentiate, ExResult, Express, FlatEx, FuncEnv};
 #[cfg(feature = "partial")]
 mod utils;
 #[cfg(feature = "partial")]
@@ -7,6 +7,8 @@ use rand::{thread_rng, Rng};
 #[cfg(feature = "partial")]
 use smallvec::{smallvec, SmallVec};
 #[cfg(feature = "partial")]
+use std::fmt::Debug;
+#[cfg(feature = "partial")]
 use std::ops::Range;
 #[cfg(feature = "partial")]
 use std::str::FromStr;
@@ -231,6 +233,23 @@ fn test_partial_finite() -> ExResult<()> {
     Ok(())
 }
 
+#[cfg(feature = "partial")]
+#[test]
+fn test_partial_generic_float() -> ExResult<()> {
+    // bounds that generic code used before differentiation was extended to complex numbers
+    fn partial_of_float<T: num::Float + FromStr + Debug>(text: &str) -> ExResult<FlatEx<T>>
+    where
+        <T as FromStr>::Err: Debug,
+    {
+        parse::<T>(text)?.partial(0)
+    }
+    let deri = partial_of_float::<f32>("x^2")?;
+    utils::assert_float_eq::<f32>(deri.eval(&[3.0])?, 6.0, 1e-6, 0.0, "");
+    let deri = partial_of_float::<f64>("x^3")?;
+    utils::assert_float_eq_f64(deri.eval(&[2.0])?, 12.0);
+    Ok(())
+}
+
 #[cfg(feature = "partial")]
 #[test]
 fn test_partial_iter() -> ExResult<()> {
@@ -285,3 +304,21 @@ fn test_log() -> ExResult<()> {
     }
     Ok(())
 }
+
+#[cfg(feature = "partial")]
+#[test]
+fn test_user_defined_functions() -> ExResult<()> {
+    let expr = parse::<f64>("f(a) = a^2 + 1; g(a, b) = f(a)*b; g(x, y)")?;
+    let dx = expr.partial(0)?;
+    let dy = expr.partial(1)?;
+    let (x, y) = (1.5, -0.7);
+    utils::assert_float_eq_f64(dx.eval(&[x, y])?, 2.0 * x * y);
+    utils::assert_float_eq_f64(dy.eval(&[x, y])?, x * x + 1.0);
+
+    let mut env = FuncEnv::new();
+    env.define("f(x) = sin(x)*x")?;
+    let expr = FlatEx::<f64>::from_str_with_env("f(z)", &env)?;
+    let dz = expr.partial(0)?;
+    utils::assert_float_eq_f64(dz.eval(&[x])?, x.cos() * x + x.sin());
+    Ok(())
+}
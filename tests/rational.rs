@@ -0,0 +1,85 @@
+use exmex::{ExResult, Express, FlatExRational, Rational};
+use num::{rational::Ratio, BigInt};
+use std::str::FromStr;
+
+#[test]
+fn test_rational() -> ExResult<()> {
+    fn test(s: &str, vars: &[&str], reference: (i64, i64)) -> ExResult<()> {
+        println!("testing {}", s);
+        let expr = FlatExRational::<i64>::from_str(s)?;
+        let vars = vars
+            .iter()
+            .map(|v| Rational::from_str(v))
+            .collect::<ExResult<Vec<_>>>()?;
+        let res = expr.eval(&vars)?.to_result()?;
+        assert_eq!(res, Ratio::new(reference.0, reference.1));
+        Ok(())
+    }
+    test("1/3 + 1/6", &[], (1, 2))?;
+    test("0.1 + 0.2", &[], (3, 10))?;
+    test("x * 3", &["1/3"], (1, 1))?;
+    test("(x - y) / 2", &["0.75", "1.25"], (-1, 4))?;
+    test("2^10", &[], (1024, 1))?;
+    test("2^-2", &[], (1, 4))?;
+    test("(4/9)^(1/2)", &[], (2, 3))?;
+    test("8^(2/3)", &[], (4, 1))?;
+    test("(-8)^(1/3)", &[], (-2, 1))?;
+    test("sqrt(9/4)", &[], (3, 2))?;
+    test("7/2 % 1", &[], (1, 2))?;
+    test("-7 % 3", &[], (-1, 1))?;
+    test("floor(-5/2) + ceil(5/2)", &[], (0, 1))?;
+    test("round(5/2) + trunc(-5/2)", &[], (1, 1))?;
+    test("fract(7/3)", &[], (1, 3))?;
+    test("abs(-2/3) * signum(-4)", &[], (-2, 3))?;
+    test("recip(x)", &["-0.4"], (-5, 2))?;
+    test("numer(6/4) + denom(6/4)", &[], (5, 1))?;
+    let res = FlatExRational::<i64>::from_str("1/3 + 1/6")?.eval(&[])?;
+    assert_eq!(res.to_string(), "1/2");
+    Ok(())
+}
+
+#[test]
+fn test_rational_errors() -> ExResult<()> {
+    fn test_err(s: &str, msg_part: &str) -> ExResult<()> {
+        println!("testing {}", s);
+        let expr = FlatExRational::<i64>::from_str(s)?;
+        let err = expr.eval(&[])?.to_result().unwrap_err();
+        assert!(err.msg().contains(msg_part), "{}", err.msg());
+        Ok(())
+    }
+    test_err("sqrt(2)", "sqrt(2) is irrational")?;
+    test_err("2^(1/2)", "irrational")?;
+    test_err("sqrt(-4)", "not a real number")?;
+    test_err("1/(1-1)", "division by zero")?;
+    test_err("5 % 0", "division by zero")?;
+    test_err("0^-1", "division by zero")?;
+    test_err("recip(0)", "division by zero")?;
+    test_err("2^100", "overflow")?;
+    test_err("9223372036854775807 + 1", "overflow")?;
+    test_err("2^(-9223372036854775807 - 1)", "overflow")?;
+    test_err("8^((-9223372036854775807 - 1)/3)", "overflow")?;
+    let res = FlatExRational::<i64>::from_str("(-1)^(-9223372036854775807 - 1)")?.eval(&[])?;
+    assert_eq!(res.to_result()?, Ratio::from_integer(1));
+    test_err("sqrt(2) + 1", "irrational")?;
+    for s in ["sin(1)", "exp(1)", "ln(x)"] {
+        assert!(FlatExRational::<i64>::from_str(s).is_err());
+    }
+    Ok(())
+}
+
+#[test]
+fn test_big_rational() -> ExResult<()> {
+    let expr = FlatExRational::<BigInt>::from_str("2^100 + 0.000000000000000000001")?;
+    let res = expr.eval(&[])?.to_result()?;
+    let reference = Ratio::from_integer(BigInt::from(2).pow(100u32))
+        + Ratio::new(BigInt::from(1), BigInt::from(10).pow(21u32));
+    assert_eq!(res, reference);
+    let expr = FlatExRational::<BigInt>::from_str("sqrt(x^2)")?;
+    let x = Rational::<BigInt>::from_str("-12345678901234567890/7")?;
+    assert_eq!(expr.eval(&[x])?.to_string(), "12345678901234567890/7");
+    assert!(FlatExRational::<BigInt>::from_str("sqrt(3)")?
+        .eval(&[])?
+        .to_result()
+        .is_err());
+    Ok(())
+}
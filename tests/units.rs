@@ -0,0 +1,130 @@
+#[cfg(feature = "units")]
+use {
+    exmex::{Dimension, DimensionError, ExResult, Express, FlatExUnits, Quantity},
+    std::str::FromStr,
+};
+
+#[cfg(feature = "units")]
+#[test]
+fn test_units() -> ExResult<()> {
+    fn test(s: &str, vars: &[&str], reference: &str) -> ExResult<()> {
+        println!("testing {}", s);
+        let expr = FlatExUnits::from_str(s)?;
+        let vars = vars
+            .iter()
+            .map(|v| Quantity::from_str(v))
+            .collect::<ExResult<Vec<_>>>()?;
+        let res = expr.eval(&vars)?;
+        let reference = Quantity::from_str(reference)?;
+        let (res_value, res_dim) = match res {
+            Quantity::Value { si_value, dim, .. } => (si_value, dim),
+            Quantity::Error(e) => panic!("{}", e),
+        };
+        match reference {
+            Quantity::Value { si_value, dim, .. } => {
+                assert!((res_value - si_value).abs() < 1e-9 * si_value.abs().max(1.0));
+                assert_eq!(res_dim, dim);
+            }
+            Quantity::Error(e) => panic!("{}", e),
+        }
+        Ok(())
+    }
+    test("3 m/s * 2 s", &[], "6 m")?;
+    test("3 km + 200 m", &[], "3200 m")?;
+    test("(2 m)^2", &[], "4 m^2")?;
+    test("2 m^2", &[], "2 m^2")?;
+    test("sqrt(16 m^2) - 1 m", &[], "3 m")?;
+    test("x / t", &["2 h", "100 km"], "13.888888888888888 m*s^-1")?;
+    test("1 N * 2 m to J", &[], "2 J")?;
+    test("m * kg * s^-2", &[], "1 N")?;
+    test("sin(PI/2) + 2", &[], "3")?;
+    test("{s} * 3 m", &["2"], "6 m")?;
+    test("to(5 min, s)", &[], "300 s")?;
+
+    let res = FlatExUnits::from_str("(3 m/s * 2 s) to(km)")?.eval(&[])?;
+    assert_eq!(res.value().unwrap(), 0.006);
+    assert_eq!(res.to_string(), "0.006 km");
+    assert_eq!(
+        FlatExUnits::from_str("2 h + 30 min")?
+            .eval(&[])?
+            .to_string(),
+        "9000 s"
+    );
+    assert_eq!(
+        FlatExUnits::from_str("2 km + 3 km")?.eval(&[])?.to_string(),
+        "5 km"
+    );
+    Ok(())
+}
+
+#[cfg(feature = "units")]
+#[test]
+fn test_units_errors() -> ExResult<()> {
+    let eval_err = |s: &str| -> ExResult<DimensionError> {
+        Ok(FlatExUnits::from_str(s)?
+            .eval(&[])?
+            .to_result()
+            .expect_err("expected dimension error"))
+    };
+    let m = Dimension([1, 0, 0, 0, 0, 0, 0]);
+    let s = Dimension([0, 0, 1, 0, 0, 0, 0]);
+    assert_eq!(
+        eval_err("3 m + 2 s")?,
+        DimensionError::Incompatible {
+            op: "+",
+            left: m,
+            right: s
+        }
+    );
+    assert_eq!(
+        eval_err("1 + (3 m - 2 s) * 5")?,
+        DimensionError::Incompatible {
+            op: "-",
+            left: m,
+            right: s
+        }
+    );
+    assert_eq!(
+        eval_err("3 m to s")?,
+        DimensionError::Incompatible {
+            op: "to",
+            left: m,
+            right: s
+        }
+    );
+    assert_eq!(
+        eval_err("sin(2 m)")?,
+        DimensionError::NotDimensionless { op: "sin", dim: m }
+    );
+    assert_eq!(
+        eval_err("2^(1 s)")?,
+        DimensionError::NotDimensionless { op: "^", dim: s }
+    );
+    assert_eq!(
+        eval_err("sqrt(2 m)")?,
+        DimensionError::NonIntegerExponent {
+            dim: m,
+            exponent: 0.5
+        }
+    );
+    let m100 = Dimension([100, 0, 0, 0, 0, 0, 0]);
+    assert_eq!(
+        eval_err("1 m^100 * 1 m^100")?,
+        DimensionError::ExponentOverflow {
+            op: "*",
+            left: m100,
+            right: m100
+        }
+    );
+    assert!(eval_err("1 m^-100 / 1 m^100")?
+        .to_string()
+        .starts_with("exponents overflow"));
+    assert!(Quantity::from_str("1 m^100*m^100").is_err());
+    let err = eval_err("3 m + 2 s")?;
+    assert_eq!(
+        err.to_string(),
+        "cannot apply '+' to quantities of dimensions m and s"
+    );
+    assert!(Quantity::from_str("3 parsec").is_err());
+    Ok(())
+}
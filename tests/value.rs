@@ -1,7 +1,7 @@
 #[cfg(feature = "value")]
 use {
     exmex::{format_exerr, ExError, ExResult, Express, FlatExVal, Val},
-    std::str::FromStr,
+    std::{slice, str::FromStr},
 };
 
 #[cfg(feature = "value")]
@@ -211,6 +211,7 @@ fn test_no_vars() -> ExResult<()> {
     test_int("1 else 2", 1)?;
     test_error("if true else 2")?;
     test_none("2 if false")?;
+    test_none("false ? 2")?;
     test_int("to_int(1)", 1)?;
     test_int("to_int(3.5)", 3)?;
     test_float("to_float(2)", 2.0)?;
@@ -231,3 +232,583 @@ fn test_no_vars() -> ExResult<()> {
 
     Ok(())
 }
+#[cfg(feature = "value")]
+#[test]
+fn test_strings() -> ExResult<()> {
+    fn test(s: &str, vars: &[Val], reference: Val) -> ExResult<()> {
+        println!("=== testing\n{}", s);
+        let res = exmex::parse_val::<i32, f64>(s)?.eval(vars)?;
+        assert_eq!(res, reference);
+        Ok(())
+    }
+    let admin = || Val::Str("admin".to_string());
+    let s = |s: &str| Val::Str(s.to_string());
+    test(r#"name == "admin""#, &[admin()], Val::Bool(true))?;
+    test(r#"name != "admin""#, &[admin()], Val::Bool(false))?;
+    test(r#""abc" < "abd""#, &[], Val::Bool(true))?;
+    test("len(label) > 3", &[admin()], Val::Bool(true))?;
+    test("len(\"äöü\")", &[], Val::Int(3))?;
+    test(r#"upper(name) + "-" + lower("X Y")"#, &[admin()], s("ADMIN-x y"))?;
+    test(r#"name contains "dm""#, &[admin()], Val::Bool(true))?;
+    test(r#"contains(name, "xyz")"#, &[admin()], Val::Bool(false))?;
+    test(r#"starts_with(name, "ad") && true"#, &[admin()], Val::Bool(true))?;
+    test("substr(name, 2)", &[admin()], s("min"))?;
+    test("substr(name, -2)", &[admin()], s("in"))?;
+    test("substr(name, 5)", &[admin()], s(""))?;
+    test(r#"len(substr("a;b(c", 1))"#, &[], Val::Int(4))?;
+    test(r#"a = "x y"; a + a"#, &[], s("x yx y"))?;
+    test(r#""1.5""#, &[], s("1.5"))?;
+    assert!(matches!(
+        exmex::parse_val::<i32, f64>("substr(name, 6)")?.eval(&[admin()])?,
+        Val::Error(_)
+    ));
+    assert!(matches!(
+        exmex::parse_val::<i32, f64>(r#""a" - "b""#)?.eval(&[])?,
+        Val::Error(_)
+    ));
+    assert!(matches!(
+        exmex::parse_val::<i32, f64>(r#"len(3)"#)?.eval(&[])?,
+        Val::Error(_)
+    ));
+    assert!(exmex::parse_val::<i32, f64>(r#"contains(name, "a", "b")"#).is_err());
+    assert!(exmex::parse_val::<i32, f64>(r#""abc"#).is_err());
+    assert_eq!(s("abc").to_str()?, "abc");
+    assert!(Val::<i32, f64>::Int(1).to_str().is_err());
+    Ok(())
+}
+#[cfg(feature = "value")]
+#[test]
+fn test_arrays() -> ExResult<()> {
+    fn test(s: &str, vars: &[Val], reference: Val) -> ExResult<()> {
+        println!("=== testing\n{}", s);
+        let res = exmex::parse_val::<i32, f64>(s)?.eval(vars)?;
+        assert_eq!(res, reference);
+        Ok(())
+    }
+    fn test_error(s: &str, vars: &[Val]) -> ExResult<()> {
+        println!("=== testing\n{}", s);
+        match exmex::parse_val::<i32, f64>(s) {
+            Ok(expr) => match expr.eval(vars)? {
+                Val::Error(e) => {
+                    println!("found expected error {:?}", e);
+                    Ok(())
+                }
+                v => Err(format_exerr!("'{}' should fail but returned {:?}", s, v)),
+            },
+            Err(e) => {
+                println!("found expected error {:?}", e);
+                Ok(())
+            }
+        }
+    }
+    let ints = |v: &[i32]| Val::Array(v.iter().map(|i| Val::Int(*i)).collect());
+    let floats = |v: &[f64]| Val::Array(v.iter().map(|f| Val::Float(*f)).collect());
+    let x = floats(&[1.5, -2.0, 4.0]);
+    let w = ints(&[2, 1, 3]);
+
+    test("[1, 2, 3]", &[], ints(&[1, 2, 3]))?;
+    test("[]", &[], Val::Array(vec![]))?;
+    test(
+        r#"[-1.5, true, "a,b"]"#,
+        &[],
+        Val::Array(vec![
+            Val::Float(-1.5),
+            Val::Bool(true),
+            Val::Str("a,b".to_string()),
+        ]),
+    )?;
+    test("[1, 2] + [3, 4]", &[], ints(&[4, 6]))?;
+    test("2 * [1, 2] - 1", &[], ints(&[1, 3]))?;
+    test("-[1, 2]^2", &[], ints(&[1, 4]))?;
+    test("[1, 2, 3] > 1", &[], Val::Array(vec![Val::Bool(false), Val::Bool(true), Val::Bool(true)]))?;
+    test("abs(x)", slice::from_ref(&x), floats(&[1.5, 2.0, 4.0]))?;
+    test("to_int(x)", slice::from_ref(&x), ints(&[1, -2, 4]))?;
+    test("sum(w * x)", &[w.clone(), x.clone()], Val::Float(13.0))?;
+    test("w dot x", &[w.clone(), x.clone()], Val::Float(13.0))?;
+    test("dot(w, x) == sum(w * x)", &[w.clone(), x.clone()], Val::Bool(true))?;
+    test("mean(w)", slice::from_ref(&w), Val::Float(2.0))?;
+    test("min(x)", slice::from_ref(&x), Val::Float(-2.0))?;
+    test("max(x)", slice::from_ref(&x), Val::Float(4.0))?;
+    test("max([1, 2.5, 2])", &[], Val::Float(2.5))?;
+    test("len(x)", slice::from_ref(&x), Val::Int(3))?;
+    test("sum([])", &[], Val::Int(0))?;
+    test("x[0]", slice::from_ref(&x), Val::Float(1.5))?;
+    test("x[-1] + w[1]", &[w.clone(), x.clone()], Val::Float(5.0))?;
+    test("x[len(w) - 2]^2", &[w.clone(), x.clone()], Val::Float(4.0))?;
+    test("[4, 5, 6][2]", &[], Val::Int(6))?;
+    test("(w * 2)[1]", slice::from_ref(&w), Val::Int(2))?;
+    test(r#""abc"[1]"#, &[], Val::Str("b".to_string()))?;
+    test("w contains 3", slice::from_ref(&w), Val::Bool(true))?;
+    test("contains(w, 4)", slice::from_ref(&w), Val::Bool(false))?;
+    test("[1, 2] == [1, 2.0]", &[], Val::Bool(true))?;
+
+    test_error("[1, 2] + [1, 2, 3]", &[])?;
+    test_error("x[3]", slice::from_ref(&x))?;
+    test_error("x[-4]", slice::from_ref(&x))?;
+    test_error("x[1.0]", slice::from_ref(&x))?;
+    test_error("sum(1)", &[])?;
+    test_error("mean([])", &[])?;
+    test_error("max([])", &[])?;
+    test_error(r#"max([1, "a"])"#, &[])?;
+    test_error("[1, 2] dot [1]", &[])?;
+    test_error("x[0", slice::from_ref(&x))?;
+    assert!(ints(&[1]).to_array().is_ok());
+    assert!(Val::<i32, f64>::Int(1).to_array().is_err());
+    Ok(())
+}
+
+#[cfg(feature = "value")]
+#[test]
+fn test_type_check() -> ExResult<()> {
+    use exmex::{check_val_types, parse_val_typed, ValType};
+    let var_types = [
+        ("i", ValType::Int),
+        ("x", ValType::Float),
+        ("b", ValType::Bool),
+        ("s", ValType::Str),
+        ("v", ValType::Array),
+    ];
+    let check = |text: &str| check_val_types::<i32, f64>(text, &var_types);
+    let test = |text: &str, reference: ValType| {
+        println!("testing {}", text);
+        assert_eq!(check(text).unwrap(), reference);
+    };
+    test("i + 2", ValType::Int);
+    test("i * x", ValType::Float);
+    test("2^i", ValType::Int);
+    test("x^i", ValType::Float);
+    test("-i % 3", ValType::Int);
+    test("i << 2 | 1", ValType::Int);
+    test("sin(x) + to_float(i)", ValType::Float);
+    test("(x > i) && !=(s, \"a\")", ValType::Bool);
+    test("s + \"!\"", ValType::Str);
+    test("upper(substr(s, 1))", ValType::Str);
+    test("len(s) + len(v)", ValType::Int);
+    test("s[0] + \"!\"", ValType::Str);
+    test("v * 2 + 1", ValType::Array);
+    test("sum(v * x)", ValType::Any);
+    test("mean(v) + 1", ValType::Float);
+    test("v[0] + x", ValType::Any);
+    test("v contains 2", ValType::Bool);
+    test("x if b else i", ValType::Any);
+    test("x if b else 2.0", ValType::Float);
+    test("(i if b else 2) if i > 0 else 3", ValType::Int);
+    test("b ? 1 : i > 0 ? 2 : 3", ValType::Int);
+    test("b ? 1 : i > 0 ? 2.0 : 3", ValType::Any);
+    test("1 if b else 2 if i > 0 else 3", ValType::Int);
+    test("fact(to_int(x))", ValType::Int);
+    test("PI * x", ValType::Float);
+    test("[1, 2.5] * x", ValType::Array);
+    test("y = x * 2; z = y > 1; 1 if z else 0", ValType::Int);
+
+    let test_err = |text: &str, msg: &str, span: std::ops::Range<usize>| {
+        println!("testing {}", text);
+        let err = check(text).unwrap_err();
+        assert_eq!(err.msg(), msg);
+        assert_eq!(err.span(), span);
+        assert_eq!(&text[err.span()], &text[span]);
+    };
+    test_err("x + b", "cannot apply '+' to Float and Bool", 0..5);
+    test_err("2 * (true + 1)", "cannot apply '+' to Bool and Int", 5..13);
+    test_err("fact(x)", "cannot apply 'fact' to Float", 0..7);
+    test_err("1 + fact 2.5", "cannot apply 'fact' to Float", 4..12);
+    test_err("sin(i)", "cannot apply 'sin' to Int", 0..6);
+    test_err("i ^ x", "cannot apply '^' to Int and Float", 0..5);
+    test_err("x % 2", "cannot apply '%' to Float and Int", 0..5);
+    test_err("(b && (i > 1)) || x", "cannot apply '||' to Bool and Float", 0..19);
+    test_err("s - \"a\"", "cannot apply '-' to Str and Str", 0..7);
+    test_err("starts_with(s, 1)", "cannot apply 'starts_with' to Str and Int", 0..17);
+    test_err("len(i)", "cannot apply 'len' to Int", 0..6);
+    test_err("x if b", "'if' without 'else'", 0..6);
+    test_err("(x if b) + 1", "'if' without 'else'", 0..8);
+    test_err("x else 1", "'else' without 'if'", 0..8);
+    test_err("x if i else 1", "condition of 'if' needs to be Bool, got Int", 5..6);
+    test_err("i + y", "type of variable 'y' is not declared", 4..5);
+    test_err("f(x)", "unknown function 'f'", 0..1);
+    test_err("a = x + 1; a + b", "cannot apply '+' to Float and Bool", 11..16);
+    test_err("a = x + b; a", "cannot apply '+' to Float and Bool", 4..9);
+    test("x > i && b", ValType::Bool);
+    test("!(x > 3) && b xor not b", ValType::Bool);
+    test("x in (1, 2.5, i)", ValType::Bool);
+    test("s in (\"a\", \"b\") || i in v", ValType::Bool);
+    test("between(x, 0, i) && x between [1, 2]", ValType::Bool);
+    test_err("!x", "cannot apply '!' to Float", 0..2);
+    test_err("1 + (x in (1, 2))", "cannot apply '+' to Int and Bool", 0..17);
+    test_err("x in (1, (2 if b))", "'if' without 'else'", 9..17);
+    test_err("between(s, 0, 1)", "cannot apply '>=' to Str and Int", 0..16);
+    test("b ? x : 2.0", ValType::Float);
+    test("if(b, i, 2) * 2", ValType::Int);
+    test_err("b ? x", "'?' without ':'", 0..5);
+    test_err("x : 1", "':' without '?'", 0..5);
+    test_err("i ? x : 1", "condition of '?' needs to be Bool, got Int", 0..1);
+    test_err("if(i, x, 1)", "condition of 'if' needs to be Bool, got Int", 3..4);
+    test_err("if(b, x, 2.0) && b", "cannot apply '&&' to Float and Bool", 0..18);
+    test_err("i && b", "cannot apply '&&' to Int and Bool", 0..6);
+
+    // type check at parse time
+    let (expr, t) = parse_val_typed::<i32, f64>("x * 2 if b else x", &var_types[1..3])?;
+    assert_eq!(t, ValType::Float);
+    assert_eq!(
+        expr.eval(&[Val::Bool(true), Val::Float(1.5)])?.to_float()?,
+        3.0
+    );
+    let err = parse_val_typed::<i32, f64>("x + b", &var_types).unwrap_err();
+    assert_eq!(err.msg(), "cannot apply '+' to Float and Bool at 0..5");
+    Ok(())
+}
+
+#[cfg(feature = "value")]
+#[test]
+fn test_type_check_agrees_with_eval() -> ExResult<()> {
+    use exmex::{check_val_types, NoneIsNull, ValType};
+    fn type_of(val: &Val) -> Option<ValType> {
+        match val {
+            Val::Int(_) => Some(ValType::Int),
+            Val::Float(_) => Some(ValType::Float),
+            Val::Bool(_) => Some(ValType::Bool),
+            Val::Str(_) => Some(ValType::Str),
+            Val::Array(_) => Some(ValType::Array),
+            _ => None,
+        }
+    }
+    let var_types = [
+        ("b", ValType::Bool),
+        ("i", ValType::Int),
+        ("s", ValType::Str),
+        ("v", ValType::Array),
+        ("x", ValType::Float),
+    ];
+    let var_sets = [
+        [
+            Val::Bool(true),
+            Val::Int(3),
+            Val::Str("abc".to_string()),
+            Val::Array(vec![Val::Int(1), Val::Int(2)]),
+            Val::Float(1.5),
+        ],
+        [
+            Val::Bool(false),
+            Val::Int(2),
+            Val::Str("xy".to_string()),
+            Val::Array(vec![Val::Float(0.5), Val::Int(-1)]),
+            Val::Float(-0.5),
+        ],
+    ];
+    let texts = [
+        "i + 2 * i - i / 2",
+        "i * x + x / 2",
+        "2^i + x^2",
+        "i % 3 << 2 | 1 & i",
+        "-i! + fact(2) - 5",
+        "x% * 2",
+        "sin(x) + to_float(i) + abs(x)",
+        "to_int(x) + to_int(b)",
+        "x > i && !b || i == 3 xor b",
+        "s + \"!\" + upper(s) + lower(substr(s, 1))",
+        "len(s) + len(v)",
+        "v * 2 + 1",
+        "sum(v) + x",
+        "mean(v) + 1",
+        "v contains 2 || s starts_with \"a\" || i in v",
+        "between(x, -1, i) && x between [0, 2]",
+        "x if b else 2.0",
+        "b ? i : i * 2",
+        "b ? 1 : i > 0 ? 2 : 3",
+        "1 if b else 2 if i > 0 else 3",
+        "if(b, x, 0.5) * 2",
+        "(i if b else 2) if i > 0 else 3",
+        "s ?? \"default\"",
+        "coalesce(x, 1.0) + 1",
+        "is_none(x) || b",
+        "[1, 2.5] * x",
+        "y = x * 2; z = y > 1; 1 if z else 0",
+        "a = i + 1; c = a * a; c - a",
+    ];
+    for text in texts {
+        println!("testing {}", text);
+        let t = check_val_types::<i32, f64>(text, &var_types)
+            .map_err(|e| format_exerr!("{}", e))?;
+        let expr = FlatExVal::<i32, f64>::from_str(text)?;
+        let expr_null = FlatExVal::<i32, f64, NoneIsNull>::from_str(text)?;
+        for vars in &var_sets {
+            let vars = expr
+                .var_names()
+                .iter()
+                .map(|name| {
+                    let idx = var_types.iter().position(|(n, _)| n == name).unwrap();
+                    vars[idx].clone()
+                })
+                .collect::<Vec<_>>();
+            for res in [expr.eval(&vars)?, expr_null.eval(&vars)?] {
+                let res_type = type_of(&res);
+                assert!(res_type.is_some(), "{:?}", res);
+                if t != ValType::Any {
+                    assert_eq!(res_type, Some(t), "{:?}", res);
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(feature = "value")]
+#[test]
+fn test_lazy() -> ExResult<()> {
+    fn test(s: &str, vars: &[Val], reference: Val) -> ExResult<()> {
+        println!("=== testing\n{}", s);
+        let res = exmex::parse_val::<i32, f64>(s)?.eval(vars)?;
+        assert_eq!(res, reference);
+        Ok(())
+    }
+    // the right operands would result in errors if they were evaluated
+    test("false && fact(2.5)", &[], Val::Bool(false))?;
+    test("(x > 1) && fact(x)", &[Val::Float(0.5)], Val::Bool(false))?;
+    test("true || (1 + \"a\")", &[], Val::Bool(true))?;
+    test("(x < 2) || fact(x)", &[Val::Float(1.5)], Val::Bool(true))?;
+    test("x || y", &[Val::Bool(false), Val::Bool(true)], Val::Bool(true))?;
+
+    test("x ? 1 : 2", &[Val::Bool(true)], Val::Int(1))?;
+    test("x ? 1 : 2", &[Val::Bool(false)], Val::Int(2))?;
+    test("x > 0 ? ln(x) : 0", &[Val::Float(1.0)], Val::Float(0.0))?;
+    test("1 + (x ? 1 : 2) * 3", &[Val::Bool(false)], Val::Int(7))?;
+    test("x ? 1 : (y ? 2 : 3)", &[Val::Bool(false), Val::Bool(true)], Val::Int(2))?;
+    test("if(x > 0, 1, 2)", &[Val::Int(-1)], Val::Int(2))?;
+    test("if(x > 0, 1, 2)", &[Val::Int(1)], Val::Int(1))?;
+    test("-if(x, if(y, 1, 2), 3)", &[Val::Bool(true), Val::Bool(false)], Val::Int(-2))?;
+    test("contains([1, 2], if(x, 1, 3))", &[Val::Bool(true)], Val::Bool(true))?;
+    test("x if y else z", &[Val::Int(1), Val::Bool(false), Val::Int(3)], Val::Int(3))?;
+    test("true ? 1 : false ? 2 : 3", &[], Val::Int(1))?;
+    test("false ? 1 : true ? 2 : 3", &[], Val::Int(2))?;
+    test("false ? 1 : false ? 2 : 3", &[], Val::Int(3))?;
+    test("1 if true else 2 if false else 3", &[], Val::Int(1))?;
+    test("1 if false else 2 if false else 3", &[], Val::Int(3))?;
+    test("x || y ? 1 : 2", &[Val::Bool(false), Val::Bool(true)], Val::Int(1))?;
+    test("x ? y ?? 1 : 2", &[Val::Bool(true), Val::None], Val::Int(1))?;
+    let res = exmex::parse_val::<i32, f64>("c ? x : 5")?.eval(&[Val::Bool(true), Val::None])?;
+    assert!(matches!(res, Val::None));
+    let res = exmex::parse_val::<i32, f64>("x if c else 5")?.eval(&[Val::Bool(true), Val::None])?;
+    assert!(matches!(res, Val::None));
+    test("(2 if false) else 3", &[], Val::Int(3))?;
+    test("(2 if true) else 3", &[], Val::Int(2))?;
+    let vars = [Val::Bool(false), Val::Bool(true), Val::Int(1)];
+    test("(x if a) if b else 3", &vars, Val::Int(3))?;
+    test("(x if b) if a else 3", &vars, Val::Int(3))?;
+    test("(x if b) if b else a", &vars, Val::Int(1))?;
+    let res = exmex::parse_val::<i32, f64>("x if 1 else 2")?.eval(&[Val::Int(1)])?;
+    assert!(matches!(res, Val::Error(_)));
+    assert!(exmex::parse_val::<i32, f64>("if(x, 1, 2, 3)").is_err());
+
+    // long chains are evaluated without recursion
+    let n_terms = 100_000;
+    let chain = vec!["x"; n_terms].join(" && ");
+    let expr = exmex::parse_val::<i32, f64>(&chain)?;
+    assert_eq!(expr.eval(&[Val::Bool(true)])?, Val::Bool(true));
+    assert_eq!(expr.eval(&[Val::Bool(false)])?, Val::Bool(false));
+    let chain = vec!["x"; n_terms].join(" || ");
+    let expr = exmex::parse_val::<i32, f64>(&chain)?;
+    assert_eq!(expr.eval(&[Val::Bool(false)])?, Val::Bool(false));
+
+    // sets with short-circuit expressions agree with the separately evaluated expressions
+    use exmex::{ExprSet, ValMatcher, ValOpsFactory};
+    let set = ExprSet::<Val, ValOpsFactory<i32, f64>, ValMatcher>::from_strs(&[
+        "x * 2",
+        "x > 1 ? fact(x) : 0",
+        "b && fact(x) > 1",
+        "x * 2 + 1",
+    ])?;
+    assert_eq!(set.var_names(), ["b", "x"]);
+    let res = set.eval(&[Val::Bool(false), Val::Float(0.5)])?;
+    assert_eq!(
+        res,
+        [Val::Float(1.0), Val::Int(0), Val::Bool(false), Val::Float(2.0)]
+    );
+    Ok(())
+}
+
+#[cfg(feature = "value")]
+#[test]
+fn test_logic() -> ExResult<()> {
+    fn test(s: &str, vars: &[Val], reference: bool) -> ExResult<()> {
+        println!("=== testing\n{}", s);
+        let res = exmex::parse_val::<i32, f64>(s)?.eval(vars)?;
+        assert_eq!(res.to_bool()?, reference);
+        Ok(())
+    }
+    test("!(a > 3) && b", &[Val::Int(2), Val::Bool(true)], true)?;
+    test("!(a > 3) && b", &[Val::Int(4), Val::Bool(true)], false)?;
+    test("not a > 3 || b", &[Val::Bool(true), Val::Bool(false)], false)?;
+    test("!true == false", &[], true)?;
+    test("a > 1 && a < 3", &[Val::Float(2.5)], true)?;
+    test("a > 1 || a < -1 && a > -5", &[Val::Int(-7)], false)?;
+    test("true xor false", &[], true)?;
+    test("a xor b || true xor true", &[Val::Bool(true), Val::Bool(true)], false)?;
+    test("a in (1, 2, 5)", &[Val::Int(2)], true)?;
+    test("a in (1, 2, 5)", &[Val::Float(5.0)], true)?;
+    test("a in (1, 2, 5)", &[Val::Int(3)], false)?;
+    test("a + 1 in (1, 2 * 2) && true", &[Val::Int(3)], true)?;
+    test("!(a in (b, 2))", &[Val::Int(7), Val::Int(7)], false)?;
+    test("contains(\"xyz\", \"y\" in (\"a\", \"y\") ? \"x\" : \"a\")", &[], true)?;
+    test("index in [1, 2, 5]", &[Val::Int(3)], false)?;
+    test("\"dm\" in name", &[Val::Str("admin".to_string())], true)?;
+    test("between(a, 1, 3)", &[Val::Float(1.0)], true)?;
+    test("between(a, 1, 3)", &[Val::Int(4)], false)?;
+    test("between(a * 2, 1, 3) || b", &[Val::Int(1), Val::Bool(false)], true)?;
+    test("a between [\"a\", \"c\"]", &[Val::Str("b".to_string())], true)?;
+    assert!(exmex::parse_val::<i32, f64>("between(a, 1, 2, 3)").is_err());
+    let res = exmex::parse_val::<i32, f64>("between(a, 1)")?.eval(&[Val::Int(1)])?;
+    assert!(matches!(res, Val::Error(_)));
+    let res = exmex::parse_val::<i32, f64>("!2")?.eval(&[])?;
+    assert!(matches!(res, Val::Error(_)));
+    let res = exmex::parse_val::<i32, f64>("true xor 1")?.eval(&[])?;
+    assert!(matches!(res, Val::Error(_)));
+    let res = exmex::parse_val::<i32, f64>("!a")?.eval(&[Val::Array(vec![Val::Bool(true)])])?;
+    assert_eq!(res.to_array()?, vec![Val::Bool(false)]);
+    Ok(())
+}
+#[cfg(feature = "value")]
+#[test]
+fn test_none() -> ExResult<()> {
+    use exmex::NoneIsNull;
+    fn eval(s: &str, vars: &[Val]) -> ExResult<Val> {
+        println!("=== testing\n{}", s);
+        FlatExVal::<i32, f64, NoneIsNull>::from_str(s)?.eval(vars)
+    }
+    let none = || Val::None;
+    // operators that replace or detect missing values work in both modes
+    let expr = exmex::parse_val::<i32, f64>("a ?? 2 * 3")?;
+    assert_eq!(expr.eval(&[none()])?.to_int()?, 6);
+    assert_eq!(expr.eval(&[Val::Int(1)])?.to_int()?, 1);
+    let expr = exmex::parse_val::<i32, f64>("coalesce(a, b) + 1")?;
+    assert_eq!(expr.eval(&[none(), Val::Float(0.5)])?.to_float()?, 1.5);
+    let expr = exmex::parse_val::<i32, f64>("is_none(a) || a > 1")?;
+    assert!(expr.eval(&[none()])?.to_bool()?);
+    assert!(!expr.eval(&[Val::Int(0)])?.to_bool()?);
+    let expr = exmex::parse_val::<i32, f64>("b ? a ?? 0 : 1")?;
+    assert_eq!(expr.eval(&[none(), Val::Bool(true)])?.to_int()?, 0);
+    assert_eq!(expr.eval(&[none(), Val::Bool(false)])?.to_int()?, 1);
+    // by default, missing values lead to errors
+    let res = exmex::parse_val::<i32, f64>("a + 1")?.eval(&[none()])?;
+    assert!(matches!(res, Val::Error(_)));
+    let res = exmex::parse_val::<i32, f64>("a == a")?.eval(&[none()])?;
+    assert!(!res.to_bool()?);
+
+    // three-valued semantics
+    assert!(matches!(eval("a + 1", &[none()])?, Val::None));
+    assert!(matches!(eval("-sin(a) * 2", &[none()])?, Val::None));
+    assert!(matches!(eval("a == a", &[none()])?, Val::None));
+    assert!(matches!(eval("a < 1 || b", &[none(), Val::Bool(false)])?, Val::None));
+    assert!(eval("a < 1 || b", &[none(), Val::Bool(true)])?.to_bool()?);
+    assert!(!eval("a && b", &[none(), Val::Bool(false)])?.to_bool()?);
+    assert!(!eval("b && a", &[none(), Val::Bool(false)])?.to_bool()?);
+    assert!(matches!(eval("!a", &[none()])?, Val::None));
+    assert!(matches!(eval("a in (1, 2)", &[none()])?, Val::None));
+    assert_eq!(eval("1 if a > 0 else 2", &[none()])?.to_int()?, 2);
+    assert_eq!(eval("a > 0 ? 1 : 2", &[none()])?.to_int()?, 2);
+    assert_eq!(eval("(a * 2) ?? -1", &[none()])?.to_int()?, -1);
+    assert_eq!(eval("(a * 2) ?? -1", &[Val::Int(3)])?.to_int()?, 6);
+    assert!(eval("is_none(a / 2)", &[none()])?.to_bool()?);
+    let res = eval("a + [1, 2]", &[Val::Array(vec![none(), Val::Int(1)])])?.to_array()?;
+    assert!(matches!(res[0], Val::None));
+    assert_eq!(res[1].clone().to_int()?, 3);
+    let res = eval("a + \"x\"", &[Val::Int(1)])?;
+    assert!(matches!(res, Val::Error(_)));
+
+    use exmex::{check_val_types, ValType};
+    let var_types = [("i", ValType::Int), ("x", ValType::Float)];
+    let check = |text: &str| check_val_types::<i32, f64>(text, &var_types).unwrap();
+    assert_eq!(check("i ?? 0"), ValType::Int);
+    assert_eq!(check("coalesce(i, x)"), ValType::Any);
+    assert_eq!(check("is_none(x)"), ValType::Bool);
+    Ok(())
+}
+#[cfg(feature = "value")]
+#[test]
+fn test_postfix() -> ExResult<()> {
+    fn eval(s: &str, vars: &[Val]) -> ExResult<Val> {
+        println!("=== testing\n{}", s);
+        exmex::parse_val::<i32, f64>(s)?.eval(vars)
+    }
+    assert_eq!(eval("5!", &[])?.to_int()?, 120);
+    assert_eq!(eval("3!!", &[])?.to_int()?, 720);
+    assert_eq!(eval("-3!", &[])?.to_int()?, -6);
+    assert_eq!(eval("2^3!", &[])?.to_int()?, 64);
+    assert_eq!(eval("2*3!+1", &[])?.to_int()?, 13);
+    assert_eq!(eval("(1+2)!", &[])?.to_int()?, 6);
+    assert_eq!(eval("x!", &[Val::Int(4)])?.to_int()?, 24);
+    assert_eq!(eval("len(\"abc\")!", &[])?.to_int()?, 6);
+    assert_eq!(eval("v[1]!", &[Val::Array(vec![Val::Int(1), Val::Int(3)])])?.to_int()?, 6);
+    assert!(eval("!a", &[Val::Bool(false)])?.to_bool()?);
+    assert!(!eval("!(3! == 6)", &[])?.to_bool()?);
+    assert_eq!(eval("50%", &[])?.to_float()?, 0.5);
+    assert_eq!(eval("50% * 2", &[])?.to_float()?, 1.0);
+    assert_eq!(eval("x% + 1", &[Val::Float(20.0)])?.to_float()?, 1.2);
+    assert_eq!(eval("7 % 2", &[])?.to_int()?, 1);
+    assert_eq!(eval("x % -4", &[Val::Int(7)])?.to_int()?, 3);
+    assert_eq!(eval("x % (5)", &[Val::Int(7)])?.to_int()?, 2);
+    assert!(matches!(eval("2.5!", &[])?, Val::Error(_)));
+    assert_eq!(eval("12!", &[])?.to_int()?, 479001600);
+    assert!(matches!(eval("13!", &[])?, Val::Error(_)));
+    assert!(matches!(eval("5!!", &[])?, Val::Error(_)));
+    assert!(matches!(eval("fact(x)", &[Val::Int(i32::MAX)])?, Val::Error(_)));
+    assert!(matches!(eval("(-3)!", &[])?, Val::Error(_)));
+    assert!(exmex::parse_val::<i32, f64>("5 + !").is_err());
+
+    use exmex::{check_val_types, ValType};
+    let var_types = [("i", ValType::Int), ("x", ValType::Float)];
+    let check = |text: &str| check_val_types::<i32, f64>(text, &var_types);
+    assert_eq!(check("i! + 1").unwrap(), ValType::Int);
+    assert_eq!(check("-i!").unwrap(), ValType::Int);
+    assert_eq!(check("x% * 2").unwrap(), ValType::Float);
+    assert_eq!(check("i % 2").unwrap(), ValType::Int);
+    assert_eq!(check("[1, 2][0]!").unwrap(), ValType::Int);
+    let err = check("1 + x!").unwrap_err();
+    assert_eq!(err.msg(), "cannot apply postfix '!' to Float");
+    assert_eq!(err.span(), 4..6);
+    Ok(())
+}
+#[cfg(feature = "value")]
+#[test]
+fn test_literals() -> ExResult<()> {
+    fn eval(s: &str) -> ExResult<Val> {
+        println!("=== testing\n{}", s);
+        exmex::parse_val::<i32, f64>(s)?.eval(&[])
+    }
+    assert_eq!(eval("0xFF")?.to_int()?, 255);
+    assert_eq!(eval("0Xff + 0o17")?.to_int()?, 270);
+    assert_eq!(eval("0b1010 | 0b0101")?.to_int()?, 15);
+    assert_eq!(eval("1_000_000 - 1")?.to_int()?, 999_999);
+    assert_eq!(eval("0b1111_0000")?.to_int()?, 240);
+    assert_eq!(eval("-0x10")?.to_int()?, -16);
+    assert_eq!(eval("1e-3")?.to_float()?, 0.001);
+    assert_eq!(eval("6.022E23")?.to_float()?, 6.022e23);
+    assert_eq!(eval("1_000.5")?.to_float()?, 1000.5);
+    assert!(eval("2e3 == 2000")?.to_bool()?);
+    let arr = eval("[0x1, -0b10, 1e1, 1_0]")?.to_array()?;
+    assert_eq!(arr[0].clone().to_int()?, 1);
+    assert_eq!(arr[1].clone().to_int()?, -2);
+    assert_eq!(arr[2].clone().to_float()?, 10.0);
+    assert_eq!(arr[3].clone().to_int()?, 10);
+    assert!(exmex::parse_val::<i32, f64>("0xFFFFFFFFF").is_err());
+    assert!(exmex::parse_val::<i32, f64>("1__0").is_err());
+    let expr = exmex::parse_val::<i32, f64>("x_1 * 2")?;
+    assert_eq!(expr.var_names(), ["x_1"]);
+    Ok(())
+}
+#[cfg(feature = "value")]
+#[test]
+fn test_localized() -> ExResult<()> {
+    use exmex::DecimalSeparator;
+    let comma = DecimalSeparator::Comma;
+    let text = r#"sum([1,5; 2] * x) if s == "a,b" else -0,5"#;
+    let expr = FlatExVal::<i32, f64>::from_str_localized(text, comma)?;
+    assert_eq!(expr.unparse(), r#"sum([1.5, 2] * x) if s == "a,b" else -0.5"#);
+    assert_eq!(expr.unparse_localized(comma), text);
+    let res = expr.eval(&[Val::Str("a,b".to_string()), Val::Int(2)])?;
+    assert_eq!(res.to_float()?, 7.0);
+    let res = expr.eval(&[Val::Str("c".to_string()), Val::Int(2)])?;
+    assert_eq!(res.to_float()?, -0.5);
+    let expr = FlatExVal::<i32, f64>::from_str_localized("x in (1; 2,5)", comma)?;
+    assert!(expr.eval(&[Val::Float(2.5)])?.to_bool()?);
+    assert!(FlatExVal::<i32, f64>::from_str_localized("x in (1, 2)", comma).is_err());
+    Ok(())
+}